@@ -0,0 +1,66 @@
+// `std::hint::black_box` requires Rust 1.66, benchmarks are not bound to the MSRV
+#![allow(clippy::incompatible_msrv)]
+
+use std::time::{Duration, Instant};
+
+use custos::{
+    eval_in_lanes, ApplyFunction, Buffer, Combiner, Dim1, Eval, MayToCLSource, Resolve, Stack, CPU,
+};
+
+const SIZE: usize = 1 << 16;
+const RUNS: u32 = 1000;
+
+fn bench(name: &str, mut f: impl FnMut()) -> Duration {
+    // warm up
+    f();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    let avg = start.elapsed() / RUNS;
+    println!("{name:<28} {avg:?}");
+    avg
+}
+
+fn op(x: Resolve<f32>) -> impl Eval<f32> + MayToCLSource {
+    x.geq(0.5).mul(x).add(x.mul(x).mul(3.).div(x.add(2.)))
+}
+
+fn main() {
+    let data = (0..SIZE)
+        .map(|x| x as f32 / SIZE as f32)
+        .collect::<Vec<_>>();
+    let mut out = vec![0.; SIZE];
+
+    // evaluation only, without allocating the output
+    let scalar = bench("element-wise eval", || {
+        for (value, x) in out.iter_mut().zip(&data) {
+            *value = op(Resolve::with_val(*x)).eval();
+        }
+        std::hint::black_box(&mut out);
+    });
+    let lanes = bench("lane eval", || {
+        eval_in_lanes(&data, op, |idx, values| {
+            out[idx..idx + values.len()].copy_from_slice(values)
+        });
+        std::hint::black_box(&mut out);
+    });
+    println!(
+        "speedup: {:.2}x\n",
+        scalar.as_secs_f64() / lanes.as_secs_f64()
+    );
+
+    let device = CPU::new();
+    let buf = Buffer::<_, _>::from((&device, &data));
+    bench("cpu apply_fn", || {
+        let out = device.apply_fn(&buf, op);
+        std::hint::black_box(out.as_slice());
+    });
+
+    let buf = Buffer::<f32, Stack, Dim1<SIZE>>::from((&Stack, &data));
+    bench("stack apply_fn", || {
+        let out = Stack.apply_fn(&buf, op);
+        std::hint::black_box(out.as_slice());
+    });
+}
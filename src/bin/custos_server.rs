@@ -0,0 +1,27 @@
+//! Hosts the local devices for [`Network`](custos::Network) clients.
+//!
+//! Usage: `custos-server [address] [max buffer size in bytes]` (default: `127.0.0.1:11001`, 1 GiB)
+
+use std::net::TcpListener;
+
+use custos::network::ServerConfig;
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:11001".to_string());
+
+    let mut config = ServerConfig::default();
+    if let Some(max_alloc_size) = args.next() {
+        config.max_alloc_size = max_alloc_size.parse().map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("`{max_alloc_size}` is not a valid buffer size."),
+            )
+        })?;
+    }
+
+    let listener = TcpListener::bind(&addr)?;
+    println!("custos-server listening on {}", listener.local_addr()?);
+
+    custos::network::serve_with(listener, config)
+}
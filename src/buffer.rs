@@ -7,15 +7,20 @@ use crate::cpu::{CPUPtr, CPU};
 use crate::CPU;
 
 use crate::{
-    flag::AllocFlag, shape::Shape, Alloc, ClearBuf, CloneBuf, CommonPtrs, Device, DevicelessAble,
-    Ident, IsShapeIndep, MainMemory, PtrType, Read, ShallowCopy, WriteBuf,
+    flag::AllocFlag, shape::Shape, Alloc, CastBuf, ClearBuf, CloneBuf, CommonPtrs, Device,
+    DevicelessAble, Ident, IsShapeIndep, MainMemory, PtrType, Read, ShallowCopy, WriteBuf,
 };
 
 pub use self::num::Num;
 pub use impl_from_const::*;
 
+mod constructors;
+#[cfg(not(feature = "no-std"))]
+mod impl_complex;
 mod impl_from;
 mod impl_from_const;
+#[cfg(all(feature = "half", not(feature = "no-std")))]
+mod impl_half;
 mod num;
 
 /// The underlying non-growable array structure of `custos`. A `Buffer` may be encapsulated in other data structures.
@@ -40,13 +45,12 @@ pub struct Buffer<'a, T = f32, D: Device = CPU, S: Shape = ()> {
     /// A reference to the corresponding device. Mainly used for operations without a device parameter.
     pub device: Option<&'a D>,
     /// Used as a cache and autograd identifier.
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     pub ident: Option<Ident>,
 }
 
-unsafe impl<'a, T, D: Device, S: Shape> Send for Buffer<'a, T, D, S> {}
-
-unsafe impl<'a, T, D: Device, S: Shape> Sync for Buffer<'a, T, D, S> {}
+// A `Buffer` is `Send` (`Sync`) if the pointer is `Send` (`Sync`) and the device is `Sync`.
+// The `CPU` and the `OpenCL` device are `Sync` if the `thread-safe` feature is enabled.
 
 impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     /// Creates a zeroed (or values set to default) `Buffer` with the given length on the specified device.
@@ -73,7 +77,7 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     {
         let ptr = device.alloc(len, AllocFlag::None);
 
-        #[cfg(not(feature = "no-std"))]
+        #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
         let ident = device.add_to_cache(&ptr);
 
         Buffer {
@@ -81,7 +85,7 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
             device: Some(device),
             // TODO: enable, if leafs get more important
             //node: device.graph().add_leaf(len),
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident,
         }
     }
@@ -110,7 +114,7 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     {
         Buffer {
             ptr: device.alloc(len, AllocFlag::None),
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: None,
             device: None,
         }
@@ -216,7 +220,7 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
         Buffer {
             ptr: self.ptr.shallow(),
             device: self.device,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: self.ident,
         }
     }
@@ -248,12 +252,12 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     /// Panics, if `Buffer` hasn't an id.
     #[inline]
     pub fn id(&self) -> Ident {
-        #[cfg(feature = "no-std")]
+        #[cfg(all(feature = "no-std", not(feature = "alloc")))]
         {
             unimplemented!("This buffer has no trackable id. Who?: e.g. 'Stack' Buffer, Buffers created via Buffer::from_raw_host..(..), `Num` (scalar) Buffer")
         }
 
-        #[cfg(not(feature = "no-std"))]
+        #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
         self.ident.expect("This buffer has no trackable id. Who?: e.g. 'Stack' Buffer, Buffers created via Buffer::from_raw_host..(..), `Num` (scalar) Buffer")
     }
 
@@ -264,6 +268,25 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     {
         self.device().clear(self)
     }
+
+    /// Casts the elements of the `Buffer` to another type.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer};
+    ///
+    /// let device = CPU::new();
+    /// let buf = Buffer::from((&device, [1.7, 2.2, -3.9]));
+    ///
+    /// assert_eq!(buf.cast::<i32>().read(), [1, 2, -3]);
+    /// ```
+    #[inline]
+    pub fn cast<U>(&self) -> Buffer<'a, U, D, S>
+    where
+        D: CastBuf<T, U, S, D>,
+    {
+        self.device().cast(self)
+    }
 }
 
 impl<'a, T, D: Device, S: Shape> Drop for Buffer<'a, T, D, S> {
@@ -273,7 +296,7 @@ impl<'a, T, D: Device, S: Shape> Drop for Buffer<'a, T, D, S> {
             return;
         }
 
-        #[cfg(not(feature = "no-std"))]
+        #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
         if let Some(device) = self.device {
             if let Some(ident) = self.ident {
                 device.remove(ident)
@@ -285,6 +308,8 @@ impl<'a, T, D: Device, S: Shape> Drop for Buffer<'a, T, D, S> {
 // TODO better solution for the to_dims stack problem?
 impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     /// Converts a non stack allocated `Buffer` with shape `S` to a `Buffer` with shape `O`.
+    /// Fails to build if `S` and `O` are const shapes with a different amount of elements.
+    /// Converting from `()` to a const shape requires [`Buffer::try_to_dims`].
     /// # Example
     #[cfg_attr(feature = "cpu", doc = "```")]
     #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
@@ -295,20 +320,73 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     /// let _b = a.to_dims::<Dim2<5, 2>>();
     ///
     /// ```
+    ///
+    #[cfg_attr(feature = "cpu", doc = "```compile_fail")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, Dim2, Dim3};
+    ///
+    /// let device = CPU::new();
+    /// let a = Buffer::<i32, CPU, Dim2<5, 5>>::new(&device, 25);
+    /// let _b = a.to_dims::<Dim3<4134, 20, 10>>();
+    /// ```
     #[inline]
     pub fn to_dims<O: Shape>(self) -> Buffer<'a, T, D, O>
+    where
+        D: crate::ToDim<T, S, O>,
+        D::Ptr<T, S>: ShallowCopy,
+    {
+        let () = crate::shape::AssertConvertible::<S, O>::OK;
+
+        // Safety: the lengths of the shapes were checked at compile time.
+        unsafe { self.to_dims_unchecked() }
+    }
+
+    /// Converts a non stack allocated `Buffer` with shape `S` to a `Buffer` with shape `O`.
+    /// The amount of elements is checked at runtime, hence this also works for `()` shapes.
+    /// # Errors
+    /// [`DeviceError::ShapeLengthMismatch`](crate::DeviceError::ShapeLengthMismatch) if the length of the buffer does not match `O::LEN`.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, Dim2};
+    ///
+    /// let device = CPU::new();
+    /// let a = Buffer::<i32, CPU>::new(&device, 10);
+    /// let b = a.try_to_dims::<Dim2<5, 2>>().unwrap();
+    ///
+    /// assert!(b.try_to_dims::<Dim2<3, 3>>().is_err());
+    /// ```
+    #[inline]
+    pub fn try_to_dims<O: Shape>(self) -> crate::Result<Buffer<'a, T, D, O>>
+    where
+        D: crate::ToDim<T, S, O>,
+        D::Ptr<T, S>: ShallowCopy,
+    {
+        if !crate::shape::fits_shape::<O>(self.len()) {
+            return Err(crate::DeviceError::ShapeLengthMismatch.into());
+        }
+
+        // Safety: the length was checked above.
+        Ok(unsafe { self.to_dims_unchecked() })
+    }
+
+    /// Converts a non stack allocated `Buffer` with shape `S` to a `Buffer` with shape `O` without checking the amount of elements.
+    /// # Safety
+    /// The buffer must contain exactly `O::LEN` elements if `O` is a const shape.
+    #[inline]
+    pub unsafe fn to_dims_unchecked<O: Shape>(self) -> Buffer<'a, T, D, O>
     where
         D: crate::ToDim<T, S, O>,
         D::Ptr<T, S>: ShallowCopy,
     {
         let buf = ManuallyDrop::new(self);
 
-        let ptr = buf.device().to_dim(unsafe { buf.ptr.shallow() });
+        let ptr = buf.device().to_dim(buf.ptr.shallow());
 
         Buffer {
             ptr,
             device: buf.device,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: buf.ident,
         }
     }
@@ -316,20 +394,71 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
 
 impl<'a, T, D: IsShapeIndep, S: Shape> Buffer<'a, T, D, S> {
     /// Returns a reference of the same buffer, but with a different shape.
-    /// The Buffer is shape independet, so it can be converted to any shape.
+    /// Fails to build if `S` and `O` are const shapes with a different amount of elements.
+    /// Viewing a `()` buffer with a const shape requires [`Buffer::try_as_dims`].
     #[inline]
     pub fn as_dims<'b, O: Shape>(&self) -> &Buffer<'b, T, D, O> {
+        let () = crate::shape::AssertConvertible::<S, O>::OK;
+
+        // Safety: the lengths of the shapes were checked at compile time.
+        unsafe { self.as_dims_unchecked() }
+    }
+
+    /// Returns a mutable reference of the same buffer, but with a different shape.
+    /// Fails to build if `S` and `O` are const shapes with a different amount of elements.
+    #[inline]
+    pub fn as_dims_mut<'b, O: Shape>(&mut self) -> &mut Buffer<'b, T, D, O> {
+        let () = crate::shape::AssertConvertible::<S, O>::OK;
+
+        // Safety: the lengths of the shapes were checked at compile time.
+        unsafe { self.as_dims_mut_unchecked() }
+    }
+
+    /// Returns a reference of the same buffer, but with a different shape.
+    /// The amount of elements is checked at runtime, hence this also works for `()` shapes.
+    /// # Errors
+    /// [`DeviceError::ShapeLengthMismatch`](crate::DeviceError::ShapeLengthMismatch) if the length of the buffer does not match `O::LEN`.
+    #[inline]
+    pub fn try_as_dims<'b, O: Shape>(&self) -> crate::Result<&Buffer<'b, T, D, O>> {
+        if !crate::shape::fits_shape::<O>(self.len()) {
+            return Err(crate::DeviceError::ShapeLengthMismatch.into());
+        }
+
+        // Safety: the length was checked above.
+        Ok(unsafe { self.as_dims_unchecked() })
+    }
+
+    /// Returns a mutable reference of the same buffer, but with a different shape.
+    /// The amount of elements is checked at runtime, hence this also works for `()` shapes.
+    /// # Errors
+    /// [`DeviceError::ShapeLengthMismatch`](crate::DeviceError::ShapeLengthMismatch) if the length of the buffer does not match `O::LEN`.
+    #[inline]
+    pub fn try_as_dims_mut<'b, O: Shape>(&mut self) -> crate::Result<&mut Buffer<'b, T, D, O>> {
+        if !crate::shape::fits_shape::<O>(self.len()) {
+            return Err(crate::DeviceError::ShapeLengthMismatch.into());
+        }
+
+        // Safety: the length was checked above.
+        Ok(unsafe { self.as_dims_mut_unchecked() })
+    }
+
+    /// Returns a reference of the same buffer, but with a different shape, without checking the amount of elements.
+    /// # Safety
+    /// The buffer must contain exactly `O::LEN` elements if `O` is a const shape.
+    #[inline]
+    pub unsafe fn as_dims_unchecked<'b, O: Shape>(&self) -> &Buffer<'b, T, D, O> {
         // Safety: shape independent buffers
         // -> all dims have a size of 0
         // -> all other buffer types do not depend on any features of the shape (S::ARR).
-        unsafe { &*(self as *const Self).cast() }
+        &*(self as *const Self).cast()
     }
 
-    /// Returns a mutable reference of the same buffer, but with a different shape.
-    /// The Buffer is shape independet, so it can be converted to any shape.
+    /// Returns a mutable reference of the same buffer, but with a different shape, without checking the amount of elements.
+    /// # Safety
+    /// The buffer must contain exactly `O::LEN` elements if `O` is a const shape.
     #[inline]
-    pub fn as_dims_mut<'b, O: Shape>(&mut self) -> &mut Buffer<'b, T, D, O> {
-        unsafe { &mut *(self as *mut Self).cast() }
+    pub unsafe fn as_dims_mut_unchecked<'b, O: Shape>(&mut self) -> &mut Buffer<'b, T, D, O> {
+        &mut *(self as *mut Self).cast()
     }
 }
 
@@ -377,12 +506,12 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     {
         let ptr = device.with_slice(slice);
 
-        #[cfg(not(feature = "no-std"))]
+        #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
         let ident = device.add_to_cache(&ptr);
 
         Buffer {
             ptr,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident,
             device: Some(device),
         }
@@ -420,12 +549,12 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     {
         let ptr = device.with_array(array);
 
-        #[cfg(not(feature = "no-std"))]
+        #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
         let ident = device.add_to_cache(&ptr);
 
         Buffer {
             ptr,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident,
             device: Some(device),
         }
@@ -561,11 +690,6 @@ where
     }
 }
 
-/*#[cfg(feature = "safe")]
-unsafe impl<T> Send for Buffer<'a, T> {}
-#[cfg(feature = "safe")]
-unsafe impl<T> Sync for Buffer<'a, T> {}*/
-
 impl<'a, T, D: Device, S: Shape> Default for Buffer<'a, T, D, S>
 where
     D::Ptr<T, S>: Default,
@@ -574,7 +698,7 @@ where
         Self {
             ptr: D::Ptr::<T, S>::default(),
             device: None,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: None,
         }
     }
@@ -771,15 +895,34 @@ mod tests {
     #[cfg(feature = "cpu")]
     #[test]
     fn test_to_dims() {
-        use crate::Dim2;
+        use crate::{Dim2, WithShape};
 
         let device = crate::CPU::new();
-        let buf = Buffer::from((&device, [1, 2, 3, 4, 5, 6]));
+        let buf = Buffer::with(&device, [1, 2, 3, 4, 5, 6]);
         let buf_dim2 = buf.to_dims::<Dim2<3, 2>>();
 
         buf_dim2.to_dims::<()>();
     }
 
+    #[cfg(feature = "cpu")]
+    #[test]
+    fn test_try_to_dims() {
+        use crate::{DeviceError, Dim1, Dim2, ErrorKind};
+
+        let device = crate::CPU::new();
+        let buf = Buffer::<_, _>::from((&device, [1, 2, 3, 4, 5, 6]));
+
+        assert_eq!(
+            buf.try_as_dims::<Dim2<2, 2>>().err().unwrap().kind(),
+            Some(&DeviceError::ShapeLengthMismatch)
+        );
+        assert_eq!(buf.try_as_dims::<Dim1<6>>().unwrap().read(), [1, 2, 3, 4, 5, 6]);
+
+        let buf = buf.try_to_dims::<Dim2<2, 3>>().unwrap();
+        assert_eq!(buf.read(), [1, 2, 3, 4, 5, 6]);
+        assert!(buf.to_dims::<()>().try_to_dims::<Dim1<5>>().is_err());
+    }
+
     #[cfg(feature = "cpu")]
     #[test]
     fn test_id_cpu() {
@@ -788,7 +931,13 @@ mod tests {
         let device = CPU::new();
 
         let buf = Buffer::from((&device, [1, 2, 3, 4]));
-        assert_eq!(buf.id(), Ident { idx: 0, len: 4 })
+        assert_eq!(
+            buf.id(),
+            Ident {
+                idx: crate::thread_ident_offset(),
+                len: 4
+            }
+        )
     }
 
     #[cfg(feature = "stack")]
@@ -0,0 +1,177 @@
+use crate::{
+    number::{Float, Number},
+    shape::Shape,
+    Alloc, Buffer, Dim2, FillBuf,
+};
+
+impl<'a, T, D, S> Buffer<'a, T, D, S>
+where
+    T: Number,
+    D: Alloc<'a, T, S> + FillBuf<T, S>,
+    S: Shape,
+{
+    /// Creates a `Buffer` with `len` elements set to `value`.
+    /// The elements are filled on the device.
+    ///
+    /// # Panics
+    /// If the shape `S` has a constant length that differs from `len`.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer};
+    ///
+    /// let device = CPU::new();
+    /// let buf = Buffer::<f32>::full(&device, 4, 2.5);
+    ///
+    /// assert_eq!(buf.read(), [2.5; 4]);
+    /// ```
+    #[inline]
+    pub fn full(device: &'a D, len: usize, value: T) -> Self {
+        assert_shape_len::<S>(len);
+
+        let mut buf = Buffer::new(device, len);
+        device.fill(&mut buf, value);
+        buf
+    }
+
+    /// Creates a `Buffer` with `len` elements set to one.
+    ///
+    /// # Panics
+    /// If the shape `S` has a constant length that differs from `len`.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer};
+    ///
+    /// let device = CPU::new();
+    /// let buf = Buffer::<i32>::ones(&device, 3);
+    ///
+    /// assert_eq!(buf.read(), [1; 3]);
+    /// ```
+    #[inline]
+    pub fn ones(device: &'a D, len: usize) -> Self {
+        Buffer::full(device, len, T::one())
+    }
+
+    /// Creates a `Buffer` with the values `start, start + step, ..` that lie between `start` (inclusive) and `end` (exclusive).
+    ///
+    /// # Panics
+    /// - If `step` is zero.
+    /// - If the range is empty or `step` points away from `end`.
+    /// - If the shape `S` has a constant length that differs from the number of values.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer};
+    ///
+    /// let device = CPU::new();
+    ///
+    /// let buf = Buffer::<i32>::arange(&device, 2, 9, 2);
+    /// assert_eq!(buf.read(), [2, 4, 6, 8]);
+    ///
+    /// let buf = Buffer::<f32>::arange(&device, 0., 1., 0.25);
+    /// assert_eq!(buf.read(), [0., 0.25, 0.5, 0.75]);
+    /// ```
+    pub fn arange(device: &'a D, start: T, end: T, step: T) -> Self {
+        assert!(step != T::zero(), "The step of `arange` must not be zero.");
+        assert!(start != end, "The range of `arange` must not be empty.");
+
+        // computed with floats, as `end - start` overflows for unsigned types if `end < start`
+        let len = (end.as_f64() - start.as_f64()) / step.as_f64();
+        assert!(
+            len > 0.,
+            "The step of `arange` must point from `start` towards `end`."
+        );
+
+        // no-std friendly ceil
+        let len = if (len as usize) as f64 == len {
+            len as usize
+        } else {
+            len as usize + 1
+        };
+        assert_shape_len::<S>(len);
+
+        let mut buf = Buffer::new(device, len);
+        device.fill_range(&mut buf, start, step);
+        buf
+    }
+}
+
+impl<'a, T, D, S> Buffer<'a, T, D, S>
+where
+    T: Float,
+    D: Alloc<'a, T, S> + FillBuf<T, S>,
+    S: Shape,
+{
+    /// Creates a `Buffer` with `len` evenly spaced values from `start` to `end` (inclusive).
+    /// `end` may be smaller than `start`.
+    ///
+    /// Only floating point types are supported, as the values of integer types would be truncated and not end at `end`.
+    ///
+    /// # Panics
+    /// - If `len` is smaller than two.
+    /// - If the shape `S` has a constant length that differs from `len`.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer};
+    ///
+    /// let device = CPU::new();
+    /// let buf = Buffer::<f32>::linspace(&device, 0., 1., 5);
+    ///
+    /// assert_eq!(buf.read(), [0., 0.25, 0.5, 0.75, 1.]);
+    /// ```
+    ///
+    /// Integer types are rejected at compile time:
+    #[cfg_attr(feature = "cpu", doc = "```compile_fail")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer};
+    ///
+    /// let device = CPU::new();
+    /// let buf = Buffer::<u8>::linspace(&device, 5, 0, 6);
+    /// ```
+    pub fn linspace(device: &'a D, start: T, end: T, len: usize) -> Self {
+        assert!(len > 1, "`linspace` requires at least two elements.");
+        assert_shape_len::<S>(len);
+
+        let step = (end - start) / T::from_usize(len - 1);
+
+        let mut buf = Buffer::new(device, len);
+        device.fill_range(&mut buf, start, step);
+        buf
+    }
+}
+
+/// Shapes with a constant length (e.g. [`Dim1`](crate::Dim1)) must be constructed with exactly this length.
+#[inline]
+fn assert_shape_len<S: Shape>(len: usize) {
+    assert!(
+        S::LEN == 0 || S::LEN == len,
+        "The length {len} does not match the length {} of the shape.",
+        S::LEN
+    );
+}
+
+impl<'a, T, D, const B: usize, const A: usize> Buffer<'a, T, D, Dim2<B, A>>
+where
+    T: Number,
+    D: Alloc<'a, T, Dim2<B, A>> + FillBuf<T, Dim2<B, A>>,
+{
+    /// Creates a `B`x`A` matrix with ones on the diagonal and zeros elsewhere.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, Dim2};
+    ///
+    /// let device = CPU::new();
+    /// let buf = Buffer::<f32, _, Dim2<2, 3>>::eye(&device);
+    ///
+    /// assert_eq!(buf.read(), [1., 0., 0., 0., 1., 0.]);
+    /// ```
+    #[inline]
+    pub fn eye(device: &'a D) -> Self {
+        let mut buf = Buffer::new(device, B * A);
+        device.fill_eye(&mut buf, A);
+        buf
+    }
+}
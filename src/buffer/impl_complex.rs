@@ -0,0 +1,34 @@
+use crate::{number::Complex, Buffer, Device, Read, Shape, WriteBuf};
+
+impl<'a, T: Clone, D: Device, S: Shape> Buffer<'a, Complex<T>, D, S> {
+    /// Writes an interleaved slice `[re0, im0, re1, im1, ..]` to the complex `Buffer`.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, number::Complex};
+    ///
+    /// let device = CPU::new();
+    /// let mut buf = Buffer::<Complex<f32>>::new(&device, 2);
+    /// buf.write_interleaved(&[1., 2., 3., 4.]);
+    ///
+    /// assert_eq!(buf.read(), [Complex::new(1., 2.), Complex::new(3., 4.)]);
+    /// assert_eq!(buf.read_interleaved(), [1., 2., 3., 4.]);
+    /// ```
+    #[inline]
+    pub fn write_interleaved(&mut self, data: &[T])
+    where
+        D: WriteBuf<Complex<T>, S, D>,
+    {
+        self.write(Complex::from_interleaved(data))
+    }
+
+    /// Reads the complex `Buffer` into an interleaved vector `[re0, im0, re1, im1, ..]`.
+    #[inline]
+    pub fn read_interleaved(&self) -> Vec<T>
+    where
+        D: Read<Complex<T>, S>,
+        T: Default,
+    {
+        Complex::as_interleaved(&self.read_to_vec()).to_vec()
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{Buffer, Device, Read, Shape, WriteBuf};
+
+macro_rules! impl_half_conversion {
+    ($($t:ident),*) => {
+        $(
+            impl<'a, D: Device, S: Shape> Buffer<'a, half::$t, D, S> {
+                /// Converts the f32 slice to half precision and writes it to the `Buffer`.
+                /// # Example
+                #[cfg_attr(feature = "cpu", doc = "```")]
+                #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+                #[doc = concat!("use custos::{CPU, Buffer};\n\nlet device = CPU::new();\nlet mut buf = Buffer::<half::", stringify!($t), ">::new(&device, 3);")]
+                /// buf.write_f32(&[1.5, -2., 4.]);
+                ///
+                /// assert_eq!(buf.read_f32(), [1.5, -2., 4.]);
+                /// ```
+                pub fn write_f32(&mut self, data: &[f32])
+                where
+                    D: WriteBuf<half::$t, S, D>,
+                {
+                    let data = data.iter().map(|x| half::$t::from_f32(*x)).collect::<Vec<_>>();
+                    self.write(&data)
+                }
+
+                /// Reads the contents of the `Buffer` and converts them to f32.
+                pub fn read_f32(&self) -> Vec<f32>
+                where
+                    D: Read<half::$t, S>,
+                {
+                    self.read_to_vec().iter().map(|x| x.to_f32()).collect()
+                }
+            }
+        )*
+    };
+}
+
+impl_half_conversion!(f16, bf16);
@@ -53,7 +53,7 @@ impl<'a, T: Clone> CloneBuf<'a, T> for () {
                 num: buf.ptr.num.clone(),
             },
             device: buf.device,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: buf.ident,
         }
     }
@@ -65,7 +65,7 @@ impl<T: crate::number::Number> From<T> for Buffer<'_, T, ()> {
         Buffer {
             ptr: Num { num: ptr },
             device: None,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: None,
         }
     }
@@ -82,7 +82,7 @@ impl<'a, T> Buffer<'a, T, ()> {
         Buffer {
             ptr: Num { num: self.ptr.num },
             device: self.device,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: self.ident,
         }
     }
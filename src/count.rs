@@ -63,8 +63,8 @@ impl AsRangeArg for (usize, usize) {
 /// To disable this caching behaviour, the `realloc` feature can be enabled.
 ///
 /// # Example
-#[cfg_attr(not(feature = "no-std"), doc = "```")]
-#[cfg_attr(feature = "no-std", doc = "```ignore")]
+#[cfg_attr(any(not(feature = "no-std"), feature = "alloc"), doc = "```")]
+#[cfg_attr(all(feature = "no-std", not(feature = "alloc")), doc = "```ignore")]
 /// use custos::{get_count, range, Ident, bump_count};
 ///
 /// for _ in range(100) { // using only one usize: exclusive range
@@ -87,7 +87,7 @@ pub struct Count(pub(super) usize, pub(super) usize);
 #[derive(Debug)]
 pub struct CountIntoIter {
     epoch: usize,
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     idx: usize,
     end: usize,
 }
@@ -96,7 +96,7 @@ impl Iterator for CountIntoIter {
     type Item = usize;
 
     fn next(&mut self) -> Option<Self::Item> {
-        #[cfg(not(feature = "no-std"))]
+        #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
         unsafe {
             crate::set_count(self.idx)
         };
@@ -118,7 +118,7 @@ impl IntoIterator for Count {
     fn into_iter(self) -> Self::IntoIter {
         CountIntoIter {
             epoch: self.0,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             idx: crate::get_count(),
             end: self.1,
         }
@@ -132,13 +132,13 @@ mod tests {
     fn count_iter(iter: &mut CountIntoIter) {
         iter.next();
         assert_eq!(iter.epoch, 1);
-        #[cfg(not(feature = "no-std"))]
+        #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
         assert_eq!(iter.idx, 0);
         assert_eq!(iter.end, 10);
 
         iter.next();
         assert_eq!(iter.epoch, 2);
-        #[cfg(not(feature = "no-std"))]
+        #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
         assert_eq!(iter.idx, 0);
         assert_eq!(iter.end, 10);
     }
@@ -147,7 +147,7 @@ mod tests {
     fn test_count_into_iter() {
         let mut iter = CountIntoIter {
             epoch: 0,
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             idx: 0,
             end: 10,
         };
@@ -1,19 +1,25 @@
-use core::{cell::RefCell, fmt::Debug};
+use core::fmt::Debug;
 
-use crate::{Cache, CacheReturn, Device, GlobalCount, Graph, GraphReturn, NodeIdx, PtrConv};
+use crate::{
+    Cache, CacheReturn, Device, GlobalCount, Graph, GraphReturn, NodeIdx, PtrConv, Shared,
+    SharedRef, SharedRefMut,
+};
 
 /// Provides several addons for a device.
 /// - `graph`: An optimizeable graph.
 /// - `cache`: A cache for allocations.
 /// - `tape`: A (gradient) tape.
+///
+/// The graph and the cache are [`Shared`] and can be accessed from several threads if the `thread-safe` feature is enabled.
+/// The tape is not thread-safe.
 pub struct Addons<D: Device, IdxFrom: NodeIdx = GlobalCount> {
     /// An optimizeable graph.
-    pub graph: RefCell<Graph<IdxFrom>>,
+    pub graph: Shared<Graph<IdxFrom>>,
     /// A cache for allocations.
-    pub cache: RefCell<Cache<D>>,
+    pub cache: Shared<Cache<D>>,
     /// A (gradient) tape.
     #[cfg(feature = "autograd")]
-    pub tape: RefCell<crate::Tape<D>>,
+    pub tape: core::cell::RefCell<crate::Tape<D>>,
 }
 
 impl<D: Device + Debug> Debug for Addons<D>
@@ -60,19 +66,19 @@ pub trait AddonsReturn: Device {
 
 impl<D: AddonsReturn> GraphReturn for D {
     #[inline]
-    fn graph(&self) -> std::cell::Ref<Graph<GlobalCount>> {
+    fn graph(&self) -> SharedRef<Graph<GlobalCount>> {
         self.addons().graph.borrow()
     }
 
     #[inline]
-    fn graph_mut(&self) -> std::cell::RefMut<Graph<GlobalCount>> {
+    fn graph_mut(&self) -> SharedRefMut<Graph<GlobalCount>> {
         self.addons().graph.borrow_mut()
     }
 }
 
 impl<D: AddonsReturn> CacheReturn for D {
     #[inline]
-    fn cache(&self) -> core::cell::Ref<crate::Cache<Self>>
+    fn cache(&self) -> SharedRef<crate::Cache<Self>>
     where
         Self: PtrConv,
     {
@@ -80,7 +86,7 @@ impl<D: AddonsReturn> CacheReturn for D {
     }
 
     #[inline]
-    fn cache_mut(&self) -> core::cell::RefMut<crate::Cache<Self>>
+    fn cache_mut(&self) -> SharedRefMut<crate::Cache<Self>>
     where
         Self: PtrConv,
     {
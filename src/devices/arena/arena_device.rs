@@ -0,0 +1,273 @@
+use core::{
+    cell::Cell,
+    mem::{align_of, size_of},
+    ptr::NonNull,
+};
+
+use crate::{flag::AllocFlag, shape::Shape, Alloc, Buffer, Device, MainMemory};
+
+use super::{ArenaError, ArenaPtr};
+
+/// A device that carves its buffers out of a preallocated region by bumping an offset.
+///
+/// Allocations never call the system allocator.
+/// [`BumpArena::try_buffer`], [`BumpArena::try_from_slice`] and [`BumpArena::try_apply_fn`] return an [`ArenaError`] if the region is exhausted,
+/// all other allocations (e.g. [`Buffer::new`] or [`ApplyFunction::apply_fn`](crate::ApplyFunction::apply_fn)) panic.
+/// The memory of dropped buffers is not reused until the arena is [`reset`](BumpArena::reset).
+/// As every buffer borrows the arena, `reset` can only be called after all buffers were dropped.
+///
+/// Every buffer is aligned to the alignment of its element type and at least to [`BumpArena::align`].
+/// # Example
+/// ```
+/// use custos::{arena::BumpArena, Buffer, Combiner, ApplyFunction};
+///
+/// let region = Box::leak(vec![0; 1024].into_boxed_slice());
+/// let mut arena = BumpArena::new(region);
+///
+/// for _ in 0..100 {
+///     let buf = Buffer::from((&arena, [1., 2., 3.]));
+///     let out = arena.apply_fn(&buf, |x| x.mul(2.));
+///     assert_eq!(out.read(), [2., 4., 6.]);
+///
+///     drop((buf, out));
+///     arena.reset();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct BumpArena {
+    start: *mut u8,
+    capacity: usize,
+    used: Cell<usize>,
+    align: usize,
+    /// `true` if the region was allocated by [`BumpArena::with_capacity`] and must be freed.
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
+    owned: bool,
+}
+
+impl BumpArena {
+    /// The minimum alignment of all buffers, unless changed via [`BumpArena::with_align`].
+    pub const DEFAULT_ALIGN: usize = 16;
+
+    /// Creates an arena that allocates its buffers in `region`.
+    #[must_use]
+    pub fn new(region: &'static mut [u8]) -> BumpArena {
+        BumpArena {
+            start: region.as_mut_ptr(),
+            capacity: region.len(),
+            used: Cell::new(0),
+            align: BumpArena::DEFAULT_ALIGN,
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
+            owned: false,
+        }
+    }
+
+    /// Creates an arena that allocates its buffers in a region of `capacity` bytes,
+    /// which is allocated once by the global allocator.
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> BumpArena {
+        let region = alloc::vec![0u8; capacity].into_boxed_slice();
+        let start = alloc::boxed::Box::into_raw(region) as *mut u8;
+
+        BumpArena {
+            start,
+            capacity,
+            used: Cell::new(0),
+            align: BumpArena::DEFAULT_ALIGN,
+            owned: true,
+        }
+    }
+
+    /// Sets the minimum alignment of all buffers that are allocated afterwards.
+    /// # Panics
+    /// If `align` is not a power of two.
+    #[must_use]
+    pub fn with_align(mut self, align: usize) -> BumpArena {
+        assert!(align.is_power_of_two(), "invalid alignment: {align}");
+        self.align = align;
+        self
+    }
+
+    /// Returns the minimum alignment of all buffers.
+    #[inline]
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Returns the size of the region in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the amount of bytes that were allocated since the last reset, including padding.
+    #[inline]
+    pub fn used(&self) -> usize {
+        self.used.get()
+    }
+
+    /// Returns the amount of bytes that are left in the region.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.capacity - self.used.get()
+    }
+
+    /// Makes the whole region available again.
+    ///
+    /// This requires a mutable reference, hence no buffer of this arena can be alive.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.used.set(0);
+    }
+
+    /// Allocates `len` elements of type `T` in the region and initializes the memory with zeros.
+    ///
+    /// # Errors
+    /// [`ArenaError::Exhausted`] if the region has not enough space left, or [`ArenaError::TooLarge`].
+    pub fn try_alloc<T>(&self, len: usize, flag: AllocFlag) -> Result<ArenaPtr<T>, ArenaError> {
+        let size = size_of::<T>()
+            .checked_mul(len)
+            .ok_or(ArenaError::TooLarge)?;
+
+        // zero-sized types and empty buffers do not need any memory
+        if size == 0 {
+            return Ok(ArenaPtr {
+                ptr: NonNull::dangling().as_ptr(),
+                len,
+                flag,
+            });
+        }
+
+        let align = align_of::<T>().max(self.align);
+        let used = self.used.get();
+
+        let addr = self.start as usize + used;
+        let padding = addr.wrapping_neg() & (align - 1);
+        let requested = padding.checked_add(size).ok_or(ArenaError::TooLarge)?;
+
+        let remaining = self.capacity - used;
+        if requested > remaining {
+            return Err(ArenaError::Exhausted {
+                requested,
+                remaining,
+            });
+        }
+
+        self.used.set(used + requested);
+
+        // Safety: the allocation is within the region
+        let ptr = unsafe { self.start.add(used + padding) };
+        unsafe { ptr.write_bytes(0, size) };
+
+        Ok(ArenaPtr {
+            ptr: ptr.cast(),
+            len,
+            flag,
+        })
+    }
+
+    /// Allocates a zeroed [`Buffer`] with `len` elements, or [`Shape::LEN`] elements if `len` is smaller.
+    ///
+    /// # Errors
+    /// Like [`BumpArena::try_alloc`].
+    /// # Example
+    /// ```
+    /// use custos::{arena::{ArenaError, BumpArena}, Buffer};
+    ///
+    /// let arena = BumpArena::new(Box::leak(vec![0; 64].into_boxed_slice()));
+    ///
+    /// let buf = arena.try_buffer::<f32, ()>(16).unwrap();
+    /// assert_eq!(buf.read(), [0.; 16]);
+    ///
+    /// let err = arena.try_buffer::<f32, ()>(1).unwrap_err();
+    /// assert!(matches!(err, ArenaError::Exhausted { .. }));
+    /// ```
+    pub fn try_buffer<T, S: Shape>(
+        &self,
+        len: usize,
+    ) -> Result<Buffer<T, BumpArena, S>, ArenaError> {
+        Ok(Buffer {
+            ptr: self.try_alloc(len.max(S::LEN), AllocFlag::None)?,
+            device: Some(self),
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
+            ident: None,
+        })
+    }
+
+    /// Allocates a [`Buffer`] that contains a copy of `data`.
+    ///
+    /// # Errors
+    /// Like [`BumpArena::try_alloc`].
+    pub fn try_from_slice<T: Clone, S: Shape>(
+        &self,
+        data: &[T],
+    ) -> Result<Buffer<T, BumpArena, S>, ArenaError> {
+        let mut buf = self.try_buffer::<T, S>(data.len())?;
+        for (value, data) in buf.iter_mut().zip(data) {
+            // the zeroed memory is not a valid `T` that could be dropped
+            unsafe { (value as *mut T).write(data.clone()) };
+        }
+        Ok(buf)
+    }
+}
+
+impl Drop for BumpArena {
+    fn drop(&mut self) {
+        #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
+        if self.owned {
+            let region = core::ptr::slice_from_raw_parts_mut(self.start, self.capacity);
+            drop(unsafe { alloc::boxed::Box::from_raw(region) });
+        }
+    }
+}
+
+impl Device for BumpArena {
+    type Ptr<U, S: Shape> = ArenaPtr<U>;
+    type Cache = ();
+
+    /// A `BumpArena` requires a region, use [`BumpArena::new`] or [`BumpArena::with_capacity`] instead.
+    fn new() -> crate::Result<Self> {
+        Err(ArenaError::MissingRegion.into())
+    }
+}
+
+impl<T, S: Shape> Alloc<'_, T, S> for BumpArena {
+    /// # Panics
+    /// If the arena is exhausted. Use [`BumpArena::try_buffer`] or [`BumpArena::try_apply_fn`] to handle this case.
+    fn alloc(&self, len: usize, flag: AllocFlag) -> ArenaPtr<T> {
+        assert!(len > 0, "invalid buffer len: 0");
+
+        self.try_alloc(len.max(S::LEN), flag)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn with_slice(&self, data: &[T]) -> ArenaPtr<T>
+    where
+        T: Clone,
+    {
+        assert!(!data.is_empty(), "invalid buffer len: 0");
+        assert!(S::LEN <= data.len(), "invalid buffer len: {}", data.len());
+
+        let ptr = self
+            .try_alloc::<T>(data.len(), AllocFlag::None)
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        for (idx, value) in data.iter().enumerate() {
+            // the zeroed memory is not a valid `T` that could be dropped
+            unsafe { ptr.ptr.add(idx).write(value.clone()) };
+        }
+        ptr
+    }
+}
+
+impl MainMemory for BumpArena {
+    #[inline]
+    fn as_ptr<T, S: Shape>(ptr: &Self::Ptr<T, S>) -> *const T {
+        ptr.ptr
+    }
+
+    #[inline]
+    fn as_ptr_mut<T, S: Shape>(ptr: &mut Self::Ptr<T, S>) -> *mut T {
+        ptr.ptr
+    }
+}
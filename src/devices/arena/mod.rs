@@ -0,0 +1,118 @@
+//! The Arena module provides a device that allocates its buffers from a fixed-capacity region.
+//!
+//! The [`BumpArena`] never calls the system allocator after it was created,
+//! which makes it suitable for real-time loops and embedded targets without an allocator.
+
+mod arena_device;
+mod ops;
+
+pub use arena_device::*;
+
+use core::ptr::null_mut;
+
+use crate::{flag::AllocFlag, CommonPtrs, PtrType, ShallowCopy};
+
+/// Errors that can occur while allocating from a [`BumpArena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaError {
+    /// The region of the arena has not enough space left for the requested buffer.
+    /// Call [`BumpArena::reset`] to reuse the region.
+    Exhausted {
+        /// The amount of bytes that were requested, including the padding needed for the alignment.
+        requested: usize,
+        /// The amount of bytes that are left in the region.
+        remaining: usize,
+    },
+    /// The size of the requested buffer overflows `usize`.
+    TooLarge,
+    /// A `BumpArena` can not be created without a region (see [`BumpArena::new`]).
+    MissingRegion,
+}
+
+impl core::fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ArenaError::Exhausted {
+                requested,
+                remaining,
+            } => write!(
+                f,
+                "The arena is exhausted: {requested} bytes were requested, but only {remaining} bytes are left."
+            ),
+            ArenaError::TooLarge => write!(f, "The size of the requested buffer overflows usize."),
+            ArenaError::MissingRegion => {
+                write!(f, "A BumpArena can not be created without a region.")
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for ArenaError {}
+
+#[cfg(feature = "no-std")]
+impl From<ArenaError> for crate::Error {
+    #[inline]
+    fn from(_err: ArenaError) -> Self {
+        crate::Error {}
+    }
+}
+
+/// The pointer used for [`BumpArena`] [`Buffer`](crate::Buffer)s.
+///
+/// The memory belongs to the region of the arena, hence dropping an `ArenaPtr` does not free anything.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ArenaPtr<T> {
+    /// The pointer to the data
+    pub ptr: *mut T,
+    /// The length of the data
+    pub len: usize,
+    /// Allocation flag for the pointer
+    pub flag: AllocFlag,
+}
+
+impl<T> Default for ArenaPtr<T> {
+    #[inline]
+    fn default() -> Self {
+        ArenaPtr {
+            ptr: null_mut(),
+            len: 0,
+            flag: AllocFlag::default(),
+        }
+    }
+}
+
+impl<T> PtrType for ArenaPtr<T> {
+    #[inline]
+    fn size(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn flag(&self) -> AllocFlag {
+        self.flag
+    }
+}
+
+impl<T> CommonPtrs<T> for ArenaPtr<T> {
+    #[inline]
+    fn ptrs(&self) -> (*const T, *mut core::ffi::c_void, u64) {
+        (self.ptr as *const T, null_mut(), 0)
+    }
+
+    #[inline]
+    fn ptrs_mut(&mut self) -> (*mut T, *mut core::ffi::c_void, u64) {
+        (self.ptr, null_mut(), 0)
+    }
+}
+
+impl<T> ShallowCopy for ArenaPtr<T> {
+    #[inline]
+    unsafe fn shallow(&self) -> Self {
+        ArenaPtr {
+            ptr: self.ptr,
+            len: self.len,
+            flag: AllocFlag::Wrapper,
+        }
+    }
+}
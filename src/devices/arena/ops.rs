@@ -0,0 +1,48 @@
+use crate::{
+    devices::host_ops::{impl_host_ops, impl_host_transfer},
+    eval_in_lanes, Buffer, Eval, MainMemory, Resolve, Shape,
+};
+
+use super::{ArenaError, BumpArena};
+
+impl_host_ops!(BumpArena);
+impl_host_transfer!(BumpArena);
+
+impl BumpArena {
+    /// Like [`ApplyFunction::apply_fn`](crate::ApplyFunction::apply_fn), but returns an [`ArenaError`] instead of panicking if the arena is exhausted.
+    ///
+    /// # Errors
+    /// Like [`BumpArena::try_alloc`].
+    /// # Example
+    /// ```
+    /// use custos::{arena::{ArenaError, BumpArena}, Buffer, Combiner};
+    ///
+    /// let arena = BumpArena::new(Box::leak(vec![0; 32].into_boxed_slice()));
+    /// let buf = Buffer::from((&arena, [1f32, 2., 3.]));
+    ///
+    /// let out = arena.try_apply_fn(&buf, |x| x.mul(2.)).unwrap();
+    /// assert_eq!(out.read(), [2., 4., 6.]);
+    ///
+    /// let err = arena.try_apply_fn(&buf, |x| x.mul(2.)).unwrap_err();
+    /// assert!(matches!(err, ArenaError::Exhausted { .. }));
+    /// ```
+    pub fn try_apply_fn<T, D, S, F>(
+        &self,
+        buf: &Buffer<T, D, S>,
+        f: impl Fn(Resolve<T>) -> F,
+    ) -> Result<Buffer<T, BumpArena, S>, ArenaError>
+    where
+        T: Copy,
+        D: MainMemory,
+        S: Shape,
+        F: Eval<T>,
+    {
+        let mut out = self.try_buffer::<T, S>(buf.len())?;
+
+        eval_in_lanes(buf, f, |idx, values| {
+            out[idx..idx + values.len()].copy_from_slice(values)
+        });
+
+        Ok(out)
+    }
+}
@@ -0,0 +1,229 @@
+//! Options that are passed to the OpenCL and CUDA (NVRTC) kernel compilers.
+
+use core::fmt::Display;
+use std::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Build options of a kernel. Unset options fall back to the options of the device.
+///
+/// # Example
+/// ```
+/// use custos::BuildOptions;
+///
+/// let options = BuildOptions::new()
+///     .define("TILE_SIZE", 16)
+///     .fast_math(true)
+///     .std("CL2.0");
+///
+/// assert_eq!(options.opencl_args(), "-D TILE_SIZE=16 -cl-fast-relaxed-math -cl-std=CL2.0");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BuildOptions {
+    /// Preprocessor definitions. A definition without value is passed as `-D NAME`.
+    pub defines: Vec<(String, Option<String>)>,
+    /// Enables or disables optimizations that do not conform to the IEEE 754 standard.
+    pub fast_math: Option<bool>,
+    /// The language standard. e.g. `CL1.2` for OpenCL or `c++17` for CUDA.
+    pub std: Option<String>,
+    /// Additional arguments that are passed to the compiler unmodified.
+    pub args: Vec<String>,
+}
+
+impl BuildOptions {
+    /// Returns build options without any option set.
+    #[inline]
+    pub fn new() -> BuildOptions {
+        BuildOptions::default()
+    }
+
+    /// Adds the preprocessor definition `name=value`.
+    pub fn define(mut self, name: impl Into<String>, value: impl Display) -> BuildOptions {
+        self.defines.push((name.into(), Some(value.to_string())));
+        self
+    }
+
+    /// Adds the preprocessor definition `name` without a value.
+    pub fn define_flag(mut self, name: impl Into<String>) -> BuildOptions {
+        self.defines.push((name.into(), None));
+        self
+    }
+
+    /// Enables or disables fast math.
+    #[inline]
+    pub fn fast_math(mut self, fast_math: bool) -> BuildOptions {
+        self.fast_math = Some(fast_math);
+        self
+    }
+
+    /// Sets the language standard.
+    #[inline]
+    pub fn std(mut self, std: impl Into<String>) -> BuildOptions {
+        self.std = Some(std.into());
+        self
+    }
+
+    /// Adds an argument that is passed to the compiler unmodified.
+    #[inline]
+    pub fn arg(mut self, arg: impl Into<String>) -> BuildOptions {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Returns the options of `self`, with every option that is set in `overrides` replaced.
+    /// Definitions and arguments of `overrides` are appended, hence a later definition of the same name wins.
+    /// # Example
+    /// ```
+    /// use custos::BuildOptions;
+    ///
+    /// let device = BuildOptions::new().std("CL1.2").define("N", 1);
+    /// let call = BuildOptions::new().fast_math(true).define("N", 2);
+    ///
+    /// assert_eq!(
+    ///     device.merge(&call).opencl_args(),
+    ///     "-D N=1 -D N=2 -cl-fast-relaxed-math -cl-std=CL1.2"
+    /// );
+    /// ```
+    pub fn merge(&self, overrides: &BuildOptions) -> BuildOptions {
+        BuildOptions {
+            defines: self
+                .defines
+                .iter()
+                .chain(&overrides.defines)
+                .cloned()
+                .collect(),
+            fast_math: overrides.fast_math.or(self.fast_math),
+            std: overrides.std.clone().or_else(|| self.std.clone()),
+            args: self.args.iter().chain(&overrides.args).cloned().collect(),
+        }
+    }
+
+    /// The options as arguments of `clBuildProgram`.
+    pub fn opencl_args(&self) -> String {
+        let mut args = self
+            .defines
+            .iter()
+            .map(|(name, value)| match value {
+                Some(value) => format!("-D {name}={value}"),
+                None => format!("-D {name}"),
+            })
+            .collect::<Vec<_>>();
+
+        if self.fast_math == Some(true) {
+            args.push("-cl-fast-relaxed-math".into());
+        }
+        if let Some(std) = &self.std {
+            args.push(format!("-cl-std={std}"));
+        }
+        args.extend(self.args.iter().cloned());
+        args.join(" ")
+    }
+
+    /// The options as arguments of `nvrtcCompileProgram`.
+    pub fn nvrtc_args(&self) -> Vec<String> {
+        let mut args = self
+            .defines
+            .iter()
+            .map(|(name, value)| match value {
+                Some(value) => format!("--define-macro={name}={value}"),
+                None => format!("--define-macro={name}"),
+            })
+            .collect::<Vec<_>>();
+
+        if self.fast_math == Some(true) {
+            args.push("--use_fast_math".into());
+        }
+        if let Some(std) = &self.std {
+            args.push(format!("--std={std}"));
+        }
+        args.extend(self.args.iter().cloned());
+        args
+    }
+}
+
+/// Identifies a compiled kernel by its source code, entry point and compiler arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KernelKey {
+    /// The source code of the kernel.
+    pub src: String,
+    /// The name of the kernel function. `None` selects the first kernel of the source code.
+    pub entry: Option<String>,
+    /// The arguments that were passed to the compiler.
+    pub args: String,
+}
+
+/// A kernel failed to compile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    /// The error returned by the compiler.
+    pub reason: String,
+    /// The build log of the compiler, which contains the errors and warnings of the source code.
+    pub log: String,
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Failed to compile kernel: {}", self.reason)?;
+        if !self.log.trim().is_empty() {
+            write!(f, "\n{}", self.log.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::{BuildOptions, CompileError};
+
+    #[test]
+    fn test_nvrtc_args() {
+        let options = BuildOptions::new()
+            .define("N", 4)
+            .define_flag("USE_SHARED")
+            .fast_math(true)
+            .std("c++17")
+            .arg("-lineinfo");
+
+        assert_eq!(
+            options.nvrtc_args(),
+            [
+                "--define-macro=N=4",
+                "--define-macro=USE_SHARED",
+                "--use_fast_math",
+                "--std=c++17",
+                "-lineinfo"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_overrides_device_options() {
+        let device = BuildOptions::new().fast_math(true).std("CL1.2");
+        let call = BuildOptions::new().fast_math(false).arg("-w");
+
+        let merged = device.merge(&call);
+        assert_eq!(merged.fast_math, Some(false));
+        assert_eq!(merged.opencl_args(), "-cl-std=CL1.2 -w");
+
+        assert_eq!(device.merge(&BuildOptions::new()), device);
+    }
+
+    #[test]
+    fn test_compile_error_contains_log() {
+        let error = CompileError {
+            reason: "BuildProgramFailure".into(),
+            log: "<source>:2:5: error: use of undeclared identifier 'x'\n".into(),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Failed to compile kernel: BuildProgramFailure\n<source>:2:5: error: use of undeclared identifier 'x'"
+        );
+    }
+}
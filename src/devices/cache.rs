@@ -1,24 +1,27 @@
 //! Contains the [`Cache`]ing logic.
 
-use core::{cell::RefMut, fmt::Debug, hash::BuildHasherDefault, ops::BitXor};
+#[cfg(feature = "no-std")]
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "no-std"))]
+use core::hash::BuildHasherDefault;
+use core::{fmt::Debug, ops::BitXor};
+#[cfg(not(feature = "no-std"))]
 use std::collections::HashMap;
 
-use std::rc::Rc;
-
 use crate::{
     flag::AllocFlag, shape::Shape, Alloc, Buffer, CacheAble, Device, GlobalCount, GraphReturn,
-    Ident, PtrConv, PtrType,
+    Ident, PtrConv, PtrType, SharedPtr, SharedRef, SharedRefMut,
 };
 
 /// This trait makes a device's [`Cache`] accessible and is implemented for all compute devices.
 pub trait CacheReturn: GraphReturn<GlobalCount> {
     /// Returns a reference to a device's [`Cache`].
-    fn cache(&self) -> core::cell::Ref<Cache<Self>>
+    fn cache(&self) -> SharedRef<Cache<Self>>
     where
         Self: PtrConv;
 
     /// Returns a mutable reference to a device's [`Cache`].
-    fn cache_mut(&self) -> RefMut<Cache<Self>>
+    fn cache_mut(&self) -> SharedRefMut<Cache<Self>>
     where
         Self: PtrConv;
 }
@@ -31,7 +34,7 @@ pub struct IdentHasher {
     hash: usize,
 }
 
-impl std::hash::Hasher for IdentHasher {
+impl core::hash::Hasher for IdentHasher {
     #[inline]
     fn finish(&self) -> u64 {
         self.hash as u64
@@ -99,23 +102,32 @@ where
     fn add_to_cache<T, S: Shape>(device: &D, ptr: &<D as Device>::Ptr<T, S>) -> Option<Ident> {
         device.graph_mut().add_leaf(ptr.size());
         let ident = Ident::new_bumped(ptr.size());
-        let raw_ptr = unsafe { std::rc::Rc::new(D::convert(ptr, AllocFlag::Wrapper)) };
+        let raw_ptr = unsafe { SharedPtr::new(D::convert(ptr, AllocFlag::Wrapper)) };
         device.cache_mut().nodes.insert(ident, raw_ptr);
         Some(ident)
     }
 }
 
+/// A map keyed by [`Ident`]s or indices, which are hashed with the [`IdentHasher`].
+#[cfg(not(feature = "no-std"))]
+pub type IdentMap<K, V> = HashMap<K, V, BuildHasherDefault<IdentHasher>>;
+
+/// A map keyed by [`Ident`]s or indices.
+/// Without `std`, this is a [`BTreeMap`], as `HashMap` is not available in `alloc`.
+#[cfg(feature = "no-std")]
+pub type IdentMap<K, V> = BTreeMap<K, V>;
+
 /// A cache for 'no-generic' raw pointers.
 pub struct Cache<D: Device> {
     /// A map of all cached buffers using a custom hash function.
-    pub nodes: HashMap<Ident, Rc<D::Ptr<u8, ()>>, BuildHasherDefault<IdentHasher>>,
+    pub nodes: IdentMap<Ident, SharedPtr<D::Ptr<u8, ()>>>,
 }
 
 impl<D: Device> Debug for Cache<D>
 where
     D::Ptr<u8, ()>: Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Cache2")
             .field("cache", &self.nodes)
             .finish()
@@ -180,7 +192,7 @@ impl<D: PtrConv + GraphReturn> Cache<D> {
         };
 
         let untyped_ptr = unsafe { D::convert(&ptr, AllocFlag::None) };
-        self.nodes.insert(ident, Rc::new(untyped_ptr));
+        self.nodes.insert(ident, SharedPtr::new(untyped_ptr));
 
         callback();
 
@@ -246,7 +258,7 @@ impl<D: PtrConv + GraphReturn> Cache<D> {
 #[cfg(test)]
 mod tests {
     use core::hash::Hasher;
-    use std::collections::HashSet;
+    use alloc::collections::BTreeSet;
 
     //#[cfg(not(feature = "realloc"))]
     //use crate::set_count;
@@ -257,7 +269,7 @@ mod tests {
     fn test_ident_hasher() {
         use crate::IdentHasher;
 
-        let mut hashed_items = HashSet::new();
+        let mut hashed_items = BTreeSet::new();
         let mut hasher = IdentHasher::default();
 
         for item in 0..2500000 {
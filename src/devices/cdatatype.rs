@@ -82,3 +82,22 @@ impl CDatatype for u64 {
         "ulong"
     }
 }
+
+/// OpenCL kernels using `half` require the `cl_khr_fp16` extension, CUDA kernels `cuda_fp16.h`.
+/// Both are enabled automatically by the kernel caches.
+#[cfg(feature = "half")]
+impl CDatatype for half::f16 {
+    #[inline]
+    fn as_c_type_str() -> &'static str {
+        "half"
+    }
+}
+
+/// Only available in CUDA kernels (`cuda_bf16.h`).
+#[cfg(feature = "half")]
+impl CDatatype for half::bf16 {
+    #[inline]
+    fn as_c_type_str() -> &'static str {
+        "__nv_bfloat16"
+    }
+}
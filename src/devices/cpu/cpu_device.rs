@@ -8,7 +8,7 @@ use core::{
     mem::{align_of, size_of},
 };
 
-use super::CPUPtr;
+use super::{CPUPtr, Parallelism};
 
 #[derive(Debug, Default)]
 /// A CPU is used to perform calculations on the host CPU.
@@ -28,14 +28,31 @@ use super::CPUPtr;
 pub struct CPU {
     /// Provides additional functionality for the CPU. e.g. a cache, a gradient [`Tape`](crate::Tape), an optimizeable [`Graph`](crate::Graph) and a [`Cache`](crate::Cache).
     pub addons: Addons<CPU>,
+    /// Configures how element-wise operations (e.g. [`apply_fn`](crate::ApplyFunction::apply_fn)) are split across threads.
+    pub parallelism: Parallelism,
 }
 
 impl CPU {
     /// Creates an [CPU] with default addons.
+    /// All operations are executed on the calling thread.
     #[must_use]
     pub fn new() -> CPU {
+        CPU::with_parallelism(Parallelism::serial())
+    }
+
+    /// Creates an [CPU] that splits element-wise operations across threads.
+    /// # Example
+    /// ```
+    /// use custos::{cpu::Parallelism, CPU};
+    ///
+    /// let device = CPU::with_parallelism(Parallelism::available());
+    /// assert!(device.parallelism.threads() >= 1);
+    /// ```
+    #[must_use]
+    pub fn with_parallelism(parallelism: Parallelism) -> CPU {
         CPU {
             addons: Addons::default(),
+            parallelism,
         }
     }
 }
@@ -9,6 +9,7 @@ use core::{
     ptr::null_mut,
 };
 pub use cpu_device::*;
+pub use parallel::*;
 use std::alloc::handle_alloc_error;
 
 use crate::flag::AllocFlag;
@@ -17,6 +18,7 @@ use crate::flag::AllocFlag;
 mod blas;
 mod cpu_device;
 mod ops;
+mod parallel;
 
 /// The pointer used for `CPU` [`Buffer`](crate::Buffer)s
 #[derive(PartialEq, Eq, Debug)]
@@ -111,6 +113,12 @@ impl<T> CPUPtr<T> {
     }
 }
 
+// Safety: A `CPUPtr` owns its allocation like a `Box<[T]>`.
+unsafe impl<T: Send> Send for CPUPtr<T> {}
+
+// Safety: A `CPUPtr` owns its allocation like a `Box<[T]>`.
+unsafe impl<T: Sync> Sync for CPUPtr<T> {}
+
 impl<T> Default for CPUPtr<T> {
     fn default() -> Self {
         Self {
@@ -1,75 +1,155 @@
-use core::ops::{Index, Range, RangeBounds};
+use core::ops::{AddAssign, Mul};
 
-use crate::{bounds_to_range, Buffer, ClearBuf, CopySlice, MainMemory, Read, Shape, WriteBuf, CPU};
+use crate::{
+    devices::host_ops::impl_host_transfer, eval_in_lanes, number::Number, optim::UpdateFn,
+    ApplyFunction, Buffer, ClearBuf, Device, Eval, MainMemory, MayToCLSource, ReadAsync, Resolve,
+    Shape, Transfer, UnaryGrad, WriteAsync, WriteBuf, CPU,
+};
 
-impl<T, D: MainMemory, S: Shape> Read<T, S, D> for CPU {
-    type Read<'a> = &'a [T] where T: 'a, D: 'a, S: 'a;
+impl<T, D, S> ApplyFunction<T, S, D> for CPU
+where
+    T: Copy + Default + Send + Sync,
+    D: MainMemory,
+    S: Shape,
+{
+    fn apply_fn<F>(
+        &self,
+        buf: &Buffer<T, D, S>,
+        f: impl Fn(Resolve<T>) -> F + Sync,
+    ) -> Buffer<T, Self, S>
+    where
+        F: Eval<T> + MayToCLSource,
+    {
+        let mut out = self.retrieve::<T, S>(buf.len(), buf);
+        let buf: &[T] = buf;
 
-    #[inline]
-    fn read<'a>(&self, buf: &'a Buffer<T, D, S>) -> Self::Read<'a> {
-        buf.as_slice()
+        self.parallelism.for_each_chunk(&mut out, |offset, out| {
+            eval_in_lanes(&buf[offset..offset + out.len()], &f, |idx, values| {
+                out[idx..idx + values.len()].copy_from_slice(values)
+            });
+        });
+
+        out
     }
+}
 
-    #[inline]
-    fn read_to_vec<'a>(&self, buf: &Buffer<T, D, S>) -> Vec<T>
-    where
-        T: Default + Clone,
+impl<T, D, S> UnaryGrad<T, S, D> for CPU
+where
+    T: AddAssign + Copy + Mul<Output = T> + Send + Sync,
+    S: Shape,
+    D: MainMemory,
+{
+    fn add_unary_grad<F>(
+        &self,
+        lhs: &Buffer<T, D, S>,
+        lhs_grad: &mut Buffer<T, D, S>,
+        out: &Buffer<T, D, S>,
+        lhs_grad_fn: impl Fn(Resolve<T>) -> F + Sync,
+    ) where
+        F: Eval<T> + MayToCLSource,
     {
-        buf.to_vec()
+        let (lhs, out): (&[T], &[T]) = (lhs, out);
+
+        self.parallelism
+            .for_each_chunk(lhs_grad, |offset, lhs_grad| {
+                let lhs = &lhs[offset..offset + lhs_grad.len()];
+                eval_in_lanes(lhs, &lhs_grad_fn, |idx, grads| {
+                    let lhs_grad = &mut lhs_grad[idx..idx + grads.len()];
+                    let out = &out[offset + idx..];
+                    for ((lhs_grad, out), grad) in lhs_grad.iter_mut().zip(out).zip(grads) {
+                        *lhs_grad += *out * *grad;
+                    }
+                });
+            });
     }
 }
 
-impl<T: Copy, D: MainMemory, S: Shape> WriteBuf<T, S, D> for CPU {
+impl<T, D, S> UpdateFn<T, S, D> for CPU
+where
+    T: Copy + Send + Sync,
+    D: MainMemory,
+    S: Shape,
+{
+    fn update_fn<F, const N: usize>(
+        &self,
+        buf: &mut Buffer<T, D, S>,
+        (lhs, rhs): (&Buffer<T, D, S>, &Buffer<T, D, S>),
+        scalars: [T; N],
+        f: impl Fn(Resolve<T>, Resolve<T>, Resolve<T>, [Resolve<T>; N]) -> F + Sync,
+    ) where
+        F: Eval<T> + MayToCLSource,
+    {
+        let (lhs, rhs): (&[T], &[T]) = (lhs, rhs);
+        let scalars = scalars.map(Resolve::with_val);
+
+        self.parallelism.for_each_chunk(buf, |offset, buf| {
+            let lhs = &lhs[offset..offset + buf.len()];
+            let rhs = &rhs[offset..offset + buf.len()];
+
+            for ((value, lhs), rhs) in buf.iter_mut().zip(lhs).zip(rhs) {
+                *value = f(
+                    Resolve::with_val(*value),
+                    Resolve::with_val(*lhs),
+                    Resolve::with_val(*rhs),
+                    scalars,
+                )
+                .eval();
+            }
+        });
+    }
+}
+
+impl_host_transfer!(CPU);
+
+impl<T: Clone, D: MainMemory, S: Shape> ReadAsync<T, S, D> for CPU {
     #[inline]
-    fn write(&self, buf: &mut Buffer<T, D, S>, data: &[T]) {
-        buf.copy_from_slice(data)
+    fn read_async<'a>(&'a self, buf: &'a Buffer<T, D, S>) -> Transfer<'a, Vec<T>> {
+        Transfer::ready(buf.to_vec())
     }
+}
 
+impl<T: Copy, D: MainMemory, S: Shape> WriteAsync<T, S, D> for CPU {
     #[inline]
-    fn write_buf(&self, dst: &mut Buffer<T, D, S>, src: &Buffer<T, D, S>) {
-        self.write(dst, src)
+    fn write_async<'a>(
+        &'a self,
+        buf: &'a mut Buffer<T, D, S>,
+        data: &'a [T],
+    ) -> Transfer<'a, ()> {
+        self.write(buf, data);
+        Transfer::ready(())
     }
 }
 
 // #[impl_stack]
-impl<T: Default, D: MainMemory, S: Shape> ClearBuf<T, S, D> for CPU {
+impl<T: Default + Send, D: MainMemory, S: Shape> ClearBuf<T, S, D> for CPU {
     fn clear(&self, buf: &mut Buffer<T, D, S>) {
-        for value in buf {
-            *value = T::default();
-        }
+        self.parallelism.for_each_chunk(buf, |_, buf| {
+            for value in buf {
+                *value = T::default();
+            }
+        });
     }
 }
 
-impl<T: Copy, D: MainMemory> CopySlice<T, D> for CPU
-where
-    [T]: Index<Range<usize>, Output = [T]>,
-{
-    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
-        &self,
-        source: &Buffer<T, D>,
-        source_range: SR,
-        dest: &mut Buffer<T, Self>,
-        dest_range: DR,
-    ) {
-        let source_range = bounds_to_range(source_range, source.len());
-        let dest_range = bounds_to_range(dest_range, dest.len());
-
-        assert_eq!(
-            source_range.end - source_range.start,
-            dest_range.end - dest_range.start,
-        );
-
-        dest[dest_range].copy_from_slice(&source[source_range]);
-    }
-
-    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
-        &self,
-        source: &Buffer<T, D>,
-        dest: &mut Buffer<T, Self>,
-        ranges: I,
-    ) {
-        for (source_range, dest_range) in ranges {
-            self.copy_slice_to(source, source_range, dest, dest_range);
-        }
+impl CPU {
+    /// Returns the sum of all elements.
+    /// The chunks of the buffer are summed up on the threads of the [`Parallelism`](super::Parallelism) and the partial sums are added in order.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, CPU};
+    ///
+    /// let device = CPU::new();
+    /// let buf = Buffer::from((&device, [1, 2, 3, 4]));
+    /// assert_eq!(device.sum(&buf), 10);
+    /// ```
+    pub fn sum<T, D, S>(&self, buf: &Buffer<T, D, S>) -> T
+    where
+        T: Number + Send + Sync,
+        D: MainMemory,
+        S: Shape,
+    {
+        self.parallelism
+            .reduce(buf, |chunk| chunk.iter().copied().sum(), |lhs, rhs| lhs + rhs)
+            .unwrap_or_default()
     }
 }
@@ -0,0 +1,256 @@
+#[cfg(feature = "rayon")]
+use std::sync::Arc;
+use std::{num::NonZeroUsize, thread};
+
+#[cfg(feature = "rayon")]
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::{ParallelSlice, ParallelSliceMut},
+    ThreadPool, ThreadPoolBuilder,
+};
+
+/// Configures how a [`CPU`](crate::CPU) splits element-wise and reduction work across threads.
+///
+/// The work is divided into chunks of `chunk_size` elements, which are processed by a persistent work-stealing thread pool.
+/// The pool is created once with the `Parallelism` and is owned by it (and thus by the `CPU`).
+/// Operations on fewer than [`min_len`](Parallelism::min_len) elements are executed on the calling thread,
+/// because handing them to the pool costs more than it saves.
+///
+/// The thread pool requires the `rayon` feature. Without it, all work is executed on the calling thread.
+///
+/// Results are deterministic:
+/// element-wise operations only depend on the element itself and
+/// reductions combine the partial results of the chunks in order.
+/// As the chunk boundaries only depend on `chunk_size`, changing `threads` never changes a result.
+///
+/// # Example
+/// ```
+/// use custos::{cpu::Parallelism, Buffer, CPU};
+///
+/// let device = CPU::with_parallelism(Parallelism::new(4, 1024));
+/// let buf = Buffer::<i32, _>::from((&device, vec![1; 100_000]));
+///
+/// assert_eq!(device.sum(&buf), 100_000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Parallelism {
+    threads: usize,
+    chunk_size: usize,
+    min_len: usize,
+    #[cfg(feature = "rayon")]
+    pool: Option<Arc<ThreadPool>>,
+}
+
+impl Default for Parallelism {
+    #[inline]
+    fn default() -> Self {
+        Parallelism::serial()
+    }
+}
+
+impl Parallelism {
+    /// The default chunk size in elements.
+    pub const DEFAULT_CHUNK_SIZE: usize = 16384;
+
+    /// The default number of elements from which on work is split across threads.
+    pub const DEFAULT_MIN_LEN: usize = 2 * Parallelism::DEFAULT_CHUNK_SIZE;
+
+    /// Creates a new `Parallelism` with a thread pool of `threads` threads. `threads` and `chunk_size` are at least 1.
+    ///
+    /// If the pool cannot be created, all work is executed on the calling thread.
+    #[inline]
+    pub fn new(threads: usize, chunk_size: usize) -> Parallelism {
+        let threads = threads.max(1);
+
+        Parallelism {
+            threads,
+            chunk_size: chunk_size.max(1),
+            min_len: Parallelism::DEFAULT_MIN_LEN,
+            #[cfg(feature = "rayon")]
+            pool: (threads > 1)
+                .then(|| ThreadPoolBuilder::new().num_threads(threads).build().ok())
+                .flatten()
+                .map(Arc::new),
+        }
+    }
+
+    /// Executes all work on the calling thread.
+    #[inline]
+    pub fn serial() -> Parallelism {
+        Parallelism::new(1, Parallelism::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Uses as many threads as the system provides (see [`std::thread::available_parallelism`]).
+    #[inline]
+    pub fn available() -> Parallelism {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Parallelism::new(threads, Parallelism::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Sets the number of elements from which on work is split across threads.
+    /// # Example
+    /// ```
+    /// use custos::cpu::Parallelism;
+    ///
+    /// let parallelism = Parallelism::new(4, 256).with_min_len(1024);
+    /// assert_eq!(parallelism.min_len(), 1024);
+    /// ```
+    #[inline]
+    pub fn with_min_len(mut self, min_len: usize) -> Parallelism {
+        self.min_len = min_len;
+        self
+    }
+
+    /// The maximum number of threads that work on one operation.
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// The number of elements a thread processes at once.
+    #[inline]
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// The number of elements from which on work is split across threads.
+    #[inline]
+    pub fn min_len(&self) -> usize {
+        self.min_len
+    }
+
+    /// Returns the thread pool if work on `len` elements should be split across threads.
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn pool(&self, len: usize) -> Option<&ThreadPool> {
+        if len < self.min_len || len <= self.chunk_size {
+            return None;
+        }
+        self.pool.as_deref()
+    }
+
+    /// Calls `f` with every chunk of `data` and the offset of the chunk.
+    /// # Example
+    /// ```
+    /// use custos::cpu::Parallelism;
+    ///
+    /// let mut data = [0; 10];
+    /// Parallelism::new(3, 4).for_each_chunk(&mut data, |offset, chunk| {
+    ///     for (idx, value) in chunk.iter_mut().enumerate() {
+    ///         *value = offset + idx;
+    ///     }
+    /// });
+    /// assert_eq!(data, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    /// ```
+    pub fn for_each_chunk<T: Send>(&self, data: &mut [T], f: impl Fn(usize, &mut [T]) + Sync) {
+        let chunk_size = self.chunk_size;
+
+        #[cfg(feature = "rayon")]
+        if let Some(pool) = self.pool(data.len()) {
+            return pool.install(|| {
+                data.par_chunks_mut(chunk_size)
+                    .enumerate()
+                    .for_each(|(idx, chunk)| f(idx * chunk_size, chunk))
+            });
+        }
+
+        for (idx, chunk) in data.chunks_mut(chunk_size).enumerate() {
+            f(idx * chunk_size, chunk)
+        }
+    }
+
+    /// Maps every chunk of `data` to a partial result and combines the partial results in order.
+    /// Returns `None` if `data` is empty.
+    /// # Example
+    /// ```
+    /// use custos::cpu::Parallelism;
+    ///
+    /// let data = [1, 5, 3, 2, 4];
+    /// let max = Parallelism::new(2, 2).reduce(&data, |chunk| *chunk.iter().max().unwrap(), i32::max);
+    /// assert_eq!(max, Some(5));
+    /// ```
+    pub fn reduce<T, U>(
+        &self,
+        data: &[T],
+        map: impl Fn(&[T]) -> U + Sync,
+        combine: impl FnMut(U, U) -> U,
+    ) -> Option<U>
+    where
+        T: Sync,
+        U: Send,
+    {
+        #[cfg(feature = "rayon")]
+        if let Some(pool) = self.pool(data.len()) {
+            let partials = pool.install(|| {
+                data.par_chunks(self.chunk_size)
+                    .map(&map)
+                    .collect::<Vec<_>>()
+            });
+            return partials.into_iter().reduce(combine);
+        }
+
+        data.chunks(self.chunk_size).map(map).reduce(combine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Parallelism;
+
+    #[test]
+    fn test_for_each_chunk_covers_all() {
+        for threads in 1..6 {
+            let mut data = vec![0usize; 1001];
+            Parallelism::new(threads, 7).with_min_len(0).for_each_chunk(
+                &mut data,
+                |offset, chunk| {
+                    for (idx, value) in chunk.iter_mut().enumerate() {
+                        *value += offset + idx;
+                    }
+                },
+            );
+            assert!(data.iter().enumerate().all(|(idx, value)| idx == *value));
+        }
+    }
+
+    #[test]
+    fn test_reduce_is_deterministic() {
+        let data = (0..100_000).map(|x| (x as f32).sin()).collect::<Vec<_>>();
+        let sum = |threads| {
+            Parallelism::new(threads, 333).with_min_len(0).reduce(
+                &data,
+                |chunk| chunk.iter().sum::<f32>(),
+                |a, b| a + b,
+            )
+        };
+
+        let expected = sum(1).unwrap();
+        for threads in 2..9 {
+            assert_eq!(sum(threads).unwrap().to_bits(), expected.to_bits());
+        }
+        assert_eq!(
+            Parallelism::new(4, 10).reduce(&[] as &[f32], |_| 0., |a, b| a + b),
+            None
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_work_runs_on_the_pool() {
+        let main_thread = std::thread::current().id();
+        let on_main = |parallelism: &Parallelism, len| {
+            parallelism
+                .reduce(
+                    &vec![0u8; len],
+                    |_| std::thread::current().id() == main_thread,
+                    |a, b| a && b,
+                )
+                .unwrap()
+        };
+
+        let parallelism = Parallelism::new(2, 4).with_min_len(64);
+        assert!(on_main(&parallelism, 63));
+        assert!(!on_main(&parallelism, 64));
+        assert!(on_main(&Parallelism::serial().with_min_len(0), 64));
+    }
+}
@@ -1,12 +1,12 @@
-#[cfg(any(feature = "cpu", feature = "stack"))]
-use core::ops::AddAssign;
-
 //#[cfg(any(feature = "cpu", feature = "stack"))]
 use custos_macro::impl_stack;
 
-use crate::MayToCLSource;
 #[cfg(any(feature = "cpu", feature = "stack"))]
-use crate::{ApplyFunction, Buffer, Device, Eval, MainMemory, Resolve, Shape, ToVal, UnaryGrad};
+use crate::{
+    number::{CastAs, Float, Number},
+    random::Distribution,
+    Buffer, CastBuf, Device, FillBuf, MainMemory, RandBuf, Shape,
+};
 
 #[cfg(feature = "cpu")]
 use crate::CPU;
@@ -15,20 +15,18 @@ use crate::CPU;
 use crate::Stack;
 
 #[impl_stack]
-impl<T, D, S> ApplyFunction<T, S, D> for CPU
+impl<T, U, D, S> CastBuf<T, U, S, D> for CPU
 where
-    T: Copy + Default + ToVal,
-    D: crate::MainMemory,
+    T: CastAs<U> + Copy,
+    U: Copy + Default,
+    D: MainMemory,
     S: Shape,
 {
-    fn apply_fn<F>(&self, buf: &Buffer<T, D, S>, f: impl Fn(Resolve<T>) -> F) -> Buffer<T, Self, S>
-    where
-        F: Eval<T> + MayToCLSource,
-    {
-        let mut out = self.retrieve::<T, S>(buf.len(), buf);
+    fn cast(&self, buf: &Buffer<T, D, S>) -> Buffer<U, Self, S> {
+        let mut out = self.retrieve::<U, S>(buf.len(), buf);
 
         for (value, x) in out.iter_mut().zip(buf.iter()) {
-            *value = f((*x).to_val()).eval()
+            *value = x.cast_as();
         }
 
         out
@@ -36,23 +34,46 @@ where
 }
 
 #[impl_stack]
-impl<T, D, S> UnaryGrad<T, S, D> for CPU
+impl<T, D, S> RandBuf<T, S, D> for CPU
 where
-    T: AddAssign + Copy + std::ops::Mul<Output = T>,
+    T: Float,
+    D: MainMemory,
     S: Shape,
+{
+    fn rand(&self, buf: &mut Buffer<T, D, S>, seed: u64, dist: Distribution<T>) {
+        for (idx, value) in buf.iter_mut().enumerate() {
+            *value = dist.sample(seed, idx);
+        }
+    }
+}
+
+#[impl_stack]
+impl<T, D, S> FillBuf<T, S, D> for CPU
+where
+    T: Number,
     D: MainMemory,
+    S: Shape,
 {
-    fn add_unary_grad<F>(
-        &self,
-        lhs: &Buffer<T, D, S>,
-        lhs_grad: &mut Buffer<T, D, S>,
-        out: &Buffer<T, D, S>,
-        lhs_grad_fn: impl Fn(Resolve<T>) -> F,
-    ) where
-        F: Eval<T> + MayToCLSource,
-    {
-        for ((lhs, lhs_grad), out) in lhs.iter().zip(lhs_grad.iter_mut()).zip(out.iter()) {
-            *lhs_grad += *out * lhs_grad_fn((*lhs).to_val()).eval();
+    #[inline]
+    fn fill(&self, buf: &mut Buffer<T, D, S>, value: T) {
+        for x in buf.iter_mut() {
+            *x = value;
+        }
+    }
+
+    fn fill_range(&self, buf: &mut Buffer<T, D, S>, start: T, step: T) {
+        for (idx, x) in buf.iter_mut().enumerate() {
+            *x = start + T::from_usize(idx) * step;
+        }
+    }
+
+    fn fill_eye(&self, buf: &mut Buffer<T, D, S>, cols: usize) {
+        for (idx, x) in buf.iter_mut().enumerate() {
+            *x = if idx / cols == idx % cols {
+                T::one()
+            } else {
+                T::zero()
+            };
         }
     }
 }
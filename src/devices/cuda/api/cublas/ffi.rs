@@ -38,6 +38,23 @@ pub enum cublasOperation_t {
     CUBLAS_OP_C = 2,
 }
 
+#[repr(u32)]
+pub enum cudaDataType {
+    CUDA_R_32F = 0,
+    CUDA_R_16F = 2,
+    CUDA_R_16BF = 14,
+}
+
+#[repr(u32)]
+pub enum cublasComputeType_t {
+    CUBLAS_COMPUTE_32F = 68,
+}
+
+#[repr(i32)]
+pub enum cublasGemmAlgo_t {
+    CUBLAS_GEMM_DEFAULT = -1,
+}
+
 #[link(name = "cublas")]
 extern "C" {
     pub fn cublasCreate_v2(handle: *mut cublasHandle_t) -> cublasStatus_t;
@@ -76,6 +93,28 @@ extern "C" {
         ldc: i32,
     ) -> cublasStatus_t;
 
+    pub fn cublasGemmEx(
+        handle: cublasHandle_t,
+        transa: cublasOperation_t,
+        transb: cublasOperation_t,
+        m: i32,
+        n: i32,
+        k: i32,
+        alpha: *const std::ffi::c_void,
+        A: *const std::ffi::c_void,
+        Atype: cudaDataType,
+        lda: i32,
+        B: *const std::ffi::c_void,
+        Btype: cudaDataType,
+        ldb: i32,
+        beta: *const std::ffi::c_void,
+        C: *mut std::ffi::c_void,
+        Ctype: cudaDataType,
+        ldc: i32,
+        computeType: cublasComputeType_t,
+        algo: cublasGemmAlgo_t,
+    ) -> cublasStatus_t;
+
     pub fn cublasSgeam(
         handle: cublasHandle_t,
         transa: cublasOperation_t,
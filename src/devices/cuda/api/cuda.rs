@@ -1,10 +1,12 @@
 use super::{
-    cuCtxCreate_v2, cuCtxDestroy, cuDeviceGet, cuDeviceGetCount, cuInit, cuLaunchKernel,
-    cuMemFree_v2, cuMemcpyDtoH_v2, cuMemcpyHtoD_v2, cuModuleGetFunction, cuModuleLoad,
-    cuModuleLoadData, cuModuleUnload, cuStreamCreate, cuStreamSynchronize,
+    cuCtxCreate_v2, cuCtxDestroy, cuDeviceGet, cuDeviceGetAttribute, cuDeviceGetCount,
+    cuDeviceGetName, cuDriverGetVersion, cuInit, cuLaunchKernel, cuMemFree_v2,
+    cuMemcpyDtoHAsync_v2, cuMemcpyDtoH_v2, cuMemcpyHtoDAsync_v2, cuMemcpyHtoD_v2,
+    cuModuleGetFunction, cuModuleLoad, cuModuleLoadData, cuModuleUnload, cuStreamCreate,
+    cuStreamQuery, cuStreamSynchronize,
     error::{CudaErrorKind, CudaResult},
     ffi::cuMemAlloc_v2,
-    CUcontext, CUdevice, CUfunction, CUmodule, CUstream,
+    CUcontext, CUdevice, CUdevice_attribute, CUfunction, CUmodule, CUresult, CUstream,
 };
 
 use std::{
@@ -21,6 +23,37 @@ pub type CUdeviceptr = core::ffi::c_ulonglong;
 #[derive(Debug)]
 pub struct CudaIntDevice(pub CUdevice);
 
+impl CudaIntDevice {
+    pub fn name(&self) -> CudaResult<String> {
+        let mut name = [0u8; 256];
+        unsafe { cuDeviceGetName(name.as_mut_ptr().cast(), name.len() as i32, self.0) }
+            .to_result()?;
+
+        let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+        Ok(String::from_utf8_lossy(&name[..len]).into_owned())
+    }
+
+    pub fn attribute(&self, attribute: CUdevice_attribute) -> CudaResult<i32> {
+        let mut value = 0;
+        unsafe { cuDeviceGetAttribute(&mut value, attribute, self.0) }.to_result()?;
+        Ok(value)
+    }
+
+    /// Returns the major and minor compute capability.
+    pub fn compute_capability(&self) -> CudaResult<(i32, i32)> {
+        Ok((
+            self.attribute(CUdevice_attribute::CU_DEVICE_ATTRIBUTE_COMPUTE_CAPABILITY_MAJOR)?,
+            self.attribute(CUdevice_attribute::CU_DEVICE_ATTRIBUTE_COMPUTE_CAPABILITY_MINOR)?,
+        ))
+    }
+}
+
+pub fn driver_version() -> CudaResult<i32> {
+    let mut version = 0;
+    unsafe { cuDriverGetVersion(&mut version) }.to_result()?;
+    Ok(version)
+}
+
 pub fn device_count() -> CudaResult<i32> {
     let mut count = 0;
     unsafe { cuDeviceGetCount(&mut count as *mut i32) }.to_result()?;
@@ -87,6 +120,40 @@ pub fn cu_read<T>(dst_host: &mut [T], src: CUdeviceptr) -> CudaResult<()> {
     unsafe { cuMemcpyDtoH_v2(dst_host.as_mut_ptr() as *mut c_void, src, bytes_to_copy) }.into()
 }
 
+/// # Safety
+/// `src_host` must stay valid until all work on the `stream` is complete.
+pub unsafe fn cu_write_async<T>(
+    dst: CUdeviceptr,
+    src_host: &[T],
+    stream: &Stream,
+) -> CudaResult<()> {
+    let bytes_to_copy = std::mem::size_of_val(src_host);
+    cuMemcpyHtoDAsync_v2(
+        dst,
+        src_host.as_ptr() as *const c_void,
+        bytes_to_copy,
+        stream.0,
+    )
+    .into()
+}
+
+/// # Safety
+/// `dst_host` must stay valid until all work on the `stream` is complete.
+pub unsafe fn cu_read_async<T>(
+    dst_host: &mut [T],
+    src: CUdeviceptr,
+    stream: &Stream,
+) -> CudaResult<()> {
+    let bytes_to_copy = std::mem::size_of_val(dst_host);
+    cuMemcpyDtoHAsync_v2(
+        dst_host.as_mut_ptr() as *mut c_void,
+        src,
+        bytes_to_copy,
+        stream.0,
+    )
+    .into()
+}
+
 #[derive(Debug)]
 pub struct Module(pub CUmodule);
 
@@ -136,6 +203,14 @@ impl Stream {
     pub fn sync(&self) -> CudaResult<()> {
         unsafe { cuStreamSynchronize(self.0) }.to_result()
     }
+
+    /// Returns `true` if all work on the stream is complete. This does not block.
+    pub fn is_complete(&self) -> CudaResult<bool> {
+        match unsafe { cuStreamQuery(self.0) } {
+            CUresult::CUDA_ERROR_NOT_READY => Ok(false),
+            result => result.to_result().map(|_| true),
+        }
+    }
 }
 
 pub fn create_stream() -> CudaResult<Stream> {
@@ -31,6 +31,8 @@ pub enum CUdevice_attribute {
     CU_DEVICE_ATTRIBUTE_MAX_GRID_DIM_Y = 6,
     CU_DEVICE_ATTRIBUTE_MAX_GRID_DIM_Z = 7,
     CU_DEVICE_ATTRIBUTE_UNIFIED_ADDRESSING = 41,
+    CU_DEVICE_ATTRIBUTE_COMPUTE_CAPABILITY_MAJOR = 75,
+    CU_DEVICE_ATTRIBUTE_COMPUTE_CAPABILITY_MINOR = 76,
 }
 
 #[repr(u32)]
@@ -138,6 +140,8 @@ extern "C" {
     pub fn cuInit(flags: u32) -> CUresult;
     pub fn cuDeviceGetCount(count: *mut i32) -> CUresult;
     pub fn cuDeviceGet(device: *mut CUdevice, ordinal: i32) -> CUresult;
+    pub fn cuDeviceGetName(name: *mut c_char, len: i32, device: CUdevice) -> CUresult;
+    pub fn cuDriverGetVersion(driver_version: *mut i32) -> CUresult;
     pub fn cuDeviceGetAttribute(
         pi: *mut i32,
         attrib: CUdevice_attribute,
@@ -161,6 +165,18 @@ extern "C" {
         src_device: CUdeviceptr,
         bytes_to_copy: usize,
     ) -> CUresult;
+    pub fn cuMemcpyHtoDAsync_v2(
+        dst_device: CUdeviceptr,
+        src_host: *const c_void,
+        bytes_to_copy: usize,
+        stream: CUstream,
+    ) -> CUresult;
+    pub fn cuMemcpyDtoHAsync_v2(
+        dst_host: *mut c_void,
+        src_device: CUdeviceptr,
+        bytes_to_copy: usize,
+        stream: CUstream,
+    ) -> CUresult;
     pub fn cuModuleLoad(module: *mut CUmodule, fname: *const c_char) -> CUresult;
     pub fn cuModuleLoadData(module: *mut CUmodule, data: *const c_void) -> CUresult;
     pub fn cuModuleGetFunction(
@@ -185,6 +201,7 @@ extern "C" {
     pub fn cuStreamCreate(ph_stream: *mut CUstream, flags: u32) -> CUresult;
     pub fn cuStreamDestroy(hstream: CUstream) -> CUresult;
     pub fn cuStreamSynchronize(stream: CUstream) -> CUresult;
+    pub fn cuStreamQuery(stream: CUstream) -> CUresult;
     pub fn cuOccupancyMaxPotentialBlockSize(
         min_grid_size: *mut i32,
         block_size: *mut i32,
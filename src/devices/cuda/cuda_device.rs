@@ -11,8 +11,8 @@ use super::{
 };
 
 use crate::{
-    cache::Cache, flag::AllocFlag, Addons, AddonsReturn, Alloc, Buffer, CacheReturn, CloneBuf,
-    Device, PtrConv, Shape,
+    cache::Cache, flag::AllocFlag, Addons, AddonsReturn, Alloc, Buffer, BuildOptions, CacheReturn,
+    CloneBuf, Device, PtrConv, Shape,
 };
 
 /// Used to perform calculations with a CUDA capable device.
@@ -58,6 +58,21 @@ impl CUDA {
         })
     }
 
+    /// Returns the build options that are used for every kernel of this device.
+    #[inline]
+    pub fn build_options(&self) -> BuildOptions {
+        self.kernel_cache.borrow().build_options().clone()
+    }
+
+    /// Sets the build options that are used for every kernel of this device.
+    /// Kernels launched afterwards are recompiled with the new options.
+    #[inline]
+    pub fn set_build_options(&self, build_options: BuildOptions) {
+        self.kernel_cache
+            .borrow_mut()
+            .set_build_options(build_options)
+    }
+
     /// Returns the internal CUDA device.
     #[inline]
     pub fn device(&self) -> &CudaIntDevice {
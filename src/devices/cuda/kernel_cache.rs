@@ -1,19 +1,64 @@
 use super::api::{
-    load_module_data,
+    driver_version, load_module_data,
     nvrtc::{create_program, nvrtcDestroyProgram},
-    FnHandle,
+    FnHandle, Module,
+};
+use crate::{
+    BuildOptions, CompileError, Error, KernelCompiler, KernelDiskCache, KernelKey, KernelLang,
+    KernelTemplate, TemplateCache, CUDA,
 };
-use crate::{Error, CUDA};
 use std::{collections::HashMap, ffi::CString};
 
+#[cfg(feature = "half")]
+use crate::{devices::kernel_source::contains_ident, CDatatype};
+
 /// This stores the previously compiled CUDA functions / kernels.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct KernelCacheCU {
-    /// Uses the kernel source code to retrieve the corresponding `FnHandle`.
-    pub kernels: HashMap<String, FnHandle>,
+    /// Uses the kernel source code and the function name to retrieve the corresponding `FnHandle`.
+    /// Contains the kernels compiled with the build options of the cache, see [`KernelCacheCU::kernel`].
+    pub kernels: HashMap<String, HashMap<String, FnHandle>>,
+    /// Contains the kernels compiled with per-call build options, see [`KernelCacheCU::kernel_with`].
+    pub custom_kernels: HashMap<KernelKey, FnHandle>,
+    /// The index of the module in [`CUDA::modules`] by source code and compile arguments.
+    /// Kernels of the same module share it.
+    modules: HashMap<(String, String), usize>,
+    build_options: BuildOptions,
+    /// The instantiated sources of [`KernelTemplate`]s, see [`KernelCacheCU::template_kernel`].
+    pub templates: TemplateCache,
+    /// If set, the PTX of the kernels is stored on disk and reused by other processes.
+    /// By default, this is configured with [`KernelDiskCache::from_env`].
+    pub disk_cache: Option<KernelDiskCache>,
+}
+
+impl Default for KernelCacheCU {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            kernels: HashMap::new(),
+            custom_kernels: HashMap::new(),
+            modules: HashMap::new(),
+            build_options: BuildOptions::new().fast_math(true),
+            templates: TemplateCache::new(KernelLang::CUDA),
+            disk_cache: KernelDiskCache::from_env(),
+        }
+    }
 }
 
 impl KernelCacheCU {
+    /// The build options used for every kernel. Defaults to `--use_fast_math`.
+    #[inline]
+    pub fn build_options(&self) -> &BuildOptions {
+        &self.build_options
+    }
+
+    /// Sets the build options used for every kernel.
+    /// Kernels compiled with the previous options are not returned by [`KernelCacheCU::kernel`] anymore.
+    pub fn set_build_options(&mut self, build_options: BuildOptions) {
+        self.kernels.clear();
+        self.build_options = build_options;
+    }
+
     /// Returns a cached kernel. If the kernel source code does not exist, a new kernel is created and cached.
     ///
     /// # Example
@@ -38,25 +83,183 @@ impl KernelCacheCU {
     /// }
     /// ```
     pub fn kernel(&mut self, device: &CUDA, src: &str, fn_name: &str) -> Result<FnHandle, Error> {
-        let kernel = self.kernels.get(src);
+        let kernel = self
+            .kernels
+            .get(src)
+            .and_then(|functions| functions.get(fn_name));
 
         if let Some(kernel) = kernel {
             return Ok(*kernel);
         }
 
-        let mut x = create_program(src, "")?;
+        let function =
+            self.build_function(device, src, fn_name, self.build_options.nvrtc_args())?;
 
-        x.compile(Some(vec![CString::new("--use_fast_math").unwrap()]))?;
+        self.kernels
+            .entry(src.into())
+            .or_default()
+            .insert(fn_name.into(), function);
+        Ok(function)
+    }
 
-        let module = load_module_data(x.ptx()?)?;
-        let function = module.function(fn_name)?;
+    /// Returns a cached kernel, which is compiled with the build options of the cache merged with `options` (see [`BuildOptions::merge`]).
+    ///
+    /// # Example
+    /// ```
+    /// use custos::{BuildOptions, CUDA, cuda::KernelCacheCU};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = CUDA::new(0)?;
+    ///     let mut kernel_cache = KernelCacheCU::default();
+    ///
+    ///     let src = r#"
+    ///         extern "C" __global__ void fill(float* out) { out[threadIdx.x] = VALUE; }
+    ///         extern "C" __global__ void clear(float* out) { out[threadIdx.x] = 0; }
+    ///     "#;
+    ///     let options = BuildOptions::new().define("VALUE", 3.0);
+    ///
+    ///     let fill = kernel_cache.kernel_with(&device, src, "fill", &options)?.0;
+    ///     let clear = kernel_cache.kernel_with(&device, src, "clear", &options)?.0;
+    ///     assert_ne!(fill, clear);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn kernel_with(
+        &mut self,
+        device: &CUDA,
+        src: &str,
+        fn_name: &str,
+        options: &BuildOptions,
+    ) -> Result<FnHandle, Error> {
+        let args = self.build_options.merge(options).nvrtc_args();
+        let key = KernelKey {
+            src: src.into(),
+            entry: Some(fn_name.into()),
+            args: args.join(" "),
+        };
 
-        device.modules.borrow_mut().push(module);
+        if let Some(kernel) = self.custom_kernels.get(&key) {
+            return Ok(*kernel);
+        }
 
-        self.kernels.insert(src.into(), function);
-        unsafe { nvrtcDestroyProgram(&mut x.0).to_result()? };
+        let function = self.build_function(device, src, fn_name, args)?;
+        self.custom_kernels.insert(key, function);
         Ok(function)
     }
+
+    /// Returns a cached kernel of the template instantiated for CUDA (see [`KernelTemplate`]).
+    /// The instantiated source is cached as well, hence the template is only rendered once.
+    pub fn template_kernel(
+        &mut self,
+        device: &CUDA,
+        template: &KernelTemplate,
+        fn_name: &str,
+    ) -> Result<FnHandle, Error> {
+        let src = self.templates.source(template)?.to_string();
+        self.kernel(device, &src, fn_name)
+    }
+
+    /// Returns the function `fn_name` of a module, which is compiled if it was not compiled with the same arguments before.
+    fn build_function(
+        &mut self,
+        device: &CUDA,
+        src: &str,
+        fn_name: &str,
+        args: Vec<String>,
+    ) -> Result<FnHandle, Error> {
+        let key = (src.to_string(), args.join(" "));
+
+        let idx = match self.modules.get(&key) {
+            Some(idx) => *idx,
+            None => {
+                let compiler = PtxCompiler {
+                    device,
+                    args: &args,
+                };
+                let module = match &self.disk_cache {
+                    Some(disk_cache) => disk_cache.load_or_compile(&compiler, src)?,
+                    None => compiler.compile(src)?.0,
+                };
+
+                let mut modules = device.modules.borrow_mut();
+                modules.push(module);
+                self.modules.insert(key, modules.len() - 1);
+                modules.len() - 1
+            }
+        };
+
+        Ok(device.modules.borrow()[idx].function(fn_name)?)
+    }
+}
+
+/// Compiles CUDA source code to PTX with NVRTC. The PTX is used as binary representation of a module.
+struct PtxCompiler<'a> {
+    device: &'a CUDA,
+    args: &'a [String],
+}
+
+impl KernelCompiler for PtxCompiler<'_> {
+    type Artifact = Module;
+
+    fn identity(&self) -> String {
+        let device = self.device.device();
+        let (major, minor) = device.compute_capability().unwrap_or_default();
+        format!(
+            "cuda|{}|sm_{major}{minor}|{}|{}",
+            device.name().unwrap_or_default(),
+            driver_version().unwrap_or_default(),
+            self.args.join(" ")
+        )
+    }
+
+    /// Compiles the source code to PTX. If the compilation fails, the returned [`CompileError`] contains the log of NVRTC.
+    fn compile(&self, src: &str) -> crate::Result<(Module, Vec<u8>)> {
+        let mut x = create_program(&with_includes(src), "")?;
+
+        let options = self
+            .args
+            .iter()
+            .map(|arg| CString::new(arg.as_str()).unwrap())
+            .collect();
+
+        if let Err(e) = x.compile(Some(options)) {
+            let log = x.log().unwrap_or_default();
+            unsafe { nvrtcDestroyProgram(&mut x.0).to_result()? };
+
+            return Err(CompileError {
+                reason: e.to_string(),
+                log,
+            }
+            .into());
+        }
+
+        let ptx = x.ptx()?;
+        unsafe { nvrtcDestroyProgram(&mut x.0).to_result()? };
+
+        let binary = ptx.as_bytes().to_vec();
+        Ok((load_module_data(ptx)?, binary))
+    }
+
+    fn load(&self, binary: &[u8]) -> crate::Result<Module> {
+        Ok(load_module_data(CString::new(binary)?)?)
+    }
+}
+
+/// Includes the half precision headers if the kernel source uses the element type of `half::f16` or `half::bf16`.
+fn with_includes(src: &str) -> String {
+    #[cfg_attr(not(feature = "half"), allow(unused_mut))]
+    let mut includes = String::new();
+
+    #[cfg(feature = "half")]
+    for (c_type, header) in [
+        (half::f16::as_c_type_str(), "cuda_fp16.h"),
+        (half::bf16::as_c_type_str(), "cuda_bf16.h"),
+    ] {
+        if contains_ident(src, c_type) && !src.contains(header) {
+            includes.push_str(&format!("#include <{header}>\n"));
+        }
+    }
+    includes + src
 }
 
 /// Exactly like [`KernelCacheCU`], but with a immutable source of the cache using interior mutability.
@@ -66,3 +269,48 @@ pub fn fn_cache(device: &CUDA, src: &str, fn_name: &str) -> crate::Result<FnHand
         .borrow_mut()
         .kernel(device, src, fn_name)
 }
+
+/// Exactly like [`KernelCacheCU::kernel_with`], but with a immutable source of the cache using interior mutability.
+pub fn fn_cache_with(
+    device: &CUDA,
+    src: &str,
+    fn_name: &str,
+    options: &BuildOptions,
+) -> crate::Result<FnHandle> {
+    device
+        .kernel_cache
+        .borrow_mut()
+        .kernel_with(device, src, fn_name, options)
+}
+
+/// Exactly like [`KernelCacheCU::template_kernel`], but with a immutable source of the cache using interior mutability.
+/// # Example
+/// ```
+/// use custos::{Buffer, CUDA, KernelTemplate, Read, cuda::{api::culaunch_kernel, fn_cache_template}};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = CUDA::new(0)?;
+///     let buf = Buffer::<u32, _>::new(&device, 4);
+///
+///     let template = KernelTemplate::new(r#"
+///         extern "C" __global__ void fill(${T}* x) { x[threadIdx.x] = ${VALUE}; }
+///     "#)
+///     .ty::<u32>("T")
+///     .value("VALUE", 7);
+///
+///     let fill = fn_cache_template(&device, &template, "fill")?;
+///     culaunch_kernel(&fill, [1, 1, 1], [4, 1, 1], 0, &mut device.stream(), &mut [&buf.ptrs().2 as *const u64 as *mut _])?;
+///     assert_eq!(device.read(&buf), [7; 4]);
+///     Ok(())
+/// }
+/// ```
+pub fn fn_cache_template(
+    device: &CUDA,
+    template: &KernelTemplate,
+    fn_name: &str,
+) -> crate::Result<FnHandle> {
+    device
+        .kernel_cache
+        .borrow_mut()
+        .template_kernel(device, template, fn_name)
+}
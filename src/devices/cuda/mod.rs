@@ -16,7 +16,15 @@ pub use cuda_device::*;
 pub use kernel_cache::*;
 pub use kernel_launch::*;
 
-use crate::{flag::AllocFlag, Buffer, CDatatype, CommonPtrs, PtrType, ShallowCopy};
+use crate::{
+    devices::kernel_source::complex_prelude,
+    flag::AllocFlag,
+    number::{Float, Number},
+    optim::{scalar_markers, SCALAR_MARKERS},
+    random::{philox_c_source, seed_key, Distribution},
+    Buffer, CDatatype, CommonPtrs, Device, KernelLang, PtrType, Resolve, ShallowCopy, ToCLSource,
+    ToMarker,
+};
 
 use self::api::cufree;
 
@@ -129,7 +137,7 @@ pub fn cu_clear<T: CDatatype>(device: &CUDA, buf: &mut Buffer<T, CUDA>) -> crate
             {{
                 int idx = blockDim.x * blockIdx.x + threadIdx.x;
                 if (idx < numElements) {{
-                    self[idx] = 0;
+                    self[idx] = {datatype}();
                 }}
                 
             }}
@@ -140,13 +148,235 @@ pub fn cu_clear<T: CDatatype>(device: &CUDA, buf: &mut Buffer<T, CUDA>) -> crate
     Ok(())
 }
 
+/// Casts the elements of a CUDA `Buffer` to another type.
+/// # Example
+/// ```
+/// use custos::{CUDA, Buffer, Read, cuda::cu_cast};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = CUDA::new(0)?;
+///     let buf = Buffer::from((&device, [1.5f32, 0., -3.2]));
+///
+///     let casted = cu_cast::<_, i32>(&device, &buf)?;
+///     assert_eq!(device.read(&casted), vec![1, 0, -3]);
+///     Ok(())
+/// }
+/// ```
+pub fn cu_cast<'a, T, U>(
+    device: &'a CUDA,
+    x: &Buffer<T, CUDA>,
+) -> crate::Result<Buffer<'a, U, CUDA>>
+where
+    T: CDatatype,
+    U: CDatatype,
+{
+    let operation = if U::as_c_type_str() == "bool" {
+        "x[idx] != 0".to_string()
+    } else {
+        format!("({}) x[idx]", U::as_c_type_str())
+    };
+
+    let src = format!(
+        r#"extern "C" __global__ void cast({datatype}* x, {out_datatype}* out, int numElements)
+            {{
+                int idx = blockDim.x * blockIdx.x + threadIdx.x;
+                if (idx < numElements) {{
+                    out[idx] = {operation};
+                }}
+            }}
+    "#,
+        datatype = T::as_c_type_str(),
+        out_datatype = U::as_c_type_str(),
+    );
+
+    let out = device.retrieve::<U, ()>(x.len(), x);
+    launch_kernel1d(x.len(), device, &src, "cast", &[x, &out, &x.len()])?;
+    Ok(out)
+}
+
+/// Sets the i-th element of a CUDA `Buffer` to `start + i * step`.
+/// # Example
+/// ```
+/// use custos::{CUDA, Buffer, Read, cuda::cu_fill_range};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = CUDA::new(0)?;
+///     let mut buf = Buffer::<i32, _>::new(&device, 4);
+///
+///     cu_fill_range(&device, &mut buf, 3, 2)?;
+///     assert_eq!(device.read(&buf), vec![3, 5, 7, 9]);
+///     Ok(())
+/// }
+/// ```
+pub fn cu_fill_range<T: CDatatype + Number>(
+    device: &CUDA,
+    buf: &mut Buffer<T, CUDA>,
+    start: T,
+    step: T,
+) -> crate::Result<()> {
+    let src = format!(
+        r#"extern "C" __global__ void fill_range({datatype}* out, {datatype} start, {datatype} step, int numElements)
+            {{
+                int idx = blockDim.x * blockIdx.x + threadIdx.x;
+                if (idx < numElements) {{
+                    out[idx] = start + ({datatype}) idx * step;
+                }}
+            }}
+    "#,
+        datatype = T::as_c_type_str()
+    );
+    launch_kernel1d(
+        buf.len(),
+        device,
+        &src,
+        "fill_range",
+        &[buf, &start, &step, &buf.len()],
+    )?;
+    Ok(())
+}
+
+/// Sets the elements on the diagonal of a row-major CUDA matrix with `cols` columns to one and all other elements to zero.
+pub fn cu_fill_eye<T: CDatatype>(
+    device: &CUDA,
+    buf: &mut Buffer<T, CUDA>,
+    cols: usize,
+) -> crate::Result<()> {
+    let src = format!(
+        r#"extern "C" __global__ void fill_eye({datatype}* out, int numElements)
+            {{
+                int idx = blockDim.x * blockIdx.x + threadIdx.x;
+                if (idx < numElements) {{
+                    out[idx] = idx / {cols} == idx % {cols} ? 1 : 0;
+                }}
+            }}
+    "#,
+        datatype = T::as_c_type_str()
+    );
+    launch_kernel1d(buf.len(), device, &src, "fill_eye", &[buf, &buf.len()])?;
+    Ok(())
+}
+
+/// Updates a CUDA `Buffer` in place with the result of `f`, which is called with the values of `buf`, `lhs`, `rhs` and the `scalars`.
+/// The scalars are passed as kernel arguments, hence the kernel is only compiled once for different values.
+/// # Example
+/// ```
+/// use custos::{CUDA, Buffer, Combiner, Read, cuda::try_cu_update_fn};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = CUDA::new(0)?;
+///     let mut buf = Buffer::from((&device, [1., 2., 3.]));
+///     let lhs = Buffer::from((&device, [2., 2., 2.]));
+///     let rhs = Buffer::from((&device, [1., 0., -1.]));
+///
+///     try_cu_update_fn(&device, &mut buf, (&lhs, &rhs), [0.5], |x, lhs, rhs, [scale]| {
+///         x.mul(lhs).add(rhs).mul(scale)
+///     })?;
+///     assert_eq!(device.read(&buf), vec![1.5, 2., 2.5]);
+///     Ok(())
+/// }
+/// ```
+pub fn try_cu_update_fn<T, F, const N: usize>(
+    device: &CUDA,
+    buf: &mut Buffer<T, CUDA>,
+    (lhs, rhs): (&Buffer<T, CUDA>, &Buffer<T, CUDA>),
+    scalars: [T; N],
+    f: impl Fn(Resolve<T>, Resolve<T>, Resolve<T>, [Resolve<T>; N]) -> F,
+) -> crate::Result<()>
+where
+    T: CDatatype + Number,
+    F: ToCLSource,
+{
+    let datatype = T::as_c_type_str();
+    let scalar_params = SCALAR_MARKERS[..N]
+        .iter()
+        .map(|marker| format!("{datatype} {marker}, "))
+        .collect::<String>();
+
+    let operation = f(
+        "buf[idx]".to_marker(),
+        "lhs[idx]".to_marker(),
+        "rhs[idx]".to_marker(),
+        scalar_markers(),
+    );
+    let src = format!(
+        r#"{complex_helpers}
+        extern "C" __global__ void update_fn({datatype}* buf, const {datatype}* lhs, const {datatype}* rhs, {scalar_params}int numElements)
+            {{
+                int idx = blockDim.x * blockIdx.x + threadIdx.x;
+                if (idx < numElements) {{
+                    buf[idx] = {operation};
+                }}
+            }}
+    "#,
+        complex_helpers = complex_prelude(operation.complex_c_type(), KernelLang::CUDA),
+        operation = operation.to_cl_source()
+    );
+
+    let len = buf.len();
+    let mut args: Vec<&dyn AsCudaCvoidPtr> = vec![&*buf, lhs, rhs];
+    args.extend(scalars.iter().map(|scalar| scalar as &dyn AsCudaCvoidPtr));
+    args.push(&len);
+
+    launch_kernel1d(len, device, &src, "update_fn", &args)?;
+    Ok(())
+}
+
+/// Fills a CUDA `Buffer` with values sampled from the [`Distribution`].
+/// # Example
+/// ```
+/// use custos::{CUDA, Buffer, Read, random::{self, Distribution}, cuda::cu_rand};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = CUDA::new(0)?;
+///     let mut buf = Buffer::<f32, _>::new(&device, 4);
+///
+///     cu_rand(&device, &mut buf, 42, Distribution::Uniform { lo: -1., hi: 1. })?;
+///     assert_eq!(device.read(&buf)[3], random::uniform(42, 3, -1., 1.));
+///     Ok(())
+/// }
+/// ```
+pub fn cu_rand<T: CDatatype + Float>(
+    device: &CUDA,
+    buf: &mut Buffer<T, CUDA>,
+    seed: u64,
+    dist: Distribution<T>,
+) -> crate::Result<()> {
+    let src = format!(
+        r#"{philox}
+        extern "C" __global__ void rand({datatype}* out, unsigned int k0, unsigned int k1, {datatype} a, {datatype} b, int numElements)
+            {{
+                int idx = blockDim.x * blockIdx.x + threadIdx.x;
+                if (idx < numElements) {{
+                    unsigned int bits[4] = {{(unsigned int) idx, 0, 0, 0}};
+                    philox4x32(bits, k0, k1);
+                    out[idx] = {sample};
+                }}
+            }}
+    "#,
+        philox = philox_c_source("unsigned int", "unsigned long long", "__device__"),
+        datatype = T::as_c_type_str(),
+        sample = dist.sample_src(T::as_c_type_str(), false),
+    );
+
+    let [k0, k1] = seed_key(seed);
+    let (a, b) = dist.params();
+    launch_kernel1d(
+        buf.len(),
+        device,
+        &src,
+        "rand",
+        &[buf, &k0, &k1, &a, &b, &buf.len()],
+    )?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use core::ffi::c_void;
 
     use crate::{
-        cuda::{api::culaunch_kernel, fn_cache},
-        Buffer, Read, CUDA,
+        cuda::{api::culaunch_kernel, fn_cache, fn_cache_with},
+        Buffer, BuildOptions, CompileError, Read, CUDA,
     };
 
     #[test]
@@ -191,4 +421,42 @@ mod tests {
         assert_eq!(&vec![5, 3, 10, 10, 14], &device.read(&c));
         Ok(())
     }
+
+    #[test]
+    fn test_kernel_entries_and_build_options() -> crate::Result<()> {
+        let device = CUDA::new(0)?;
+
+        let src = r#"
+            extern "C" __global__ void fill(int* out) { out[threadIdx.x] = VALUE; }
+            extern "C" __global__ void clear(int* out) { out[threadIdx.x] = 0; }
+        "#;
+
+        // `VALUE` is not defined
+        let error = fn_cache(&device, src, "fill").unwrap_err();
+        assert!(error
+            .downcast_ref::<CompileError>()
+            .unwrap()
+            .log
+            .contains("VALUE"));
+
+        let options = BuildOptions::new().define("VALUE", 3);
+        let fill = fn_cache_with(&device, src, "fill", &options)?;
+        let clear = fn_cache_with(&device, src, "clear", &options)?;
+        assert_ne!(fill.0, clear.0);
+
+        // both functions share a module
+        assert_eq!(device.modules.borrow().len(), 1);
+
+        let out = Buffer::<i32, _>::new(&device, 4);
+        culaunch_kernel(
+            &fill,
+            [1, 1, 1],
+            [out.len() as u32, 1, 1],
+            0,
+            &mut device.stream(),
+            &mut [&out.ptrs().2 as *const u64 as *mut c_void],
+        )?;
+        assert_eq!(device.read(&out), [3; 4]);
+        Ok(())
+    }
 }
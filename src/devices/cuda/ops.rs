@@ -1,13 +1,18 @@
 use core::ops::{Range, RangeBounds};
 
 use crate::{
-    bounds_to_range, cuda::api::cu_read, Buffer, CDatatype, ClearBuf, CopySlice, Read, WriteBuf,
-    CUDA,
+    bounds_to_range,
+    cuda::api::{cu_read, cu_read_async, cu_write_async},
+    number::{Float, Number},
+    optim::UpdateFn,
+    random::Distribution,
+    Buffer, CDatatype, CastBuf, ClearBuf, CopySlice, FillBuf, RandBuf, Read, ReadAsync, Resolve,
+    ToCLSource, Transfer, WriteAsync, WriteBuf, CUDA,
 };
 
 use super::{
     api::{cuMemcpy, cu_write},
-    cu_clear,
+    cu_cast, cu_clear, cu_fill_eye, cu_fill_range, cu_rand, try_cu_update_fn,
 };
 
 impl<T: Default + Clone> Read<T> for CUDA {
@@ -38,6 +43,65 @@ impl<T: Default + Clone> Read<T> for CUDA {
     }
 }
 
+impl<T: Default + Clone> ReadAsync<T> for CUDA {
+    #[inline]
+    fn read_async<'a>(&'a self, buf: &'a Buffer<T, CUDA>) -> Transfer<'a, Vec<T>> {
+        try_cu_read_async(self, buf).unwrap_or_else(|e| Transfer::from_result(Err(e)))
+    }
+}
+
+/// Enqueues a read of a CUDA buffer on the stream of the device.
+/// The returned [`Transfer`] is complete once all work on the stream is complete.
+fn try_cu_read_async<'a, T: Default + Clone>(
+    device: &'a CUDA,
+    buf: &'a Buffer<T, CUDA>,
+) -> crate::Result<Transfer<'a, Vec<T>>> {
+    let mut read = vec![T::default(); buf.len()];
+    // The heap allocation of `read` does not move, even if `read` is moved into the transfer.
+    unsafe { cu_read_async(&mut read, buf.ptr.ptr, device.stream())? };
+
+    let mut read = Some(read);
+    Ok(Transfer::new(move |block| {
+        let complete = match block {
+            true => device.stream().sync().map(|_| true),
+            false => device.stream().is_complete(),
+        };
+        match complete {
+            Ok(true) => Some(Ok(read.take().unwrap())),
+            Ok(false) => None,
+            Err(e) => Some(Err(e.into())),
+        }
+    }))
+}
+
+impl<T: Clone> WriteAsync<T> for CUDA {
+    #[inline]
+    fn write_async<'a>(
+        &'a self,
+        buf: &'a mut Buffer<T, CUDA>,
+        data: &'a [T],
+    ) -> Transfer<'a, ()> {
+        // the data is copied, hence the host memory stays valid even if the transfer is leaked
+        let data = data.to_vec();
+        if let Err(e) = unsafe { cu_write_async(buf.cu_ptr(), &data, self.stream()) } {
+            return Transfer::from_result(Err(e.into()));
+        }
+
+        Transfer::new(move |block| {
+            let _data = &data;
+            let complete = match block {
+                true => self.stream().sync().map(|_| true),
+                false => self.stream().is_complete(),
+            };
+            match complete {
+                Ok(true) => Some(Ok(())),
+                Ok(false) => None,
+                Err(e) => Some(Err(e.into())),
+            }
+        })
+    }
+}
+
 impl<T: CDatatype> ClearBuf<T> for CUDA {
     #[inline]
     fn clear(&self, buf: &mut Buffer<T, CUDA>) {
@@ -45,6 +109,52 @@ impl<T: CDatatype> ClearBuf<T> for CUDA {
     }
 }
 
+impl<T: CDatatype, U: CDatatype> CastBuf<T, U> for CUDA {
+    #[inline]
+    fn cast(&self, buf: &Buffer<T, CUDA>) -> Buffer<U, CUDA> {
+        cu_cast(self, buf).unwrap()
+    }
+}
+
+impl<T: CDatatype + Number> FillBuf<T> for CUDA {
+    #[inline]
+    fn fill(&self, buf: &mut Buffer<T, CUDA>, value: T) {
+        cu_fill_range(self, buf, value, T::zero()).unwrap()
+    }
+
+    #[inline]
+    fn fill_range(&self, buf: &mut Buffer<T, CUDA>, start: T, step: T) {
+        cu_fill_range(self, buf, start, step).unwrap()
+    }
+
+    #[inline]
+    fn fill_eye(&self, buf: &mut Buffer<T, CUDA>, cols: usize) {
+        cu_fill_eye(self, buf, cols).unwrap()
+    }
+}
+
+impl<T: CDatatype + Float> RandBuf<T> for CUDA {
+    #[inline]
+    fn rand(&self, buf: &mut Buffer<T, CUDA>, seed: u64, dist: Distribution<T>) {
+        cu_rand(self, buf, seed, dist).unwrap()
+    }
+}
+
+impl<T: CDatatype + Number> UpdateFn<T> for CUDA {
+    #[inline]
+    fn update_fn<F, const N: usize>(
+        &self,
+        buf: &mut Buffer<T, Self>,
+        inputs: (&Buffer<T, Self>, &Buffer<T, Self>),
+        scalars: [T; N],
+        f: impl Fn(Resolve<T>, Resolve<T>, Resolve<T>, [Resolve<T>; N]) -> F + Sync,
+    ) where
+        F: ToCLSource,
+    {
+        try_cu_update_fn(self, buf, inputs, scalars, f).unwrap();
+    }
+}
+
 impl<T> CopySlice<T> for CUDA {
     fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
         &self,
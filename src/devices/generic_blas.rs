@@ -258,3 +258,87 @@ impl GenericBlas for f64 {
         Ok(())
     }
 }
+
+/// Mixed precision gemm for half precision floats. The products are accumulated in f32.
+#[cfg(feature = "half")]
+macro_rules! half_blas_impl {
+    ($($t:ident: $cuda_ty:ident),*) => {
+        $(
+            impl GenericBlas for half::$t {
+                #[cfg(feature = "blas")]
+                #[cfg(feature = "cpu")]
+                fn blas_gemm(
+                    order: Order,
+                    trans_a: Transpose,
+                    trans_b: Transpose,
+                    m: usize,
+                    n: usize,
+                    k: usize,
+                    a: &[Self],
+                    lda: usize,
+                    b: &[Self],
+                    ldb: usize,
+                    c: &mut [Self],
+                    ldc: usize,
+                ) {
+                    let a = a.iter().map(|x| x.to_f32()).collect::<Vec<_>>();
+                    let b = b.iter().map(|x| x.to_f32()).collect::<Vec<_>>();
+                    let mut c32 = vec![0f32; c.len()];
+
+                    f32::blas_gemm(
+                        order, trans_a, trans_b, m, n, k, &a, lda, &b, ldb, &mut c32, ldc,
+                    );
+
+                    for (c, c32) in c.iter_mut().zip(c32) {
+                        *c = half::$t::from_f32(c32);
+                    }
+                }
+
+                #[cfg(feature = "cuda")]
+                #[inline]
+                fn cugemm(
+                    handle: &CublasHandle,
+                    m: usize,
+                    n: usize,
+                    k: usize,
+                    a: CUdeviceptr,
+                    b: CUdeviceptr,
+                    c: CUdeviceptr,
+                ) -> crate::Result<()> {
+                    use super::cuda::api::cublas::{
+                        cublasComputeType_t, cublasGemmAlgo_t, cublasGemmEx, cudaDataType,
+                    };
+
+                    unsafe {
+                        cublasGemmEx(
+                            handle.0,
+                            cublasOperation_t::CUBLAS_OP_N,
+                            cublasOperation_t::CUBLAS_OP_N,
+                            n as i32,
+                            m as i32,
+                            k as i32,
+                            &1f32 as *const f32 as *const _,
+                            b as *const u64 as *const _,
+                            cudaDataType::$cuda_ty,
+                            n as i32,
+                            a as *const u64 as *const _,
+                            cudaDataType::$cuda_ty,
+                            k as i32,
+                            &0f32 as *const f32 as *const _,
+                            c as *mut u64 as *mut _,
+                            cudaDataType::$cuda_ty,
+                            n as i32,
+                            cublasComputeType_t::CUBLAS_COMPUTE_32F,
+                            cublasGemmAlgo_t::CUBLAS_GEMM_DEFAULT,
+                        )
+                    }
+                    .to_result()?;
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "half")]
+half_blas_impl!(f16: CUDA_R_16F, bf16: CUDA_R_16BF);
@@ -0,0 +1,143 @@
+use core::fmt::Debug;
+
+use crate::{
+    flag::AllocFlag, shape::Shape, Addons, AddonsReturn, Alloc, Buffer, Cache, CloneBuf, Device,
+    DevicelessAble, MainMemory, PtrConv,
+};
+
+use super::{Global, HeapAllocator, HeapPtr};
+
+/// A device that allocates memory on the heap of the host with a [`HeapAllocator`].
+/// In contrast to the [`CPU`](crate::CPU), it only requires `alloc`, hence it can be used in `no-std` environments.
+///
+/// Like the `CPU`, it provides a [`Cache`] and a [`Graph`](crate::Graph), so buffers returned by `retrieve` are reused across iterations.
+///
+/// # Example
+/// ```
+/// use custos::{Buffer, Heap, Read};
+///
+/// let device = Heap::new();
+/// let a = Buffer::from((&device, [1, 2, 3]));
+///
+/// assert_eq!(device.read(&a), [1, 2, 3]);
+/// ```
+pub struct Heap {
+    /// Provides additional functionality for the Heap device. e.g. a cache, a gradient [`Tape`](crate::Tape), an optimizeable [`Graph`](crate::Graph) and a [`Cache`].
+    pub addons: Addons<Heap>,
+    /// The allocator of all buffers of this device.
+    pub allocator: &'static dyn HeapAllocator,
+}
+
+impl Heap {
+    /// Creates a [`Heap`] device that uses the global allocator.
+    #[must_use]
+    pub fn new() -> Heap {
+        Heap::with_allocator(&Global)
+    }
+
+    /// Creates a [`Heap`] device that allocates its buffers with `allocator`, e.g. an arena.
+    #[must_use]
+    pub fn with_allocator(allocator: &'static dyn HeapAllocator) -> Heap {
+        Heap {
+            addons: Addons::default(),
+            allocator,
+        }
+    }
+}
+
+impl Default for Heap {
+    #[inline]
+    fn default() -> Self {
+        Heap::new()
+    }
+}
+
+impl Debug for Heap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Heap")
+            .field("addons", &self.addons)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Device for Heap {
+    type Ptr<U, S: Shape> = HeapPtr<U>;
+    type Cache = Cache<Heap>;
+
+    fn new() -> crate::Result<Self> {
+        Ok(Heap::new())
+    }
+}
+
+impl AddonsReturn for Heap {
+    #[inline]
+    fn addons(&self) -> &Addons<Self> {
+        &self.addons
+    }
+}
+
+impl<'a, T> DevicelessAble<'a, T> for Heap {}
+
+impl<T, S: Shape> Alloc<'_, T, S> for Heap {
+    fn alloc(&self, mut len: usize, flag: AllocFlag) -> HeapPtr<T> {
+        assert!(len > 0, "invalid buffer len: 0");
+
+        if S::LEN > len {
+            len = S::LEN
+        }
+
+        HeapPtr::new_zeroed(len, flag, self.allocator)
+    }
+
+    fn with_slice(&self, data: &[T]) -> HeapPtr<T>
+    where
+        T: Clone,
+    {
+        assert!(!data.is_empty(), "invalid buffer len: 0");
+        assert!(S::LEN <= data.len(), "invalid buffer len: {}", data.len());
+
+        let ptr = HeapPtr::<T>::new_zeroed(data.len(), AllocFlag::None, self.allocator);
+        for (idx, value) in data.iter().enumerate() {
+            // the zeroed memory is not a valid `T` that could be dropped
+            unsafe { ptr.ptr.add(idx).write(value.clone()) };
+        }
+        ptr
+    }
+}
+
+impl PtrConv for Heap {
+    #[inline]
+    unsafe fn convert<T, IS: Shape, Conv, OS: Shape>(
+        ptr: &Self::Ptr<T, IS>,
+        flag: AllocFlag,
+    ) -> Self::Ptr<Conv, OS> {
+        HeapPtr {
+            ptr: ptr.ptr as *mut Conv,
+            len: ptr.len,
+            flag,
+            layout: ptr.layout,
+            allocator: ptr.allocator,
+        }
+    }
+}
+
+impl MainMemory for Heap {
+    #[inline]
+    fn as_ptr<T, S: Shape>(ptr: &Self::Ptr<T, S>) -> *const T {
+        ptr.ptr
+    }
+
+    #[inline]
+    fn as_ptr_mut<T, S: Shape>(ptr: &mut Self::Ptr<T, S>) -> *mut T {
+        ptr.ptr
+    }
+}
+
+impl<'a, T: Clone, S: Shape> CloneBuf<'a, T, S> for Heap {
+    #[inline]
+    fn clone_buf(&'a self, buf: &Buffer<'a, T, Heap, S>) -> Buffer<'a, T, Heap, S> {
+        let mut cloned = Buffer::new(self, buf.len());
+        cloned.clone_from_slice(buf);
+        cloned
+    }
+}
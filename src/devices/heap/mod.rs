@@ -0,0 +1,207 @@
+//! The Heap module provides a host device for `no-std` environments with an allocator.
+//!
+//! Buffers of the [`Heap`] device are allocated with a [`HeapAllocator`],
+//! which is the global allocator ([`Global`]) by default, but may be an arena or a pool as well.
+
+mod heap_device;
+mod ops;
+
+pub use heap_device::*;
+
+use core::{
+    alloc::Layout,
+    fmt::Debug,
+    ptr::{null_mut, NonNull},
+};
+
+use crate::{flag::AllocFlag, CommonPtrs, PtrType, ShallowCopy};
+
+/// Allocates the memory of [`Heap`] buffers.
+///
+/// Implement this trait to allocate buffers in an arena or a pool instead of using the global allocator.
+/// # Example
+/// ```
+/// use core::{alloc::Layout, cell::Cell};
+/// use custos::{heap::HeapAllocator, Buffer, Heap};
+///
+/// /// Counts the allocations of the global allocator.
+/// struct Counting {
+///     allocations: Cell<usize>,
+/// }
+///
+/// impl HeapAllocator for Counting {
+///     fn allocate(&self, layout: Layout) -> *mut u8 {
+///         self.allocations.set(self.allocations.get() + 1);
+///         unsafe { std::alloc::alloc(layout) }
+///     }
+///
+///     unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout) {
+///         std::alloc::dealloc(ptr, layout)
+///     }
+/// }
+///
+/// let counting: &'static Counting = Box::leak(Box::new(Counting { allocations: Cell::new(0) }));
+///
+/// let device = Heap::with_allocator(counting);
+/// let buf = Buffer::from((&device, [1, 2, 3]));
+///
+/// assert_eq!(buf.read(), [1, 2, 3]);
+/// assert_eq!(counting.allocations.get(), 1);
+/// ```
+pub trait HeapAllocator {
+    /// Allocates memory as described by `layout`, which never has a size of zero.
+    /// Returns a null pointer if the memory could not be allocated.
+    fn allocate(&self, layout: Layout) -> *mut u8;
+
+    /// Deallocates memory that was allocated by [`HeapAllocator::allocate`].
+    ///
+    /// # Safety
+    /// `ptr` must have been allocated by this allocator with the same `layout`, and must not be used afterwards.
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout);
+}
+
+/// The global allocator, which is registered with `#[global_allocator]`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Global;
+
+impl HeapAllocator for Global {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> *mut u8 {
+        unsafe { alloc::alloc::alloc(layout) }
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout) {
+        alloc::alloc::dealloc(ptr, layout)
+    }
+}
+
+/// The pointer used for [`Heap`] [`Buffer`](crate::Buffer)s
+pub struct HeapPtr<T> {
+    /// The pointer to the data
+    pub ptr: *mut T,
+    /// The length of the data
+    pub len: usize,
+    /// Allocation flag for the pointer
+    pub flag: AllocFlag,
+    /// The layout of the allocation, which may differ from the layout of `[T; len]` after a conversion.
+    pub layout: Layout,
+    /// The allocator that allocated the memory.
+    pub allocator: &'static dyn HeapAllocator,
+}
+
+impl<T> HeapPtr<T> {
+    /// Allocates `len` elements with `allocator` and initializes the memory with zeros.
+    ///
+    /// # Panics
+    /// If `allocator` could not allocate the memory, e.g. because an arena is exhausted.
+    pub fn new_zeroed(
+        len: usize,
+        flag: AllocFlag,
+        allocator: &'static dyn HeapAllocator,
+    ) -> HeapPtr<T> {
+        let layout = Layout::array::<T>(len).unwrap();
+
+        // zero-sized types and empty buffers do not need any memory
+        if layout.size() == 0 {
+            return HeapPtr {
+                ptr: NonNull::dangling().as_ptr(),
+                len,
+                flag,
+                layout,
+                allocator,
+            };
+        }
+
+        let ptr = allocator.allocate(layout);
+        assert!(
+            !ptr.is_null(),
+            "could not allocate {} bytes for a Heap buffer",
+            layout.size()
+        );
+
+        unsafe { ptr.write_bytes(0, layout.size()) };
+
+        HeapPtr {
+            ptr: ptr.cast(),
+            len,
+            flag,
+            layout,
+            allocator,
+        }
+    }
+}
+
+impl<T> Default for HeapPtr<T> {
+    fn default() -> Self {
+        HeapPtr {
+            ptr: null_mut(),
+            len: 0,
+            flag: AllocFlag::default(),
+            layout: Layout::new::<()>(),
+            allocator: &Global,
+        }
+    }
+}
+
+impl<T> Debug for HeapPtr<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HeapPtr")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .field("flag", &self.flag)
+            .field("layout", &self.layout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for HeapPtr<T> {
+    fn drop(&mut self) {
+        if !matches!(self.flag, AllocFlag::None | AllocFlag::BorrowedCache) {
+            return;
+        }
+
+        if self.ptr.is_null() || self.layout.size() == 0 {
+            return;
+        }
+
+        unsafe { self.allocator.deallocate(self.ptr.cast(), self.layout) }
+    }
+}
+
+impl<T> PtrType for HeapPtr<T> {
+    #[inline]
+    fn size(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn flag(&self) -> AllocFlag {
+        self.flag
+    }
+}
+
+impl<T> CommonPtrs<T> for HeapPtr<T> {
+    #[inline]
+    fn ptrs(&self) -> (*const T, *mut core::ffi::c_void, u64) {
+        (self.ptr as *const T, null_mut(), 0)
+    }
+
+    #[inline]
+    fn ptrs_mut(&mut self) -> (*mut T, *mut core::ffi::c_void, u64) {
+        (self.ptr, null_mut(), 0)
+    }
+}
+
+impl<T> ShallowCopy for HeapPtr<T> {
+    #[inline]
+    unsafe fn shallow(&self) -> Self {
+        HeapPtr {
+            ptr: self.ptr,
+            len: self.len,
+            flag: AllocFlag::Wrapper,
+            layout: self.layout,
+            allocator: self.allocator,
+        }
+    }
+}
@@ -0,0 +1,6 @@
+use crate::devices::host_ops::{impl_host_ops, impl_host_transfer};
+
+use super::Heap;
+
+impl_host_ops!(Heap);
+impl_host_transfer!(Heap);
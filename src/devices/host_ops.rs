@@ -0,0 +1,185 @@
+//! Operations of the host devices that execute them on the calling thread.
+//! The [`CPU`](crate::CPU) implements the element-wise operations itself, as it splits them across threads.
+
+/// Implements [`ApplyFunction`](crate::ApplyFunction), [`UnaryGrad`](crate::UnaryGrad), [`UpdateFn`](crate::optim::UpdateFn) and [`ClearBuf`](crate::ClearBuf)
+/// for a device that reads the buffers of all [`MainMemory`](crate::MainMemory) devices.
+#[cfg(any(feature = "stack", feature = "alloc", feature = "arena"))]
+macro_rules! impl_host_ops {
+    ($device:ident) => {
+        impl<T, D, S> $crate::ApplyFunction<T, S, D> for $device
+        where
+            T: Copy + Default,
+            D: $crate::MainMemory,
+            S: $crate::Shape,
+        {
+            fn apply_fn<F>(
+                &self,
+                buf: &$crate::Buffer<T, D, S>,
+                f: impl Fn($crate::Resolve<T>) -> F + Sync,
+            ) -> $crate::Buffer<T, Self, S>
+            where
+                F: $crate::Eval<T> + $crate::MayToCLSource,
+            {
+                let mut out = $crate::Device::retrieve::<T, S>(self, buf.len(), buf);
+
+                $crate::eval_in_lanes(buf, f, |idx, values| {
+                    out[idx..idx + values.len()].copy_from_slice(values)
+                });
+
+                out
+            }
+        }
+
+        impl<T, D, S> $crate::UnaryGrad<T, S, D> for $device
+        where
+            T: core::ops::AddAssign + Copy + core::ops::Mul<Output = T>,
+            S: $crate::Shape,
+            D: $crate::MainMemory,
+        {
+            fn add_unary_grad<F>(
+                &self,
+                lhs: &$crate::Buffer<T, D, S>,
+                lhs_grad: &mut $crate::Buffer<T, D, S>,
+                out: &$crate::Buffer<T, D, S>,
+                lhs_grad_fn: impl Fn($crate::Resolve<T>) -> F + Sync,
+            ) where
+                F: $crate::Eval<T> + $crate::MayToCLSource,
+            {
+                $crate::eval_in_lanes(lhs, lhs_grad_fn, |idx, grads| {
+                    let lhs_grad = &mut lhs_grad[idx..idx + grads.len()];
+                    for ((lhs_grad, out), grad) in lhs_grad.iter_mut().zip(&out[idx..]).zip(grads) {
+                        *lhs_grad += *out * *grad;
+                    }
+                });
+            }
+        }
+
+        impl<T, D, S> $crate::optim::UpdateFn<T, S, D> for $device
+        where
+            T: Copy,
+            D: $crate::MainMemory,
+            S: $crate::Shape,
+        {
+            fn update_fn<F, const N: usize>(
+                &self,
+                buf: &mut $crate::Buffer<T, D, S>,
+                (lhs, rhs): (&$crate::Buffer<T, D, S>, &$crate::Buffer<T, D, S>),
+                scalars: [T; N],
+                f: impl Fn(
+                        $crate::Resolve<T>,
+                        $crate::Resolve<T>,
+                        $crate::Resolve<T>,
+                        [$crate::Resolve<T>; N],
+                    ) -> F
+                    + Sync,
+            ) where
+                F: $crate::Eval<T> + $crate::MayToCLSource,
+            {
+                let scalars = scalars.map($crate::Resolve::with_val);
+                for ((value, lhs), rhs) in buf.iter_mut().zip(lhs.iter()).zip(rhs.iter()) {
+                    *value = $crate::Eval::eval(f(
+                        $crate::Resolve::with_val(*value),
+                        $crate::Resolve::with_val(*lhs),
+                        $crate::Resolve::with_val(*rhs),
+                        scalars,
+                    ));
+                }
+            }
+        }
+
+        impl<T: Default, D: $crate::MainMemory, S: $crate::Shape> $crate::ClearBuf<T, S, D>
+            for $device
+        {
+            fn clear(&self, buf: &mut $crate::Buffer<T, D, S>) {
+                for value in buf {
+                    *value = T::default();
+                }
+            }
+        }
+    };
+}
+
+/// Implements [`Read`](crate::Read), [`WriteBuf`](crate::WriteBuf) and [`CopySlice`](crate::CopySlice)
+/// for a device that accesses the buffers of all [`MainMemory`](crate::MainMemory) devices as slices.
+#[cfg(any(feature = "cpu", feature = "alloc", feature = "arena"))]
+macro_rules! impl_host_transfer {
+    ($device:ident) => {
+        impl<T, D: $crate::MainMemory, S: $crate::Shape> $crate::Read<T, S, D> for $device {
+            type Read<'a>
+                = &'a [T]
+            where
+                T: 'a,
+                D: 'a,
+                S: 'a;
+
+            #[inline]
+            fn read<'a>(&self, buf: &'a $crate::Buffer<T, D, S>) -> Self::Read<'a> {
+                buf.as_slice()
+            }
+
+            #[cfg(not(feature = "no-std"))]
+            #[inline]
+            fn read_to_vec(&self, buf: &$crate::Buffer<T, D, S>) -> Vec<T>
+            where
+                T: Default + Clone,
+            {
+                buf.to_vec()
+            }
+        }
+
+        impl<T: Copy, D: $crate::MainMemory, S: $crate::Shape> $crate::WriteBuf<T, S, D>
+            for $device
+        {
+            #[inline]
+            fn write(&self, buf: &mut $crate::Buffer<T, D, S>, data: &[T]) {
+                buf.copy_from_slice(data)
+            }
+
+            #[inline]
+            fn write_buf(&self, dst: &mut $crate::Buffer<T, D, S>, src: &$crate::Buffer<T, D, S>) {
+                $crate::WriteBuf::write(self, dst, src)
+            }
+        }
+
+        impl<T: Copy, D: $crate::MainMemory> $crate::CopySlice<T, D> for $device {
+            fn copy_slice_to<SR, DR>(
+                &self,
+                source: &$crate::Buffer<T, D>,
+                source_range: SR,
+                dest: &mut $crate::Buffer<T, Self>,
+                dest_range: DR,
+            ) where
+                SR: core::ops::RangeBounds<usize>,
+                DR: core::ops::RangeBounds<usize>,
+            {
+                let source_range = $crate::bounds_to_range(source_range, source.len());
+                let dest_range = $crate::bounds_to_range(dest_range, dest.len());
+
+                assert_eq!(
+                    source_range.end - source_range.start,
+                    dest_range.end - dest_range.start,
+                );
+
+                dest[dest_range].copy_from_slice(&source[source_range]);
+            }
+
+            fn copy_slice_all<I>(
+                &self,
+                source: &$crate::Buffer<T, D>,
+                dest: &mut $crate::Buffer<T, Self>,
+                ranges: I,
+            ) where
+                I: IntoIterator<Item = (core::ops::Range<usize>, core::ops::Range<usize>)>,
+            {
+                for (source_range, dest_range) in ranges {
+                    $crate::CopySlice::copy_slice_to(self, source, source_range, dest, dest_range);
+                }
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "stack", feature = "alloc", feature = "arena"))]
+pub(crate) use impl_host_ops;
+#[cfg(any(feature = "cpu", feature = "alloc", feature = "arena"))]
+pub(crate) use impl_host_transfer;
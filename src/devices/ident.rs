@@ -1,32 +1,84 @@
+#[cfg(not(feature = "no-std"))]
 use core::cell::Cell;
+#[cfg(feature = "no-std")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(not(feature = "no-std"))]
 use std::thread_local;
 
+#[cfg(not(feature = "no-std"))]
 thread_local! {
     pub(crate) static COUNT: Cell<usize> = Cell::new(0);
 }
 
+/// Without threads, there is a single count.
+/// Only atomic loads and stores are used, which are available on targets without atomic read-modify-write operations.
+#[cfg(feature = "no-std")]
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
 /// Sets current cache identifier / index.
 /// This function is usually called after an iteration in a loop -> [Count](crate::Count) or [range](crate::range)
 /// # Safety
 /// Manually setting the count may yield multiple `Buffer` pointing two the same data.
 #[inline]
 pub unsafe fn set_count(count: usize) {
+    #[cfg(not(feature = "no-std"))]
     COUNT.with(|c| c.set(count));
+
+    #[cfg(feature = "no-std")]
+    COUNT.store(count, Ordering::Relaxed);
 }
 
 /// Returns current cache identifier / index
 #[inline]
 pub fn get_count() -> usize {
-    COUNT.with(|c| c.get())
+    #[cfg(not(feature = "no-std"))]
+    {
+        COUNT.with(|c| c.get())
+    }
+
+    #[cfg(feature = "no-std")]
+    COUNT.load(Ordering::Relaxed)
+}
+
+/// Returns the first cache identifier / index of the current thread.
+///
+/// With the `thread-safe` feature, the caches of a device are shared between threads.
+/// Every thread is therefore assigned its own range of identifiers, otherwise two threads would retrieve the same cached pointer.
+/// Without the feature, this is always 0.
+#[inline]
+pub fn thread_ident_offset() -> usize {
+    #[cfg(feature = "thread-safe")]
+    {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static NEXT_THREAD: AtomicUsize = AtomicUsize::new(0);
+        thread_local! {
+            static OFFSET: usize = NEXT_THREAD.fetch_add(1, Ordering::Relaxed) << (usize::BITS / 2);
+        }
+        OFFSET.with(|offset| *offset)
+    }
+
+    #[cfg(not(feature = "thread-safe"))]
+    0
+}
+
+/// Returns the index of the next cached pointer of the current thread (see [`thread_ident_offset`]).
+#[inline]
+pub fn get_ident_idx() -> usize {
+    thread_ident_offset().wrapping_add(get_count())
 }
 
 #[inline]
 /// Increases the cache identifier / index by 1.
 pub fn bump_count() {
+    #[cfg(not(feature = "no-std"))]
     COUNT.with(|c| {
         let count = c.get();
         c.set(count + 1);
-    })
+    });
+
+    #[cfg(feature = "no-std")]
+    COUNT.store(get_count() + 1, Ordering::Relaxed);
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -43,7 +95,7 @@ impl Ident {
     #[inline]
     pub fn new(len: usize) -> Ident {
         Ident {
-            idx: get_count(),
+            idx: get_ident_idx(),
             len,
         }
     }
@@ -52,7 +104,7 @@ impl Ident {
     #[inline]
     pub fn new_bumped(len: usize) -> Ident {
         let id = Ident {
-            idx: get_count(),
+            idx: get_ident_idx(),
             len,
         };
         bump_count();
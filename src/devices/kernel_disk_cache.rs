@@ -0,0 +1,473 @@
+//! An on-disk cache for compiled kernels, which is shared between processes.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    format, fs, io,
+    path::{Path, PathBuf},
+    string::String,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+    vec::Vec,
+};
+
+/// Compiles kernel source code to a device specific artifact (e.g. an OpenCL program or a CUDA module)
+/// and is able to recreate this artifact from its binary representation.
+pub trait KernelCompiler {
+    /// The compiled kernel, program or module.
+    type Artifact;
+
+    /// Identifies everything, apart from the source code, that affects the compiled binary.
+    /// e.g. the device name, the driver version and the build options.
+    ///
+    /// A binary is only reused if the identity matches.
+    fn identity(&self) -> String;
+
+    /// Compiles the source code and returns the artifact and its binary representation.
+    fn compile(&self, src: &str) -> crate::Result<(Self::Artifact, Vec<u8>)>;
+
+    /// Creates the artifact from a binary previously returned by [`compile`](KernelCompiler::compile).
+    fn load(&self, binary: &[u8]) -> crate::Result<Self::Artifact>;
+}
+
+/// The default maximum size of a [`KernelDiskCache`]: 256 MiB.
+pub const DEFAULT_KERNEL_CACHE_SIZE: u64 = 256 * 1024 * 1024;
+
+const MAGIC: &[u8; 8] = b"CUSTOSKC";
+const HEADER_LEN: usize = MAGIC.len() + 16;
+const EXTENSION: &str = "kbin";
+/// The extension of the file that stores when an entry was used last.
+const USED_EXTENSION: &str = "used";
+
+/// Stores compiled kernel binaries in a directory.
+///
+/// An entry is keyed by a hash of the source code and the [`identity`](KernelCompiler::identity) of the compiler.
+/// Entries that can not be loaded anymore (e.g. after a driver update that did not change the identity) are removed and recompiled.
+/// If the size of all entries exceeds the maximum size, the least recently used entries are removed.
+/// The time of the last use is stored in a small file next to the entry, hence a cache hit does not rewrite the binary.
+///
+/// # Example
+/// ```
+/// use custos::{KernelCompiler, KernelDiskCache};
+///
+/// struct Upper;
+///
+/// impl KernelCompiler for Upper {
+///     type Artifact = String;
+///
+///     fn identity(&self) -> String {
+///         "upper".into()
+///     }
+///
+///     fn compile(&self, src: &str) -> custos::Result<(String, Vec<u8>)> {
+///         let compiled = src.to_uppercase();
+///         Ok((compiled.clone(), compiled.into_bytes()))
+///     }
+///
+///     fn load(&self, binary: &[u8]) -> custos::Result<String> {
+///         Ok(String::from_utf8(binary.to_vec())?)
+///     }
+/// }
+///
+/// let dir = std::env::temp_dir().join(format!("custos-doc-kernel-cache-{}", std::process::id()));
+/// let cache = KernelDiskCache::new(&dir);
+///
+/// assert_eq!(cache.load_or_compile(&Upper, "kernel").unwrap(), "KERNEL");
+/// assert_eq!(cache.get(&Upper.identity(), "kernel").unwrap(), b"KERNEL");
+///
+/// cache.clear().unwrap();
+/// # std::fs::remove_dir(dir).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelDiskCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+impl KernelDiskCache {
+    /// Creates a cache that stores its entries in `dir`.
+    /// The directory is created when the first entry is inserted.
+    #[inline]
+    pub fn new(dir: impl Into<PathBuf>) -> KernelDiskCache {
+        KernelDiskCache {
+            dir: dir.into(),
+            max_size: DEFAULT_KERNEL_CACHE_SIZE,
+        }
+    }
+
+    /// Returns a cache in the directory specified by the environment variable `CUSTOS_KERNEL_CACHE_DIR`.
+    /// The maximum size in bytes can be set with `CUSTOS_KERNEL_CACHE_SIZE`.
+    ///
+    /// Returns `None` if `CUSTOS_KERNEL_CACHE_DIR` is not set.
+    pub fn from_env() -> Option<KernelDiskCache> {
+        let dir = std::env::var_os("CUSTOS_KERNEL_CACHE_DIR")?;
+        let mut cache = KernelDiskCache::new(dir);
+
+        if let Some(max_size) = std::env::var("CUSTOS_KERNEL_CACHE_SIZE")
+            .ok()
+            .and_then(|max_size| max_size.parse().ok())
+        {
+            cache.max_size = max_size;
+        }
+        Some(cache)
+    }
+
+    /// Sets the maximum size of all entries in bytes.
+    #[inline]
+    pub fn with_max_size(mut self, max_size: u64) -> KernelDiskCache {
+        self.max_size = max_size;
+        self
+    }
+
+    /// The directory of the cache.
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The maximum size of all entries in bytes.
+    #[inline]
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Returns the artifact of `src`.
+    /// It is loaded from a cached binary if possible, otherwise `src` is compiled and the binary is cached.
+    ///
+    /// Failing to write the binary does not result in an error, as the cache is only an optimization.
+    pub fn load_or_compile<C: KernelCompiler>(
+        &self,
+        compiler: &C,
+        src: &str,
+    ) -> crate::Result<C::Artifact> {
+        let identity = compiler.identity();
+
+        if let Some(binary) = self.get(&identity, src) {
+            match compiler.load(&binary) {
+                Ok(artifact) => return Ok(artifact),
+                Err(_) => self.invalidate(&identity, src),
+            }
+        }
+
+        let (artifact, binary) = compiler.compile(src)?;
+        let _ = self.insert(&identity, src, &binary);
+
+        Ok(artifact)
+    }
+
+    /// Returns the cached binary of `src` for a compiler with the given identity.
+    /// Entries that are corrupted or belong to a different source (hash collision) are ignored.
+    pub fn get(&self, identity: &str, src: &str) -> Option<Vec<u8>> {
+        let path = self.path(identity, src);
+        let entry = fs::read(&path).ok()?;
+
+        if entry.len() < HEADER_LEN
+            || &entry[..MAGIC.len()] != MAGIC
+            || entry[MAGIC.len()..MAGIC.len() + 8] != check_hash(identity, src).to_le_bytes()
+            || entry[MAGIC.len() + 8..HEADER_LEN]
+                != ((entry.len() - HEADER_LEN) as u64).to_le_bytes()
+        {
+            remove_entry(&path);
+            return None;
+        }
+
+        self.mark_used(&path, SystemTime::now());
+
+        Some(entry[HEADER_LEN..].to_vec())
+    }
+
+    /// Caches the binary of `src` for a compiler with the given identity.
+    /// Afterwards, the least recently used entries are removed until the cache fits into the maximum size.
+    pub fn insert(&self, identity: &str, src: &str, binary: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let mut entry = Vec::with_capacity(HEADER_LEN + binary.len());
+        entry.extend_from_slice(MAGIC);
+        entry.extend_from_slice(&check_hash(identity, src).to_le_bytes());
+        entry.extend_from_slice(&(binary.len() as u64).to_le_bytes());
+        entry.extend_from_slice(binary);
+
+        let path = self.path(identity, src);
+        write_atomic(&path, &entry)?;
+        self.mark_used(&path, SystemTime::now());
+        self.evict()
+    }
+
+    /// Removes the cached binary of `src` for a compiler with the given identity.
+    pub fn invalidate(&self, identity: &str, src: &str) {
+        remove_entry(&self.path(identity, src));
+    }
+
+    /// Removes all entries of the cache.
+    pub fn clear(&self) -> io::Result<()> {
+        for (path, _, _) in self.entries()? {
+            fs::remove_file(&path)?;
+            let _ = fs::remove_file(path.with_extension(USED_EXTENSION));
+        }
+        Ok(())
+    }
+
+    /// The size of all entries in bytes.
+    pub fn size(&self) -> io::Result<u64> {
+        Ok(self.entries()?.iter().map(|(_, size, _)| size).sum())
+    }
+
+    /// Removes the least recently used entries until the cache fits into the maximum size.
+    fn evict(&self) -> io::Result<()> {
+        let mut entries = self.entries()?;
+        let mut size = entries.iter().map(|(_, size, _)| size).sum::<u64>();
+
+        entries.sort_by_key(|(_, _, modified)| *modified);
+
+        for (path, entry_size, _) in entries {
+            if size <= self.max_size {
+                break;
+            }
+            fs::remove_file(&path)?;
+            let _ = fs::remove_file(path.with_extension(USED_EXTENSION));
+            size -= entry_size;
+        }
+        Ok(())
+    }
+
+    /// Records `time` as the last use of the entry at `path`.
+    /// Failing to do so only affects the order of eviction.
+    fn mark_used(&self, path: &Path, time: SystemTime) {
+        let nanos = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        let _ = write_atomic(&path.with_extension(USED_EXTENSION), &nanos.to_le_bytes());
+    }
+
+    /// Returns the path, size and time of the last use of every entry.
+    /// Entries without a recorded use fall back to their modification time.
+    fn entries(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut entries = Vec::new();
+        for entry in dir {
+            let path = entry?.path();
+            if path
+                .extension()
+                .map_or(true, |extension| extension != EXTENSION)
+            {
+                continue;
+            }
+
+            let metadata = fs::metadata(&path)?;
+            let used = match last_used(&path) {
+                Some(used) => used,
+                None => metadata.modified()?,
+            };
+            entries.push((path, metadata.len(), used));
+        }
+        Ok(entries)
+    }
+
+    fn path(&self, identity: &str, src: &str) -> PathBuf {
+        let key = fnv1a(FNV_OFFSET, identity, src);
+        self.dir.join(format!("{key:016x}.{EXTENSION}"))
+    }
+}
+
+/// Reads the time of the last use of the entry at `path`, see [`KernelDiskCache::mark_used`].
+fn last_used(path: &Path) -> Option<SystemTime> {
+    let nanos = fs::read(path.with_extension(USED_EXTENSION)).ok()?;
+    Some(UNIX_EPOCH + Duration::from_nanos(u64::from_le_bytes(nanos.try_into().ok()?)))
+}
+
+fn remove_entry(path: &Path) {
+    let _ = fs::remove_file(path);
+    let _ = fs::remove_file(path.with_extension(USED_EXTENSION));
+}
+
+/// Another process may read the entry at the same time.
+/// Renaming a fully written file ensures that it never sees a partial entry.
+///
+/// The temporary file is unique per process and write, as several threads may write the same entry.
+fn write_atomic(path: &Path, entry: &[u8]) -> io::Result<()> {
+    static WRITES: AtomicU64 = AtomicU64::new(0);
+
+    let write = WRITES.fetch_add(1, Ordering::Relaxed);
+    let tmp = path.with_extension(format!("{}.{write}.tmp", std::process::id()));
+    fs::write(&tmp, entry)?;
+    fs::rename(&tmp, path)
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+/// A second hash with a different offset, stored in the entry to detect collisions of the file name.
+#[inline]
+fn check_hash(identity: &str, src: &str) -> u64 {
+    fnv1a(!FNV_OFFSET, identity, src)
+}
+
+/// The FNV-1a hash of `identity` and `src`.
+/// In contrast to the `Hash` implementations of std, the result is stable across Rust versions and processes.
+fn fnv1a(offset: u64, identity: &str, src: &str) -> u64 {
+    identity
+        .bytes()
+        .chain([0])
+        .chain(src.bytes())
+        .fold(offset, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use std::{
+        format, fs,
+        path::PathBuf,
+        string::String,
+        time::{Duration, UNIX_EPOCH},
+        vec::Vec,
+    };
+
+    use super::{KernelCompiler, KernelDiskCache};
+
+    /// Compiles source code by reversing it and counts how often it compiled or loaded a binary.
+    #[derive(Default)]
+    struct FakeCompiler {
+        identity: &'static str,
+        compiled: Cell<usize>,
+        loaded: Cell<usize>,
+    }
+
+    impl KernelCompiler for FakeCompiler {
+        type Artifact = String;
+
+        fn identity(&self) -> String {
+            self.identity.into()
+        }
+
+        fn compile(&self, src: &str) -> crate::Result<(String, Vec<u8>)> {
+            self.compiled.set(self.compiled.get() + 1);
+            let artifact = src.chars().rev().collect::<String>();
+            Ok((artifact.clone(), artifact.into_bytes()))
+        }
+
+        fn load(&self, binary: &[u8]) -> crate::Result<String> {
+            self.loaded.set(self.loaded.get() + 1);
+            Ok(String::from_utf8(binary.to_vec())?)
+        }
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("custos-kernel-cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_disk_cache_reuses_binary_across_instances() -> crate::Result<()> {
+        let dir = cache_dir("reuse");
+        let compiler = FakeCompiler::default();
+
+        let cache = KernelDiskCache::new(&dir);
+        assert_eq!(cache.load_or_compile(&compiler, "abc")?, "cba");
+        assert_eq!(cache.load_or_compile(&compiler, "abc")?, "cba");
+
+        // e.g. another process
+        let cache = KernelDiskCache::new(&dir);
+        assert_eq!(cache.load_or_compile(&compiler, "abc")?, "cba");
+
+        assert_eq!(compiler.compiled.get(), 1);
+        assert_eq!(compiler.loaded.get(), 2);
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_cache_keyed_by_identity() -> crate::Result<()> {
+        let dir = cache_dir("identity");
+        let cache = KernelDiskCache::new(&dir);
+
+        let gpu_a = FakeCompiler {
+            identity: "gpu a, driver 1",
+            ..Default::default()
+        };
+        let gpu_b = FakeCompiler {
+            identity: "gpu a, driver 2",
+            ..Default::default()
+        };
+
+        cache.load_or_compile(&gpu_a, "abc")?;
+        cache.load_or_compile(&gpu_b, "abc")?;
+        cache.load_or_compile(&gpu_a, "xyz")?;
+
+        assert_eq!(gpu_a.compiled.get(), 2);
+        assert_eq!(gpu_b.compiled.get(), 1);
+        assert!(cache.get("gpu a, driver 2", "abc").is_some());
+        assert!(cache.get("gpu a, driver 2", "xyz").is_none());
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_cache_recompiles_invalid_entries() -> crate::Result<()> {
+        let dir = cache_dir("invalid");
+        let cache = KernelDiskCache::new(&dir);
+        let compiler = FakeCompiler::default();
+
+        cache.load_or_compile(&compiler, "abc")?;
+
+        // truncated entry
+        let path = fs::read_dir(&dir)?.next().unwrap()?.path();
+        let entry = fs::read(&path)?;
+        fs::write(&path, &entry[..entry.len() - 1])?;
+
+        assert_eq!(cache.load_or_compile(&compiler, "abc")?, "cba");
+        assert_eq!(compiler.compiled.get(), 2);
+
+        // binary that the compiler rejects
+        cache.insert("", "def", &[0xff, 0xfe])?;
+        assert_eq!(cache.load_or_compile(&compiler, "def")?, "fed");
+        assert_eq!(compiler.compiled.get(), 3);
+        assert_eq!(cache.get("", "def").unwrap(), b"fed");
+
+        cache.invalidate("", "def");
+        assert!(cache.get("", "def").is_none());
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_cache_evicts_least_recently_used() -> crate::Result<()> {
+        let dir = cache_dir("evict");
+        let cache = KernelDiskCache::new(&dir);
+
+        let binary = [0u8; 100];
+        cache.insert("", "first", &binary)?;
+        let entry_size = cache.size()?;
+
+        let cache = cache.with_max_size(entry_size * 2);
+        cache.insert("", "second", &binary)?;
+
+        // "first" was used more recently than "second"
+        let used = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        cache.mark_used(&cache.path("", "second"), used(1));
+        cache.mark_used(&cache.path("", "first"), used(2));
+
+        cache.insert("", "third", &binary)?;
+
+        assert_eq!(cache.size()?, entry_size * 2);
+        assert!(cache.get("", "first").is_some());
+        assert!(cache.get("", "second").is_none());
+        assert!(cache.get("", "third").is_some());
+
+        cache.clear()?;
+        assert_eq!(cache.size()?, 0);
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
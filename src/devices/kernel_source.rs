@@ -0,0 +1,106 @@
+//! Helpers for generating and inspecting kernel source code.
+
+#[cfg(any(feature = "cuda", feature = "opencl"))]
+use std::{format, string::String};
+
+#[cfg(any(feature = "cuda", feature = "opencl"))]
+use crate::KernelLang;
+
+/// Returns `true` if `src` contains `ident` as a whole identifier,
+/// e.g. the element type `half`, but not `half_len` or `bf16` when looking for `f16`.
+#[cfg(feature = "half")]
+pub(crate) fn contains_ident(src: &str, ident: &str) -> bool {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    src.match_indices(ident).any(|(start, _)| {
+        !src[..start].ends_with(is_ident_char)
+            && !src[start + ident.len()..].starts_with(is_ident_char)
+    })
+}
+
+/// Returns the helper functions for complex numbers stored as `float2` or `double2` in the language `lang`.
+/// The built-in vector operators and functions would be applied to both components.
+///
+/// `c_type` is the [`complex_c_type`](crate::ToCLSource::complex_c_type) of a generated expression.
+/// For real expressions, no helpers are added, hence kernels that merely use the vector types are not affected.
+#[cfg(any(feature = "cuda", feature = "opencl"))]
+pub(crate) fn complex_prelude(c_type: Option<&str>, lang: KernelLang) -> String {
+    let Some(vec_type) = c_type else {
+        return String::new();
+    };
+
+    let scalar = vec_type.trim_end_matches('2');
+    let (qualifier, make) = match lang {
+        KernelLang::CUDA => ("__device__ inline", format!("make_{vec_type}")),
+        _ => ("inline", format!("({vec_type})")),
+    };
+
+    format!(
+        "
+        {qualifier} {vec_type} {vec_type}_new({scalar} re, {scalar} im) {{
+            return {make}(re, im);
+        }}
+        {qualifier} {vec_type} {vec_type}_add({vec_type} a, {vec_type} b) {{
+            return {make}(a.x + b.x, a.y + b.y);
+        }}
+        {qualifier} {vec_type} {vec_type}_sub({vec_type} a, {vec_type} b) {{
+            return {make}(a.x - b.x, a.y - b.y);
+        }}
+        {qualifier} {vec_type} {vec_type}_neg({vec_type} a) {{
+            return {make}(-a.x, -a.y);
+        }}
+        {qualifier} {vec_type} {vec_type}_mul({vec_type} a, {vec_type} b) {{
+            return {make}(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+        }}
+        {qualifier} {vec_type} {vec_type}_div({vec_type} a, {vec_type} b) {{
+            {scalar} denom = b.x * b.x + b.y * b.y;
+            return {make}((a.x * b.x + a.y * b.y) / denom, (a.y * b.x - a.x * b.y) / denom);
+        }}
+        {qualifier} {vec_type} {vec_type}_exp({vec_type} a) {{
+            {scalar} r = exp(a.x);
+            return {make}(r * cos(a.y), r * sin(a.y));
+        }}
+        {qualifier} {vec_type} {vec_type}_sin({vec_type} a) {{
+            return {make}(sin(a.x) * cosh(a.y), cos(a.x) * sinh(a.y));
+        }}
+        {qualifier} {vec_type} {vec_type}_cos({vec_type} a) {{
+            return {make}(cos(a.x) * cosh(a.y), -sin(a.x) * sinh(a.y));
+        }}
+        {qualifier} {vec_type} {vec_type}_tan({vec_type} a) {{
+            return {vec_type}_div({vec_type}_sin(a), {vec_type}_cos(a));
+        }}
+    "
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_contains_ident() {
+        use super::contains_ident;
+
+        assert!(contains_ident("__kernel void f(__global half* x)", "half"));
+        assert!(contains_ident("array<f16>", "f16"));
+        assert!(!contains_ident("size_t half_len = len / 2;", "half"));
+        assert!(!contains_ident("array<bf16>", "f16"));
+        assert!(!contains_ident("let buf16 = 0;", "f16"));
+    }
+
+    #[cfg(any(feature = "cuda", feature = "opencl"))]
+    #[test]
+    fn test_complex_prelude() {
+        use super::complex_prelude;
+        use crate::KernelLang;
+
+        assert_eq!(complex_prelude(None, KernelLang::OpenCL), "");
+
+        let cl = complex_prelude(Some("double2"), KernelLang::OpenCL);
+        assert!(cl.contains("inline double2 double2_new(double re, double im)"));
+        assert!(cl.contains("return (double2)(re, im);"));
+
+        let cu = complex_prelude(Some("float2"), KernelLang::CUDA);
+        assert!(cu.contains("__device__ inline float2 float2_div(float2 a, float2 b)"));
+        assert!(!cu.contains("operator"));
+    }
+}
@@ -0,0 +1,471 @@
+//! Kernel source templates with typed placeholders, which are instantiated for OpenCL, CUDA or WGSL.
+
+use core::fmt::Display;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{CDatatype, Shape};
+
+/// The language a [`KernelTemplate`] is instantiated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KernelLang {
+    /// OpenCL C
+    OpenCL,
+    /// CUDA C++
+    CUDA,
+    /// The WebGPU Shading Language
+    WGSL,
+}
+
+/// An error that occured while instantiating a [`KernelTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// The placeholders have no value.
+    Unresolved(Vec<String>),
+    /// A `${` at the given byte offset is not closed or does not contain a valid name.
+    InvalidPlaceholder(usize),
+    /// The element type is not available in the language.
+    UnsupportedType(&'static str, KernelLang),
+    /// A vector of the element type and width is not available in the language.
+    UnsupportedVector(&'static str, usize, KernelLang),
+    /// The shape bound to the placeholder has no length known at compile time.
+    DynamicShape(String),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TemplateError::Unresolved(names) => {
+                write!(f, "Unresolved placeholders: {}", names.join(", "))
+            }
+            TemplateError::InvalidPlaceholder(offset) => {
+                write!(f, "Invalid placeholder at byte offset {offset}.")
+            }
+            TemplateError::UnsupportedType(ty, lang) => {
+                write!(f, "The type '{ty}' is not supported in {lang:?}.")
+            }
+            TemplateError::UnsupportedVector(ty, width, lang) => {
+                write!(
+                    f,
+                    "A vector of type '{ty}' and width {width} is not supported in {lang:?}."
+                )
+            }
+            TemplateError::DynamicShape(name) => write!(
+                f,
+                "The shape bound to '{name}' has no length known at compile time."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Value {
+    /// The C type of an element, see [`CDatatype`].
+    Scalar(&'static str),
+    /// The C type of the elements and the width of a vector.
+    Vector(&'static str, usize),
+    /// `None` if the shape is not known at compile time.
+    Len(Option<usize>),
+    Raw(String),
+}
+
+/// Kernel source code with placeholders of the form `${NAME}`.
+///
+/// In contrast to splicing [`CDatatype::as_c_type_str`] into the source with `format!`,
+/// a template is instantiated for a [`KernelLang`] and checks that every placeholder is resolved.
+/// Element types and vectors are translated to the type names of the language, e.g. `u32` to `uint` (OpenCL), `unsigned int` (CUDA) or `u32` (WGSL).
+///
+/// The kernel caches store the instantiated sources of templates, see [`TemplateCache`].
+///
+/// # Example
+/// ```
+/// use custos::{Dim2, KernelLang, KernelTemplate};
+///
+/// let template = KernelTemplate::new("
+///     __kernel void scale(__global ${T}* x, ${T} factor) {
+///         size_t idx = get_global_id(0);
+///         if (idx >= ${LEN}) return;
+///         x[idx] *= factor;
+///     }
+/// ")
+/// .ty::<f32>("T")
+/// .len::<Dim2<2, 3>>("LEN");
+///
+/// let src = template.render(KernelLang::OpenCL).unwrap();
+/// assert!(src.contains("__global float* x, float factor"));
+/// assert!(src.contains("if (idx >= 6) return;"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KernelTemplate {
+    src: Cow<'static, str>,
+    values: Vec<(String, Value)>,
+}
+
+impl KernelTemplate {
+    /// Creates a template from source code containing `${NAME}` placeholders.
+    #[inline]
+    pub fn new(src: impl Into<Cow<'static, str>>) -> KernelTemplate {
+        KernelTemplate {
+            src: src.into(),
+            values: Vec::new(),
+        }
+    }
+
+    /// The source code of the template.
+    #[inline]
+    pub fn src(&self) -> &str {
+        &self.src
+    }
+
+    /// Binds the element type `T` to the placeholder `name`.
+    #[inline]
+    pub fn ty<T: CDatatype>(self, name: impl Into<String>) -> KernelTemplate {
+        self.bind(name, Value::Scalar(T::as_c_type_str()))
+    }
+
+    /// Binds a vector of `width` elements of type `T` to the placeholder `name`, e.g. `float4` or `vec4<f32>`.
+    #[inline]
+    pub fn vector<T: CDatatype>(self, name: impl Into<String>, width: usize) -> KernelTemplate {
+        self.bind(name, Value::Vector(T::as_c_type_str(), width))
+    }
+
+    /// Binds the length of the shape `S` ([`Shape::LEN`]) to the placeholder `name`.
+    /// Rendering fails if `S` is not known at compile time, e.g. `()`.
+    #[inline]
+    pub fn len<S: Shape>(self, name: impl Into<String>) -> KernelTemplate {
+        let len = (!S::DIMS.is_empty()).then_some(S::LEN);
+        self.bind(name, Value::Len(len))
+    }
+
+    /// Binds `value` to the placeholder `name`. The value is inserted as is.
+    #[inline]
+    pub fn value(self, name: impl Into<String>, value: impl Display) -> KernelTemplate {
+        self.bind(name, Value::Raw(value.to_string()))
+    }
+
+    /// A later binding of the same name replaces the previous one.
+    fn bind(mut self, name: impl Into<String>, value: Value) -> KernelTemplate {
+        let name = name.into();
+        self.values.retain(|(bound, _)| *bound != name);
+        self.values.push((name, value));
+        self
+    }
+
+    /// Returns the names of all placeholders in order of their first occurrence.
+    pub fn placeholders(&self) -> Result<Vec<&str>, TemplateError> {
+        let mut names = Vec::new();
+        for (_, name) in parse(&self.src)? {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
+
+    /// Replaces every placeholder with its value in the syntax of `lang`.
+    /// # Errors
+    /// - a placeholder is not bound or malformed
+    /// - a bound type or vector does not exist in `lang`
+    /// - a bound shape has no compile-time length
+    pub fn render(&self, lang: KernelLang) -> Result<String, TemplateError> {
+        let placeholders = parse(&self.src)?;
+
+        let unresolved = self
+            .placeholders()?
+            .into_iter()
+            .filter(|name| self.get(name).is_none())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        if !unresolved.is_empty() {
+            return Err(TemplateError::Unresolved(unresolved));
+        }
+
+        let mut src = String::with_capacity(self.src.len());
+        let mut last = 0;
+
+        for (start, name) in placeholders {
+            src.push_str(&self.src[last..start]);
+            src.push_str(&self.resolve(name, lang)?);
+            last = start + name.len() + 3;
+        }
+        src.push_str(&self.src[last..]);
+
+        Ok(src)
+    }
+
+    #[inline]
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.values
+            .iter()
+            .find(|(bound, _)| bound == name)
+            .map(|(_, value)| value)
+    }
+
+    fn resolve(&self, name: &str, lang: KernelLang) -> Result<Cow<'_, str>, TemplateError> {
+        Ok(match self.get(name).unwrap() {
+            Value::Scalar(ty) => scalar_name(ty, lang)
+                .ok_or(TemplateError::UnsupportedType(ty, lang))?
+                .into(),
+            Value::Vector(ty, width) => vector_name(ty, *width, lang)
+                .ok_or(TemplateError::UnsupportedVector(ty, *width, lang))?
+                .into(),
+            Value::Len(len) => len
+                .ok_or_else(|| TemplateError::DynamicShape(name.into()))?
+                .to_string()
+                .into(),
+            Value::Raw(value) => value.as_str().into(),
+        })
+    }
+}
+
+/// Returns the byte offset and name of every placeholder.
+fn parse(src: &str) -> Result<Vec<(usize, &str)>, TemplateError> {
+    let mut placeholders = Vec::new();
+    let mut offset = 0;
+
+    while let Some(start) = src[offset..].find("${").map(|start| start + offset) {
+        let name = src[start + 2..]
+            .find('}')
+            .map(|end| &src[start + 2..start + 2 + end])
+            .filter(|name| {
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+            .ok_or(TemplateError::InvalidPlaceholder(start))?;
+
+        placeholders.push((start, name));
+        offset = start + name.len() + 3;
+    }
+    Ok(placeholders)
+}
+
+/// Translates the C type of [`CDatatype`] to the type name of `lang`.
+fn scalar_name(c_type: &'static str, lang: KernelLang) -> Option<&'static str> {
+    match lang {
+        KernelLang::OpenCL => Some(c_type),
+        KernelLang::CUDA => Some(match c_type {
+            "char" => "signed char",
+            "uchar" => "unsigned char",
+            "ushort" => "unsigned short",
+            "uint" => "unsigned int",
+            "long" => "long long",
+            "ulong" => "unsigned long long",
+            c_type => c_type,
+        }),
+        KernelLang::WGSL => match c_type {
+            "bool" => Some("bool"),
+            "int" => Some("i32"),
+            "uint" => Some("u32"),
+            "float" => Some("f32"),
+            "half" => Some("f16"),
+            _ => None,
+        },
+    }
+}
+
+/// Returns the name of a vector of `width` elements of the C type `c_type` in `lang`.
+fn vector_name(c_type: &'static str, width: usize, lang: KernelLang) -> Option<String> {
+    match lang {
+        KernelLang::OpenCL => (matches!(width, 2 | 3 | 4 | 8 | 16) && c_type != "bool")
+            .then(|| format!("{c_type}{width}")),
+        KernelLang::CUDA => {
+            let base = match c_type {
+                "char" | "uchar" | "short" | "ushort" | "int" | "uint" | "float" | "double" => {
+                    c_type
+                }
+                "long" => "longlong",
+                "ulong" => "ulonglong",
+                _ => return None,
+            };
+            (1..=4).contains(&width).then(|| format!("{base}{width}"))
+        }
+        KernelLang::WGSL => {
+            let scalar = scalar_name(c_type, lang)?;
+            (2..=4)
+                .contains(&width)
+                .then(|| format!("vec{width}<{scalar}>"))
+        }
+    }
+}
+
+/// Stores the instantiated sources of [`KernelTemplate`]s for one [`KernelLang`].
+/// Every kernel cache contains a `TemplateCache`, hence a template is only rendered once per device.
+#[derive(Debug)]
+pub struct TemplateCache {
+    lang: KernelLang,
+    instances: HashMap<KernelTemplate, String>,
+}
+
+impl TemplateCache {
+    /// Creates an empty cache for templates instantiated for `lang`.
+    #[inline]
+    pub fn new(lang: KernelLang) -> TemplateCache {
+        TemplateCache {
+            lang,
+            instances: HashMap::new(),
+        }
+    }
+
+    /// The language the templates are instantiated for.
+    #[inline]
+    pub fn lang(&self) -> KernelLang {
+        self.lang
+    }
+
+    /// The number of instantiated templates.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Returns `true` if no template was instantiated.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// Returns the instantiated source of the template. The template is rendered if it was not instantiated before.
+    /// # Example
+    /// ```
+    /// use custos::{KernelLang, KernelTemplate, TemplateCache};
+    ///
+    /// let mut cache = TemplateCache::new(KernelLang::WGSL);
+    /// let template = KernelTemplate::new("var<storage, read_write> x: array<${T}>;");
+    ///
+    /// let src = cache.source(&template.clone().vector::<f32>("T", 4)).unwrap();
+    /// assert_eq!(src, "var<storage, read_write> x: array<vec4<f32>>;");
+    ///
+    /// assert!(cache.source(&template).is_err());
+    /// ```
+    pub fn source(&mut self, template: &KernelTemplate) -> Result<&str, TemplateError> {
+        if !self.instances.contains_key(template) {
+            let src = template.render(self.lang)?;
+            self.instances.insert(template.clone(), src);
+        }
+        Ok(&self.instances[template])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{string::ToString, vec};
+
+    use super::{KernelLang, KernelTemplate, TemplateCache, TemplateError};
+    use crate::Dim1;
+
+    const ADD: &str = "void add(${T}* lhs, ${T}* rhs, ${V}* out) { ${OP} }";
+
+    #[test]
+    fn test_render_per_lang() {
+        let template = KernelTemplate::new(ADD)
+            .ty::<u32>("T")
+            .vector::<u32>("V", 4)
+            .value("OP", "out[0] = lhs[0] + rhs[0];");
+
+        assert_eq!(
+            template.render(KernelLang::OpenCL).unwrap(),
+            "void add(uint* lhs, uint* rhs, uint4* out) { out[0] = lhs[0] + rhs[0]; }"
+        );
+        assert_eq!(
+            template.render(KernelLang::CUDA).unwrap(),
+            "void add(unsigned int* lhs, unsigned int* rhs, uint4* out) { out[0] = lhs[0] + rhs[0]; }"
+        );
+        assert_eq!(
+            template.render(KernelLang::WGSL).unwrap(),
+            "void add(u32* lhs, u32* rhs, vec4<u32>* out) { out[0] = lhs[0] + rhs[0]; }"
+        );
+    }
+
+    #[test]
+    fn test_unresolved_placeholders() {
+        let template = KernelTemplate::new(ADD).ty::<f32>("T");
+
+        assert_eq!(template.placeholders().unwrap(), ["T", "V", "OP"]);
+        assert_eq!(
+            template.render(KernelLang::OpenCL),
+            Err(TemplateError::Unresolved(vec!["V".into(), "OP".into()]))
+        );
+
+        assert_eq!(
+            KernelTemplate::new("x = ${N;").render(KernelLang::CUDA),
+            Err(TemplateError::InvalidPlaceholder(4))
+        );
+        assert_eq!(
+            KernelTemplate::new("x = ${}").render(KernelLang::CUDA),
+            Err(TemplateError::InvalidPlaceholder(4))
+        );
+    }
+
+    #[test]
+    fn test_unsupported_types_and_shapes() {
+        let template = KernelTemplate::new("${T}");
+
+        assert_eq!(
+            template.clone().ty::<i64>("T").render(KernelLang::WGSL),
+            Err(TemplateError::UnsupportedType("long", KernelLang::WGSL))
+        );
+        assert_eq!(
+            template
+                .clone()
+                .vector::<f32>("T", 8)
+                .render(KernelLang::CUDA),
+            Err(TemplateError::UnsupportedVector(
+                "float",
+                8,
+                KernelLang::CUDA
+            ))
+        );
+        assert_eq!(
+            template
+                .clone()
+                .vector::<f32>("T", 8)
+                .render(KernelLang::OpenCL)
+                .unwrap(),
+            "float8"
+        );
+        assert_eq!(
+            template
+                .clone()
+                .len::<Dim1<12>>("T")
+                .render(KernelLang::WGSL)
+                .unwrap(),
+            "12"
+        );
+
+        let error = template
+            .len::<()>("T")
+            .render(KernelLang::OpenCL)
+            .unwrap_err();
+        assert_eq!(error, TemplateError::DynamicShape("T".into()));
+        assert_eq!(
+            error.to_string(),
+            "The shape bound to 'T' has no length known at compile time."
+        );
+    }
+
+    #[test]
+    fn test_template_cache_instances() {
+        let mut cache = TemplateCache::new(KernelLang::OpenCL);
+        let template = KernelTemplate::new("${T} x;");
+
+        let float = template.clone().ty::<f32>("T");
+        assert_eq!(cache.source(&float).unwrap(), "float x;");
+        assert_eq!(cache.source(&float).unwrap(), "float x;");
+        assert_eq!(cache.len(), 1);
+
+        // a later binding replaces the previous one
+        let int = float.ty::<i32>("T");
+        assert_eq!(cache.source(&int).unwrap(), "int x;");
+        assert_eq!(cache.len(), 2);
+
+        assert!(cache.source(&template).is_err());
+        assert_eq!(cache.len(), 2);
+    }
+}
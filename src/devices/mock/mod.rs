@@ -0,0 +1,180 @@
+//! The Mock module provides a device that behaves like a GPU device, but is backed by host memory.
+//!
+//! Every operation of the [`Mock`] device is recorded as a [`MockCall`].
+//! This allows testing the generated kernel code of operations on machines without an OpenCL or CUDA device.
+
+mod ops;
+
+use core::cell::RefCell;
+use std::{string::String, vec::Vec};
+
+use crate::{
+    flag::AllocFlag, heap::Global, heap::HeapPtr, shape::Shape, Alloc, Buffer, Device, PtrConv,
+};
+
+/// A recorded operation of the [`Mock`] device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCall {
+    /// The name of the operation, e.g. `apply_fn` or `copy_slice_to`.
+    pub op: &'static str,
+    /// The generated [`ToCLSource`](crate::ToCLSource) string of the operation, if it generates kernel code.
+    pub src: Option<String>,
+    /// The lengths of the buffers (or slices) that are passed to the operation, in argument order.
+    pub lens: Vec<usize>,
+}
+
+impl MockCall {
+    /// Creates a [`MockCall`] without kernel code.
+    #[inline]
+    pub fn new(op: &'static str, lens: impl Into<Vec<usize>>) -> MockCall {
+        MockCall {
+            op,
+            src: None,
+            lens: lens.into(),
+        }
+    }
+
+    /// Creates a [`MockCall`] of an operation that generated the kernel code `src`.
+    #[inline]
+    pub fn with_src(
+        op: &'static str,
+        src: impl Into<String>,
+        lens: impl Into<Vec<usize>>,
+    ) -> MockCall {
+        MockCall {
+            op,
+            src: Some(src.into()),
+            lens: lens.into(),
+        }
+    }
+}
+
+/// A device that is used in place of the [`OpenCL`](crate::OpenCL) or [`CUDA`](crate::CUDA) device in tests.
+///
+/// Its buffers live in host memory, but, like the memory of a GPU, they cannot be dereferenced to slices.
+/// Data has to be transferred with [`Read`](crate::Read) and [`WriteBuf`](crate::WriteBuf).
+/// Every call is recorded as a [`MockCall`] and can be inspected with [`Mock::calls`].
+///
+/// # Example
+/// ```
+/// use custos::{ApplyFunction, Buffer, Combiner, Mock, MockCall, Read};
+///
+/// let device = Mock::new();
+/// let buf = Buffer::from((&device, [1., 2., 3.]));
+///
+/// let out = device.apply_fn(&buf, |x| x.mul(2.).add(1.));
+/// assert_eq!(device.read(&out), [3., 5., 7.]);
+///
+/// let apply_fn = device.calls().into_iter().find(|call| call.op == "apply_fn").unwrap();
+/// assert_eq!(apply_fn, MockCall::with_src("apply_fn", "((lhs[id] * 2) + 1)", [3]));
+/// ```
+#[derive(Debug, Default)]
+pub struct Mock {
+    calls: RefCell<Vec<MockCall>>,
+}
+
+impl Mock {
+    /// Creates a [`Mock`] device without any recorded calls.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Mock {
+        Mock::default()
+    }
+
+    /// Returns all calls that were recorded since the device was created or [`cleared`](Mock::clear_calls).
+    #[inline]
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.borrow().clone()
+    }
+
+    /// Returns the most recently recorded call.
+    #[inline]
+    pub fn last_call(&self) -> Option<MockCall> {
+        self.calls.borrow().last().cloned()
+    }
+
+    /// Removes all recorded calls.
+    #[inline]
+    pub fn clear_calls(&self) {
+        self.calls.borrow_mut().clear()
+    }
+
+    /// Records a call. Used by the operations of the device.
+    #[inline]
+    pub fn record(&self, call: MockCall) {
+        self.calls.borrow_mut().push(call)
+    }
+}
+
+impl Device for Mock {
+    type Ptr<U, S: Shape> = HeapPtr<U>;
+    type Cache = ();
+
+    fn new() -> crate::Result<Self> {
+        Ok(Mock::new())
+    }
+}
+
+impl<T, S: Shape> Alloc<'_, T, S> for Mock {
+    fn alloc(&self, mut len: usize, flag: AllocFlag) -> HeapPtr<T> {
+        assert!(len > 0, "invalid buffer len: 0");
+
+        if S::LEN > len {
+            len = S::LEN
+        }
+
+        self.record(MockCall::new("alloc", [len]));
+        HeapPtr::new_zeroed(len, flag, &Global)
+    }
+
+    fn with_slice(&self, data: &[T]) -> HeapPtr<T>
+    where
+        T: Clone,
+    {
+        assert!(!data.is_empty(), "invalid buffer len: 0");
+        assert!(S::LEN <= data.len(), "invalid buffer len: {}", data.len());
+
+        self.record(MockCall::new("with_slice", [data.len()]));
+
+        let ptr = HeapPtr::<T>::new_zeroed(data.len(), AllocFlag::None, &Global);
+        for (idx, value) in data.iter().enumerate() {
+            // the zeroed memory is not a valid `T` that could be dropped
+            unsafe { ptr.ptr.add(idx).write(value.clone()) };
+        }
+        ptr
+    }
+}
+
+impl PtrConv for Mock {
+    #[inline]
+    unsafe fn convert<T, IS: Shape, Conv, OS: Shape>(
+        ptr: &Self::Ptr<T, IS>,
+        flag: AllocFlag,
+    ) -> Self::Ptr<Conv, OS> {
+        HeapPtr {
+            ptr: ptr.ptr as *mut Conv,
+            len: ptr.len,
+            flag,
+            layout: ptr.layout,
+            allocator: ptr.allocator,
+        }
+    }
+}
+
+/// The host memory of a [`Mock`] buffer.
+#[inline]
+fn host_slice<'a, T, S: Shape>(buf: &'a Buffer<T, Mock, S>) -> &'a [T] {
+    if buf.ptr.ptr.is_null() {
+        return &[];
+    }
+    unsafe { core::slice::from_raw_parts(buf.ptr.ptr, buf.len()) }
+}
+
+/// The mutable host memory of a [`Mock`] buffer.
+#[inline]
+fn host_slice_mut<'a, T, S: Shape>(buf: &'a mut Buffer<T, Mock, S>) -> &'a mut [T] {
+    if buf.ptr.ptr.is_null() {
+        return &mut [];
+    }
+    unsafe { core::slice::from_raw_parts_mut(buf.ptr.ptr, buf.len()) }
+}
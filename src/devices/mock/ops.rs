@@ -0,0 +1,121 @@
+use core::ops::{Range, RangeBounds};
+use std::vec::Vec;
+
+use crate::{
+    bounds_to_range, eval_in_lanes, ApplyFunction, Buffer, ClearBuf, CopySlice, Device, Eval,
+    MayToCLSource, Read, Resolve, Shape, ToMarker, WriteBuf,
+};
+
+use super::{host_slice, host_slice_mut, Mock, MockCall};
+
+impl<T, S> ApplyFunction<T, S> for Mock
+where
+    T: Copy + Default,
+    S: Shape,
+{
+    fn apply_fn<F>(
+        &self,
+        buf: &Buffer<T, Self, S>,
+        f: impl Fn(Resolve<T>) -> F + Sync,
+    ) -> Buffer<T, Self, S>
+    where
+        F: Eval<T> + MayToCLSource,
+    {
+        // the same expression is inserted into the OpenCL and CUDA kernels
+        let src = f("lhs[id]".to_marker()).to_cl_source();
+        self.record(MockCall::with_src("apply_fn", src, [buf.len()]));
+
+        let mut out = self.retrieve::<T, S>(buf.len(), buf);
+        let out_slice = host_slice_mut(&mut out);
+
+        eval_in_lanes(host_slice(buf), f, |idx, values| {
+            out_slice[idx..idx + values.len()].copy_from_slice(values)
+        });
+
+        out
+    }
+}
+
+impl<T: Clone, S: Shape> Read<T, S> for Mock {
+    type Read<'a>
+        = Vec<T>
+    where
+        T: 'a,
+        S: 'a;
+
+    #[inline]
+    fn read<'a>(&self, buf: &'a Buffer<T, Mock, S>) -> Self::Read<'a> {
+        self.record(MockCall::new("read", [buf.len()]));
+        host_slice(buf).to_vec()
+    }
+
+    #[inline]
+    fn read_to_vec(&self, buf: &Buffer<T, Mock, S>) -> Vec<T>
+    where
+        T: Default + Clone,
+    {
+        self.record(MockCall::new("read_to_vec", [buf.len()]));
+        host_slice(buf).to_vec()
+    }
+}
+
+impl<T: Clone, S: Shape> WriteBuf<T, S> for Mock {
+    #[inline]
+    fn write(&self, buf: &mut Buffer<T, Mock, S>, data: &[T]) {
+        self.record(MockCall::new("write", [buf.len(), data.len()]));
+        host_slice_mut(buf).clone_from_slice(data)
+    }
+
+    #[inline]
+    fn write_buf(&self, dst: &mut Buffer<T, Mock, S>, src: &Buffer<T, Mock, S>) {
+        self.record(MockCall::new("write_buf", [dst.len(), src.len()]));
+        host_slice_mut(dst).clone_from_slice(host_slice(src))
+    }
+}
+
+impl<T: Default, S: Shape> ClearBuf<T, S> for Mock {
+    fn clear(&self, buf: &mut Buffer<T, Mock, S>) {
+        self.record(MockCall::new("clear", [buf.len()]));
+        for value in host_slice_mut(buf) {
+            *value = T::default();
+        }
+    }
+}
+
+impl<T: Clone> CopySlice<T> for Mock {
+    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, Mock>,
+        source_range: SR,
+        dest: &mut Buffer<T, Mock>,
+        dest_range: DR,
+    ) {
+        let source_range = bounds_to_range(source_range, source.len());
+        let dest_range = bounds_to_range(dest_range, dest.len());
+
+        assert_eq!(
+            source_range.end - source_range.start,
+            dest_range.end - dest_range.start,
+        );
+
+        self.record(MockCall::new(
+            "copy_slice_to",
+            [source.len(), source_range.len(), dest.len()],
+        ));
+
+        host_slice_mut(dest)[dest_range].clone_from_slice(&host_slice(source)[source_range]);
+    }
+
+    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
+        &self,
+        source: &Buffer<T, Mock>,
+        dest: &mut Buffer<T, Mock>,
+        ranges: I,
+    ) {
+        self.record(MockCall::new("copy_slice_all", [source.len(), dest.len()]));
+
+        for (source_range, dest_range) in ranges {
+            self.copy_slice_to(source, source_range, dest, dest_range);
+        }
+    }
+}
@@ -3,7 +3,7 @@
 mod generic_blas;
 pub use generic_blas::*;
 
-#[cfg(feature = "no-std")]
+#[cfg(all(feature = "no-std", not(feature = "alloc")))]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 /// Dummy Ident
 pub struct Ident {
@@ -13,14 +13,17 @@ pub struct Ident {
     pub len: usize,
 }
 
-#[cfg(not(feature = "no-std"))]
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
 mod addons;
-#[cfg(not(feature = "no-std"))]
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
 pub use addons::*;
 
+mod shared;
+pub use shared::*;
+
 use crate::{flag::AllocFlag, shape::Shape, AddGraph, Alloc, Buffer, Device};
 
-#[cfg(not(feature = "no-std"))]
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
 pub mod cache;
 
 #[cfg(not(feature = "no-std"))]
@@ -28,7 +31,7 @@ pub mod cache;
 pub(crate) mod borrowing_cache;
 
 //pub mod cache;
-#[cfg(not(feature = "no-std"))]
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
 pub use cache::*;
 
 //pub use cache::{Cache, CacheReturn};
@@ -45,6 +48,15 @@ pub mod opencl;
 #[cfg(feature = "stack")]
 pub mod stack;
 
+#[cfg(feature = "alloc")]
+pub mod heap;
+
+#[cfg(feature = "arena")]
+pub mod arena;
+
+#[cfg(all(feature = "mock", not(feature = "no-std")))]
+pub mod mock;
+
 #[cfg(feature = "wgpu")]
 pub mod wgpu;
 
@@ -60,9 +72,35 @@ pub use cdatatype::*;
 #[cfg(all(any(feature = "cpu", feature = "stack"), feature = "macro"))]
 mod cpu_stack_ops;
 
+#[cfg(any(
+    feature = "cpu",
+    feature = "stack",
+    feature = "alloc",
+    feature = "arena"
+))]
+pub(crate) mod host_ops;
+
 #[cfg(not(feature = "no-std"))]
-mod ident;
+mod build_options;
 #[cfg(not(feature = "no-std"))]
+pub use build_options::*;
+
+#[cfg(not(feature = "no-std"))]
+mod kernel_disk_cache;
+#[cfg(not(feature = "no-std"))]
+pub use kernel_disk_cache::*;
+
+#[cfg(not(feature = "no-std"))]
+mod kernel_template;
+#[cfg(not(feature = "no-std"))]
+pub use kernel_template::*;
+
+#[cfg(any(feature = "cuda", feature = "opencl", feature = "wgpu"))]
+pub(crate) mod kernel_source;
+
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
+mod ident;
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
 pub use ident::*;
 
 /// Used to convert a device pointer to the a pointer of a different type.
@@ -111,17 +149,17 @@ pub trait CacheAble<D: Device> {
     /// This function is unsafe because it is possible to return multiple `Buffer` with `Ident` that share the same memory.
     /// If this function is called twice with the same `Ident`, the returned `Buffer` will be the same.
     /// Even though the return `Buffer`s are owned, this does not lead to double-frees (see [`AllocFlag`]).
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     unsafe fn get_existing_buf<T, S: Shape>(device: &D, id: Ident) -> Option<Buffer<T, D, S>>;
 
     /// Removes a `Buffer` with the provided [`Ident`] from the cache.
     /// This function is internally called when a `Buffer` with [`AllocFlag`] `None` is dropped.
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     fn remove(device: &D, ident: Ident);
 
     /// Adds a pointer that was allocated by [`Alloc`] to the cache and returns a new corresponding [`Ident`].
     /// This function is internally called when a `Buffer` with [`AllocFlag`] `None` is created.
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     fn add_to_cache<T, S: Shape>(device: &D, ptr: &D::Ptr<T, S>) -> Option<Ident>;
 }
 
@@ -135,17 +173,17 @@ impl<D: Device> CacheAble<D> for () {
         Buffer::new(device, len)
     }
 
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     #[inline]
     fn remove(_device: &D, _ident: Ident) {}
 
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     #[inline]
     fn add_to_cache<T, S: Shape>(_device: &D, _ptr: &<D as Device>::Ptr<T, S>) -> Option<Ident> {
         None
     }
 
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     #[inline]
     unsafe fn get_existing_buf<T, S: Shape>(_device: &D, _id: Ident) -> Option<Buffer<T, D, S>> {
         None
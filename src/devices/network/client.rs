@@ -0,0 +1,233 @@
+use std::{
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use super::protocol::{
+    decode_slice, encode_slice, read_frame, write_frame, AsDataType, BufId, DeviceType,
+    NetworkError, Request, Response,
+};
+
+/// Configures how a [`Client`] connects to a server.
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use custos::network::NetworkConfig;
+///
+/// let config = NetworkConfig {
+///     timeout: Some(Duration::from_secs(5)),
+///     connect_attempts: 5,
+///     ..Default::default()
+/// };
+/// assert_eq!(config.retry_delay, Duration::from_millis(100));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkConfig {
+    /// The maximum duration of a single connection attempt. `None` waits for the operating system.
+    pub connect_timeout: Option<Duration>,
+    /// The maximum duration the client waits for a response. `None` waits forever.
+    pub timeout: Option<Duration>,
+    /// How often connecting is attempted before [`NetworkError::ConnectionFailed`] is returned.
+    pub connect_attempts: u32,
+    /// The pause between two connection attempts.
+    pub retry_delay: Duration,
+}
+
+impl Default for NetworkConfig {
+    #[inline]
+    fn default() -> Self {
+        NetworkConfig {
+            connect_timeout: Some(Duration::from_secs(5)),
+            timeout: None,
+            connect_attempts: 3,
+            retry_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+/// A connection to a custos server.
+pub struct Client {
+    stream: Option<TcpStream>,
+    addrs: Vec<SocketAddr>,
+    config: NetworkConfig,
+    generation: u64,
+}
+
+impl Client {
+    /// Connects to the server at `addr` with the default [`NetworkConfig`].
+    #[inline]
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> crate::Result<Client> {
+        Client::connect_with(addr, NetworkConfig::default())
+    }
+
+    /// Connects to the server at `addr`.
+    pub fn connect_with<A: ToSocketAddrs>(addr: A, config: NetworkConfig) -> crate::Result<Client> {
+        let mut client = Client {
+            stream: None,
+            addrs: addr.to_socket_addrs()?.collect(),
+            config,
+            generation: 0,
+        };
+        client.stream = Some(client.open()?);
+        Ok(client)
+    }
+
+    /// Tries to open a connection to any of the addresses, as often as configured.
+    fn open(&self) -> Result<TcpStream, NetworkError> {
+        for attempt in 0..self.config.connect_attempts.max(1) {
+            if attempt > 0 {
+                std::thread::sleep(self.config.retry_delay);
+            }
+
+            for addr in &self.addrs {
+                let stream = match self.config.connect_timeout {
+                    Some(timeout) => TcpStream::connect_timeout(addr, timeout),
+                    None => TcpStream::connect(addr),
+                };
+
+                let Ok(stream) = stream else {
+                    continue;
+                };
+
+                let configured = stream
+                    .set_nodelay(true)
+                    .and_then(|_| stream.set_read_timeout(self.config.timeout))
+                    .and_then(|_| stream.set_write_timeout(self.config.timeout));
+
+                if configured.is_ok() {
+                    return Ok(stream);
+                }
+            }
+        }
+        Err(NetworkError::ConnectionFailed)
+    }
+
+    /// Replaces the connection with a new one.
+    /// All buffers of the previous connection are freed by the server and the [generation](Client::generation) is increased.
+    pub fn reconnect(&mut self) -> crate::Result<()> {
+        self.stream = None;
+        self.stream = Some(self.open()?);
+        self.generation += 1;
+        Ok(())
+    }
+
+    /// Returns the number of reconnects. Buffer ids are only valid within one generation.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns the [`NetworkConfig`] of this client.
+    #[inline]
+    pub fn config(&self) -> &NetworkConfig {
+        &self.config
+    }
+
+    /// Sends a request and waits for the response.
+    /// Error responses are returned as [`NetworkError::Remote`].
+    ///
+    /// If the connection fails (e.g. a timeout), it is closed, because a late response would be attributed to the next request.
+    /// All following requests return [`NetworkError::Disconnected`] until [`Client::reconnect`] is called.
+    pub fn request(&mut self, request: &Request) -> crate::Result<Response> {
+        let stream = self.stream.as_mut().ok_or(NetworkError::Disconnected)?;
+
+        let payload = write_frame(stream, &request.encode())
+            .and_then(|_| read_frame(stream))
+            .map_err(|err| NetworkError::from_io(&err))
+            .and_then(|payload| payload.ok_or(NetworkError::Disconnected));
+
+        let payload = match payload {
+            Ok(payload) => payload,
+            Err(err) => {
+                self.stream = None;
+                return Err(err.into());
+            }
+        };
+
+        match Response::decode(&payload)? {
+            Response::Err(msg) => Err(NetworkError::Remote(msg).into()),
+            response => Ok(response),
+        }
+    }
+
+    fn request_ok(&mut self, request: &Request) -> crate::Result<()> {
+        match self.request(request)? {
+            Response::Ok => Ok(()),
+            _ => Err(NetworkError::UnexpectedResponse.into()),
+        }
+    }
+
+    fn request_buf(&mut self, request: &Request) -> crate::Result<BufId> {
+        match self.request(request)? {
+            Response::Buf(id) => Ok(id),
+            _ => Err(NetworkError::UnexpectedResponse.into()),
+        }
+    }
+
+    /// Creates the device that hosts the buffers of this connection.
+    pub fn create_device(&mut self, device: DeviceType, idx: u32) -> crate::Result<()> {
+        self.request_ok(&Request::CreateDevice { device, idx })
+    }
+
+    /// Allocates a zeroed buffer with `len` elements.
+    pub fn alloc_buf<T: AsDataType>(&mut self, len: usize) -> crate::Result<BufId> {
+        self.request_buf(&Request::Alloc {
+            dtype: T::DTYPE,
+            len,
+        })
+    }
+
+    /// Deallocates a buffer.
+    pub fn dealloc_buf(&mut self, id: u64) -> crate::Result<()> {
+        self.request_ok(&Request::Dealloc { id })
+    }
+
+    /// Overwrites the contents of a buffer with `data`.
+    pub fn write_buf<T: AsDataType>(&mut self, id: u64, data: &[T]) -> crate::Result<()> {
+        self.request_ok(&Request::Write {
+            id,
+            dtype: T::DTYPE,
+            data: encode_slice(data),
+        })
+    }
+
+    /// Reads the contents of a buffer.
+    pub fn read_buf<T: AsDataType>(&mut self, id: u64) -> crate::Result<Vec<T>> {
+        match self.request(&Request::Read {
+            id,
+            dtype: T::DTYPE,
+        })? {
+            Response::Data(data) => Ok(decode_slice(&data)?),
+            _ => Err(NetworkError::UnexpectedResponse.into()),
+        }
+    }
+
+    /// Sets all elements of a buffer to zero.
+    pub fn clear_buf(&mut self, id: u64) -> crate::Result<()> {
+        self.request_ok(&Request::Clear { id })
+    }
+
+    /// Copies `src[src_start..src_end]` to `dst[dst_start..]`.
+    pub fn copy_slice(
+        &mut self,
+        src: u64,
+        src_start: usize,
+        src_end: usize,
+        dst: u64,
+        dst_start: usize,
+    ) -> crate::Result<()> {
+        self.request_ok(&Request::CopySlice {
+            src,
+            src_start,
+            src_end,
+            dst,
+            dst_start,
+        })
+    }
+
+    /// Applies the function `source` (generated by [`ToCLSource`](crate::ToCLSource)) to every element of a buffer.
+    /// The results are stored in a new buffer.
+    pub fn apply_fn(&mut self, src: u64, source: String) -> crate::Result<BufId> {
+        self.request_buf(&Request::ApplyFn { src, source })
+    }
+}
@@ -0,0 +1,363 @@
+//! Parses and evaluates the source code that is generated by [`ToCLSource`](crate::ToCLSource).
+//! This allows the server to execute functions that were built with a [`Combiner`](crate::Combiner) on the client.
+
+use core::str::FromStr;
+
+use crate::number::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    GEq,
+    LEq,
+    Eq,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Neg,
+    Exp,
+    Sin,
+    Cos,
+    Tan,
+}
+
+/// Element types that expressions can be evaluated with.
+/// Integer arithmetic wraps, like it would in a kernel.
+pub trait ExprValue: Number + FromStr + Send + Sync {
+    /// Returns `None` if the operation is not defined for `Self` (e.g. `sin` for integers).
+    fn unary(self, op: UnaryOp) -> Option<Self>;
+
+    /// Returns `None` if the operation is not defined for `Self` or `rhs` (e.g. a division by zero for integers).
+    fn binary(self, op: BinOp, rhs: Self) -> Option<Self>;
+
+    /// Returns `true` if the operation is defined for `Self`.
+    fn supports(op: UnaryOp) -> bool;
+}
+
+#[inline]
+fn cmp<T: Number>(op: BinOp, lhs: T, rhs: T) -> Option<T> {
+    let res = match op {
+        BinOp::GEq => lhs >= rhs,
+        BinOp::LEq => lhs <= rhs,
+        BinOp::Eq => lhs == rhs,
+        _ => return None,
+    };
+    Some(T::from_usize(res as usize))
+}
+
+macro_rules! impl_float_expr {
+    ($($t:ty),*) => {
+        $(
+            impl ExprValue for $t {
+                #[inline]
+                fn unary(self, op: UnaryOp) -> Option<Self> {
+                    Some(match op {
+                        UnaryOp::Neg => -self,
+                        UnaryOp::Exp => self.exp(),
+                        UnaryOp::Sin => self.sin(),
+                        UnaryOp::Cos => self.cos(),
+                        UnaryOp::Tan => self.tan(),
+                    })
+                }
+
+                #[inline]
+                fn binary(self, op: BinOp, rhs: Self) -> Option<Self> {
+                    Some(match op {
+                        BinOp::Add => self + rhs,
+                        BinOp::Sub => self - rhs,
+                        BinOp::Mul => self * rhs,
+                        BinOp::Div => self / rhs,
+                        BinOp::Pow => self.powf(rhs),
+                        _ => return cmp(op, self, rhs),
+                    })
+                }
+
+                #[inline]
+                fn supports(_op: UnaryOp) -> bool {
+                    true
+                }
+            }
+        )*
+    };
+}
+
+impl_float_expr!(f32, f64);
+
+macro_rules! impl_int_expr {
+    ($signed:literal: $($t:ty),*) => {
+        $(
+            impl ExprValue for $t {
+                #[inline]
+                fn unary(self, op: UnaryOp) -> Option<Self> {
+                    match op {
+                        UnaryOp::Neg if $signed => Some(self.wrapping_neg()),
+                        _ => None,
+                    }
+                }
+
+                #[inline]
+                fn binary(self, op: BinOp, rhs: Self) -> Option<Self> {
+                    match op {
+                        BinOp::Add => Some(self.wrapping_add(rhs)),
+                        BinOp::Sub => Some(self.wrapping_sub(rhs)),
+                        BinOp::Mul => Some(self.wrapping_mul(rhs)),
+                        BinOp::Div => self.checked_div(rhs),
+                        BinOp::Pow => None,
+                        _ => cmp(op, self, rhs),
+                    }
+                }
+
+                #[inline]
+                fn supports(op: UnaryOp) -> bool {
+                    $signed && op == UnaryOp::Neg
+                }
+            }
+        )*
+    };
+}
+
+impl_int_expr!(true: i8, i16, i32, i64);
+impl_int_expr!(false: u8, u16, u32, u64);
+
+/// A parsed expression. `X` is the element the function is applied to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr<T> {
+    X,
+    Lit(T),
+    Unary(UnaryOp, Box<Expr<T>>),
+    Binary(BinOp, Box<Expr<T>>, Box<Expr<T>>),
+}
+
+impl<T: ExprValue> Expr<T> {
+    /// Parses the source code of a combined function.
+    pub fn parse(src: &str) -> Result<Expr<T>, String> {
+        let tokens = tokenize(src)?;
+        let mut parser = Parser { tokens, pos: 0 };
+
+        let expr = parser.expr()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(format!("Unexpected trailing input in `{src}`."));
+        }
+        Ok(expr)
+    }
+
+    /// Evaluates the expression for the element `x`.
+    /// Returns `None` if an operation is undefined for the given values.
+    pub fn eval_at(&self, x: T) -> Option<T> {
+        match self {
+            Expr::X => Some(x),
+            Expr::Lit(value) => Some(*value),
+            Expr::Unary(op, expr) => expr.eval_at(x)?.unary(*op),
+            Expr::Binary(op, lhs, rhs) => lhs.eval_at(x)?.binary(*op, rhs.eval_at(x)?),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Comma,
+    Minus,
+    Op(BinOp),
+    Ident(String),
+    Num(String),
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = src.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            ' ' => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            '-' => Token::Minus,
+            '+' => Token::Op(BinOp::Add),
+            '*' => Token::Op(BinOp::Mul),
+            '/' => Token::Op(BinOp::Div),
+            '>' | '<' | '=' if chars.next_if_eq(&'=').is_some() => Token::Op(match c {
+                '>' => BinOp::GEq,
+                '<' => BinOp::LEq,
+                _ => BinOp::Eq,
+            }),
+            '0'..='9' | '.' => {
+                let mut num = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    num.push(c);
+                }
+                Token::Num(num)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    ident.push(c);
+                }
+                Token::Ident(ident)
+            }
+            c => return Err(format!("Unexpected character `{c}`.")),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    #[inline]
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    #[inline]
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(format!("Expected {expected:?}, found {token:?}.")),
+        }
+    }
+
+    fn lit<T: ExprValue>(num: &str) -> Result<Expr<T>, String> {
+        num.parse()
+            .map(Expr::Lit)
+            .map_err(|_| format!("Invalid literal `{num}`."))
+    }
+
+    fn expr<T: ExprValue>(&mut self) -> Result<Expr<T>, String> {
+        match self.next() {
+            // binary operations are always parenthesized
+            Some(Token::Open) => {
+                let lhs = self.expr()?;
+                let expr = match self.next() {
+                    Some(Token::Close) => return Ok(lhs),
+                    Some(Token::Op(op)) => Expr::Binary(op, Box::new(lhs), Box::new(self.expr()?)),
+                    Some(Token::Minus) => {
+                        Expr::Binary(BinOp::Sub, Box::new(lhs), Box::new(self.expr()?))
+                    }
+                    token => return Err(format!("Expected an operator, found {token:?}.")),
+                };
+                self.expect(Token::Close)?;
+                Ok(expr)
+            }
+            Some(Token::Minus) => match self.tokens.get(self.pos).cloned() {
+                Some(Token::Num(num)) | Some(Token::Ident(num)) if is_special_lit(&num) => {
+                    self.pos += 1;
+                    Self::lit(&format!("-{num}"))
+                }
+                _ => {
+                    if !T::supports(UnaryOp::Neg) {
+                        return Err("`-` is not supported for this type.".into());
+                    }
+                    Ok(Expr::Unary(UnaryOp::Neg, Box::new(self.expr()?)))
+                }
+            },
+            Some(Token::Num(num)) => Self::lit(&num),
+            Some(Token::Ident(ident)) if ident == "x" => Ok(Expr::X),
+            Some(Token::Ident(ident)) if is_special_lit(&ident) => Self::lit(&ident),
+            Some(Token::Ident(ident)) => self.call(&ident),
+            token => Err(format!("Unexpected token {token:?}.")),
+        }
+    }
+
+    fn call<T: ExprValue>(&mut self, func: &str) -> Result<Expr<T>, String> {
+        let op = match func {
+            "exp" => UnaryOp::Exp,
+            "sin" => UnaryOp::Sin,
+            "cos" => UnaryOp::Cos,
+            "tan" => UnaryOp::Tan,
+            "pow" => {
+                if !T::supports(UnaryOp::Exp) {
+                    return Err("`pow` is not supported for this type.".into());
+                }
+                self.expect(Token::Open)?;
+                let base = self.expr()?;
+                self.expect(Token::Comma)?;
+                let exp = self.expr()?;
+                self.expect(Token::Close)?;
+                return Ok(Expr::Binary(BinOp::Pow, Box::new(base), Box::new(exp)));
+            }
+            _ => return Err(format!("Unknown function `{func}`.")),
+        };
+
+        if !T::supports(op) {
+            return Err(format!("`{func}` is not supported for this type."));
+        }
+
+        self.expect(Token::Open)?;
+        let arg = self.expr()?;
+        self.expect(Token::Close)?;
+        Ok(Expr::Unary(op, Box::new(arg)))
+    }
+}
+
+/// Number literals and the non-finite float literals (`inf`, `NaN`) that are produced by `to_string`.
+#[inline]
+fn is_special_lit(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_digit() || c == '.') || token == "inf" || token == "NaN"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Expr;
+    use crate::{Combiner, Resolve, ToCLSource};
+
+    fn source<F: ToCLSource>(f: impl Fn(Resolve<f32>) -> F) -> String {
+        f(Resolve::with_marker("x")).to_cl_source()
+    }
+
+    #[test]
+    fn test_parse_combined() {
+        let src = source(|x| x.mul(2.).add(x.sin()).sub(-1.5).div(x.pow(3.)));
+        let expr = Expr::<f32>::parse(&src).unwrap();
+
+        let x = 0.7f32;
+        let expected = (x * 2. + x.sin() - -1.5) / x.powf(3.);
+        assert_eq!(expr.eval_at(x), Some(expected));
+
+        let src = source(|x| x.neg().exp().geq(0.5));
+        let expr = Expr::<f32>::parse(&src).unwrap();
+        assert_eq!(expr.eval_at(0.1), Some(1.));
+        assert_eq!(expr.eval_at(2.), Some(0.));
+    }
+
+    #[test]
+    fn test_parse_int() {
+        let expr = Expr::<i32>::parse("((x * -3) / (x - 2))").unwrap();
+        assert_eq!(expr.eval_at(4), Some(-6));
+        assert_eq!(expr.eval_at(2), None);
+
+        assert!(Expr::<u8>::parse("-(x)").is_err());
+        assert!(Expr::<i32>::parse("sin(x)").is_err());
+        assert!(Expr::<i32>::parse("(x + 1.5)").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Expr::<f32>::parse("(x + 1").is_err());
+        assert!(Expr::<f32>::parse("x x").is_err());
+        assert!(Expr::<f32>::parse("float2_mul(x, x)").is_err());
+        assert!(Expr::<f32>::parse("y").is_err());
+    }
+
+    #[test]
+    fn test_parse_special_floats() {
+        let expr = Expr::<f64>::parse("(x + -inf)").unwrap();
+        assert_eq!(expr.eval_at(1.), Some(f64::NEG_INFINITY));
+
+        let expr = Expr::<f64>::parse("(x * NaN)").unwrap();
+        assert!(expr.eval_at(1.).unwrap().is_nan());
+    }
+}
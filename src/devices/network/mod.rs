@@ -1,3 +1,12 @@
+//! The network module provides a device that executes operations on a remote custos server.
+
+mod client;
+mod expr;
 mod network_device;
+pub mod protocol;
+mod server;
 
+pub use client::*;
 pub use network_device::*;
+pub use protocol::{DeviceType, NetworkError};
+pub use server::*;
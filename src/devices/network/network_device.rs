@@ -1,38 +1,209 @@
+use super::{
+    protocol::{AsDataType, DeviceType, NetworkError},
+    Client, NetworkConfig,
+};
 use crate::{
-    flag::AllocFlag, Alloc, Buffer, ClearBuf, Device, DeviceError, Error, GlobalCount, Graph,
-    GraphReturn, PtrType, Read, Shape,
+    bounds_to_range, flag::AllocFlag, number::Number, Alloc, ApplyFunction, Buffer, ClearBuf,
+    CopySlice, Device, DeviceError, Eval, GlobalCount, Graph, GraphReturn, MayToCLSource, PtrType,
+    Read, Resolve, Shape, Shared, SharedRef, SharedRefMut, WriteBuf,
 };
 use core::{
-    cell::{Ref, RefCell, RefMut},
+    cell::RefCell,
     marker::PhantomData,
+    ops::{Range, RangeBounds},
 };
-use cuw::AsDataType;
-use cuwanto_client as cuw;
-use std::net::ToSocketAddrs;
-
-pub struct Network<'a> {
-    pub cuw_client: RefCell<cuw::Client>,
-    pub graph: RefCell<Graph<GlobalCount>>,
-    _p: PhantomData<&'a u8>,
+use std::{net::ToSocketAddrs, rc::Rc};
+
+/// A device that performs all operations on a remote device that is hosted by a custos server (see [`serve`](crate::network::serve)).
+///
+/// All trait implementations panic if the communication with the server fails.
+/// The `try_*` methods return the error instead (usually a [`NetworkError`]).
+///
+/// # Example
+/// ```no_run
+/// use custos::{Buffer, Network, network::DeviceType};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = Network::new("127.0.0.1:11001", DeviceType::CPU)?;
+///
+///     let buf = Buffer::from((&device, [1., 2., 3.]));
+///     assert_eq!(device.try_read(&buf)?, [1., 2., 3.]);
+///     Ok(())
+/// }
+/// ```
+pub struct Network {
+    /// The connection to the server. It is shared with the [`NetworkArray`]s, which free themselves on drop.
+    pub client: Rc<RefCell<Client>>,
+    /// An optimizeable [`Graph`].
+    pub graph: Shared<Graph<GlobalCount>>,
+    device_type: DeviceType,
 }
 
-impl<'a> Network<'a> {
-    pub fn new<A: ToSocketAddrs>(addr: A, device: cuw::DeviceType) -> crate::Result<Network<'a>> {
-        let mut cuw_client = cuw::Client::connect(addr)?;
+impl Network {
+    /// Connects to the server at `addr` and creates a remote device of type `device`.
+    /// Uses the default [`NetworkConfig`].
+    #[inline]
+    pub fn new<A: ToSocketAddrs>(addr: A, device: DeviceType) -> crate::Result<Network> {
+        Network::with_config(addr, device, NetworkConfig::default())
+    }
+
+    /// Connects to the server at `addr` and creates a remote device of type `device`.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use custos::{Network, network::{DeviceType, NetworkConfig}};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let config = NetworkConfig {
+    ///         timeout: Some(Duration::from_secs(10)),
+    ///         connect_attempts: 10,
+    ///         ..Default::default()
+    ///     };
+    ///     let device = Network::with_config("127.0.0.1:11001", DeviceType::CPU, config)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_config<A: ToSocketAddrs>(
+        addr: A,
+        device: DeviceType,
+        config: NetworkConfig,
+    ) -> crate::Result<Network> {
+        let mut client = Client::connect_with(addr, config)?;
 
         // should receive error if this is missing
-        cuw_client.create_device(device, 0)?;
+        client.create_device(device, 0)?;
 
         Ok(Network {
-            cuw_client: RefCell::new(cuw_client),
-            graph: RefCell::new(Graph::new()),
+            client: Rc::new(RefCell::new(client)),
+            graph: Shared::new(Graph::new()),
+            device_type: device,
+        })
+    }
+
+    /// Establishes a new connection, e.g. after a [`NetworkError::Disconnected`] or [`NetworkError::Timeout`].
+    /// The server frees all buffers of the previous connection, using them returns [`NetworkError::StaleBuffer`].
+    pub fn reconnect(&self) -> crate::Result<()> {
+        let mut client = self.client.borrow_mut();
+        client.reconnect()?;
+        client.create_device(self.device_type, 0)
+    }
+
+    /// Returns the id of the remote buffer, if it belongs to the current connection.
+    fn remote_id<T>(&self, buf: &Buffer<T, Network>) -> crate::Result<u64> {
+        if buf.ptr.generation != self.client.borrow().generation() {
+            return Err(NetworkError::StaleBuffer.into());
+        }
+        Ok(buf.ptr.id)
+    }
+
+    /// Allocates a zeroed remote buffer with `len` elements.
+    pub fn try_alloc<T: AsDataType>(
+        &self,
+        len: usize,
+        flag: AllocFlag,
+    ) -> crate::Result<NetworkArray<T>> {
+        let mut client = self.client.borrow_mut();
+        let id = client.alloc_buf::<T>(len)?;
+
+        Ok(NetworkArray {
+            id: id.id,
+            len: id.len,
+            flag,
+            generation: client.generation(),
+            client: self.client.clone(),
             _p: PhantomData,
         })
     }
+
+    /// Allocates a remote buffer with the contents of `data`.
+    pub fn try_with_slice<T: AsDataType>(&self, data: &[T]) -> crate::Result<NetworkArray<T>> {
+        let array = self.try_alloc(data.len(), AllocFlag::None)?;
+        self.client.borrow_mut().write_buf(array.id, data)?;
+        Ok(array)
+    }
+
+    /// Reads the contents of a buffer.
+    pub fn try_read<T: AsDataType>(&self, buf: &Buffer<T, Network>) -> crate::Result<Vec<T>> {
+        let id = self.remote_id(buf)?;
+        self.client.borrow_mut().read_buf(id)
+    }
+
+    /// Overwrites the contents of a buffer with `data`.
+    pub fn try_write<T: AsDataType>(
+        &self,
+        buf: &mut Buffer<T, Network>,
+        data: &[T],
+    ) -> crate::Result<()> {
+        let id = self.remote_id(buf)?;
+        self.client.borrow_mut().write_buf(id, data)
+    }
+
+    /// Sets all elements of a buffer to zero.
+    pub fn try_clear<T>(&self, buf: &mut Buffer<T, Network>) -> crate::Result<()> {
+        let id = self.remote_id(buf)?;
+        self.client.borrow_mut().clear_buf(id)
+    }
+
+    /// Copies a slice of the source buffer into a slice of the destination buffer.
+    pub fn try_copy_slice_to<T, SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, Network>,
+        source_range: SR,
+        dest: &mut Buffer<T, Network>,
+        dest_range: DR,
+    ) -> crate::Result<()> {
+        let source_range = bounds_to_range(source_range, source.len());
+        let dest_range = bounds_to_range(dest_range, dest.len());
+
+        assert_eq!(
+            source_range.end - source_range.start,
+            dest_range.end - dest_range.start,
+        );
+
+        let (source, dest) = (self.remote_id(source)?, self.remote_id(dest)?);
+        self.client.borrow_mut().copy_slice(
+            source,
+            source_range.start,
+            source_range.end,
+            dest,
+            dest_range.start,
+        )
+    }
+
+    /// Applies a function to a buffer and returns a new buffer.
+    /// The function is sent to the server as source code (see [`ToCLSource`](crate::ToCLSource)) and evaluated there.
+    pub fn try_apply_fn<T, F>(
+        &self,
+        buf: &Buffer<T, Network>,
+        f: impl Fn(Resolve<T>) -> F,
+    ) -> crate::Result<Buffer<'_, T, Network>>
+    where
+        T: AsDataType + Number,
+        F: Eval<T> + MayToCLSource,
+    {
+        let id = self.remote_id(buf)?;
+
+        let source = f(Resolve::with_marker("x")).to_cl_source();
+        let mut client = self.client.borrow_mut();
+        let id = client.apply_fn(id, source)?;
+
+        Ok(Buffer {
+            ptr: NetworkArray {
+                id: id.id,
+                len: id.len,
+                flag: AllocFlag::None,
+                generation: client.generation(),
+                client: self.client.clone(),
+                _p: PhantomData,
+            },
+            device: Some(self),
+            ident: None,
+        })
+    }
 }
 
-impl<'a> Device for Network<'a> {
-    type Ptr<U, S: Shape> = NetworkArray<'a, U>;
+impl Device for Network {
+    type Ptr<U, S: Shape> = NetworkArray<U>;
     type Cache = ();
 
     fn new() -> crate::Result<Self> {
@@ -40,54 +211,41 @@ impl<'a> Device for Network<'a> {
     }
 }
 
-impl<'a, T: cuw::AsDataType, S: Shape> Alloc<'a, T, S> for Network<'a> {
+impl<'a, T: AsDataType, S: Shape> Alloc<'a, T, S> for Network {
+    #[inline]
     fn alloc(&'a self, len: usize, flag: AllocFlag) -> <Self as Device>::Ptr<T, S> {
-        let id = self
-            .cuw_client
-            .borrow_mut()
-            .alloc_buf::<T>(len as u32)
-            .unwrap();
-
-        NetworkArray {
-            id,
-            client: &self.cuw_client,
-            _p: PhantomData,
-        }
+        self.try_alloc(len, flag).unwrap()
     }
 
+    #[inline]
     fn with_slice(&'a self, data: &[T]) -> <Self as Device>::Ptr<T, S>
     where
         T: Clone,
     {
-        let array: NetworkArray<T> = Alloc::<T, ()>::alloc(self, data.len(), AllocFlag::None);
-        self.cuw_client
-            .borrow_mut()
-            .write_buf(array.id, data)
-            .unwrap();
-        array
+        self.try_with_slice(data).unwrap()
     }
 }
 
-impl<'a> GraphReturn for Network<'a> {
+impl GraphReturn for Network {
     #[inline]
-    fn graph(&self) -> Ref<Graph<GlobalCount>> {
+    fn graph(&self) -> SharedRef<Graph<GlobalCount>> {
         self.graph.borrow()
     }
 
     #[inline]
-    fn graph_mut(&self) -> RefMut<Graph<GlobalCount>> {
+    fn graph_mut(&self) -> SharedRefMut<Graph<GlobalCount>> {
         self.graph.borrow_mut()
     }
 }
 
-impl<'b, T: AsDataType + Clone + Default> Read<T> for Network<'b> {
+impl<T: AsDataType> Read<T> for Network {
     type Read<'a> = Vec<T>
     where
         T: 'a,
-        Network<'b>: 'a;
+        Network: 'a;
 
     #[inline]
-    fn read<'a>(&self, buf: &'a Buffer<T, Network<'b>>) -> Self::Read<'a> {
+    fn read<'a>(&self, buf: &'a Buffer<T, Network>) -> Self::Read<'a> {
         self.read_to_vec(buf)
     }
 
@@ -96,36 +254,105 @@ impl<'b, T: AsDataType + Clone + Default> Read<T> for Network<'b> {
     where
         T: Default + Clone,
     {
-        self.cuw_client.borrow_mut().read_buf(buf.ptr.id).unwrap()
+        self.try_read(buf).unwrap()
+    }
+}
+
+impl<T: AsDataType> WriteBuf<T> for Network {
+    #[inline]
+    fn write(&self, buf: &mut Buffer<T, Network>, data: &[T]) {
+        self.try_write(buf, data).unwrap()
+    }
+
+    #[inline]
+    fn write_buf(&self, dst: &mut Buffer<T, Network>, src: &Buffer<T, Network>) {
+        self.copy_slice_to(src, .., dst, ..)
     }
 }
 
-impl<'a, T> ClearBuf<T> for Network<'a> {
-    fn clear(&self, buf: &mut Buffer<T, Network<'a>>) {
-        todo!()
+impl<T> ClearBuf<T> for Network {
+    #[inline]
+    fn clear(&self, buf: &mut Buffer<T, Network>) {
+        self.try_clear(buf).unwrap()
     }
 }
 
-pub struct NetworkArray<'a, T> {
-    id: cuw::BufId,
-    client: &'a RefCell<cuw::client::Client>,
+impl<T> CopySlice<T> for Network {
+    #[inline]
+    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, Network>,
+        source_range: SR,
+        dest: &mut Buffer<T, Network>,
+        dest_range: DR,
+    ) {
+        self.try_copy_slice_to(source, source_range, dest, dest_range)
+            .unwrap()
+    }
+
+    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
+        &self,
+        source: &Buffer<T, Network>,
+        dest: &mut Buffer<T, Network>,
+        ranges: I,
+    ) {
+        for (source_range, dest_range) in ranges {
+            self.copy_slice_to(source, source_range, dest, dest_range);
+        }
+    }
+}
+
+impl<T: AsDataType + Number> ApplyFunction<T> for Network {
+    #[inline]
+    fn apply_fn<F>(
+        &self,
+        buf: &Buffer<T, Network>,
+        f: impl Fn(Resolve<T>) -> F + Sync,
+    ) -> Buffer<T, Self>
+    where
+        F: Eval<T> + MayToCLSource,
+    {
+        self.try_apply_fn(buf, f).unwrap()
+    }
+}
+
+/// The pointer of a [`Network`] buffer. It identifies a buffer that lives on the server.
+pub struct NetworkArray<T> {
+    /// The id of the buffer on the server.
+    pub id: u64,
+    /// The element count.
+    pub len: usize,
+    /// Allocation flag for the pointer
+    pub flag: AllocFlag,
+    /// The [generation](Client::generation) of the connection that allocated the buffer.
+    pub generation: u64,
+    client: Rc<RefCell<Client>>,
     _p: PhantomData<T>,
 }
 
-impl<'a, T> PtrType for NetworkArray<'a, T> {
+impl<T> PtrType for NetworkArray<T> {
     #[inline]
     fn size(&self) -> usize {
-        self.id.len as usize
+        self.len
     }
 
     #[inline]
     fn flag(&self) -> AllocFlag {
-        AllocFlag::None
+        self.flag
     }
 }
 
-/*impl<'a, T> Dealloc<T> for NetworkArray<'a, T> {
-    unsafe fn dealloc(&mut self, _len: usize) {
-        self.client.borrow_mut().dealloc_buf(self.id).unwrap();
+impl<T> Drop for NetworkArray<T> {
+    fn drop(&mut self) {
+        if !matches!(self.flag, AllocFlag::None) {
+            return;
+        }
+
+        // a failed dealloc can't be reported, the server frees the buffer when the connection is closed anyway
+        if let Ok(mut client) = self.client.try_borrow_mut() {
+            if client.generation() == self.generation {
+                let _ = client.dealloc_buf(self.id);
+            }
+        }
     }
-}*/
+}
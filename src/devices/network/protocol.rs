@@ -0,0 +1,577 @@
+//! The binary wire protocol that is spoken between a [`Network`](crate::Network) device and a custos server.
+//!
+//! Every message is sent as a frame: the length of the payload as a little endian `u64`, followed by the payload.
+//! The first byte of a payload is the message tag. All numbers are encoded in little endian byte order.
+
+use std::io::{self, Read, Write};
+
+/// The type of the device that is hosted by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    /// The host [`CPU`](crate::CPU).
+    CPU,
+}
+
+impl DeviceType {
+    #[inline]
+    fn from_tag(tag: u8) -> Option<DeviceType> {
+        match tag {
+            0 => Some(DeviceType::CPU),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn tag(self) -> u8 {
+        match self {
+            DeviceType::CPU => 0,
+        }
+    }
+}
+
+/// The element type of a remote buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DType {
+    /// `u8`
+    U8,
+    /// `u16`
+    U16,
+    /// `u32`
+    U32,
+    /// `u64`
+    U64,
+    /// `i8`
+    I8,
+    /// `i16`
+    I16,
+    /// `i32`
+    I32,
+    /// `i64`
+    I64,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+}
+
+impl DType {
+    const ALL: [DType; 10] = [
+        DType::U8,
+        DType::U16,
+        DType::U32,
+        DType::U64,
+        DType::I8,
+        DType::I16,
+        DType::I32,
+        DType::I64,
+        DType::F32,
+        DType::F64,
+    ];
+
+    /// Returns the size of one element in bytes.
+    #[inline]
+    pub fn size(self) -> usize {
+        match self {
+            DType::U8 | DType::I8 => 1,
+            DType::U16 | DType::I16 => 2,
+            DType::U32 | DType::I32 | DType::F32 => 4,
+            DType::U64 | DType::I64 | DType::F64 => 8,
+        }
+    }
+
+    #[inline]
+    fn from_tag(tag: u8) -> Option<DType> {
+        DType::ALL.get(tag as usize).copied()
+    }
+
+    #[inline]
+    fn tag(self) -> u8 {
+        DType::ALL.iter().position(|dtype| *dtype == self).unwrap() as u8
+    }
+}
+
+/// Element types that can be sent over the network.
+pub trait AsDataType: Copy + Default {
+    /// The [`DType`] of `Self`.
+    const DTYPE: DType;
+
+    /// Appends the little endian bytes of `self` to `out`.
+    fn write_le(&self, out: &mut Vec<u8>);
+
+    /// Reads `Self` from exactly [`DType::size`] little endian bytes.
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_as_data_type {
+    ($($t:ty: $dtype:ident),*) => {
+        $(
+            impl AsDataType for $t {
+                const DTYPE: DType = DType::$dtype;
+
+                #[inline]
+                fn write_le(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes())
+                }
+
+                #[inline]
+                fn read_le(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_as_data_type! {
+    u8: U8, u16: U16, u32: U32, u64: U64,
+    i8: I8, i16: I16, i32: I32, i64: I64,
+    f32: F32, f64: F64
+}
+
+/// Encodes a slice of elements into little endian bytes.
+pub fn encode_slice<T: AsDataType>(data: &[T]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(data.len() * T::DTYPE.size());
+    for value in data {
+        value.write_le(&mut bytes);
+    }
+    bytes
+}
+
+/// Decodes little endian bytes into a vector of elements.
+pub fn decode_slice<T: AsDataType>(bytes: &[u8]) -> Result<Vec<T>, NetworkError> {
+    let size = T::DTYPE.size();
+    if bytes.len() % size != 0 {
+        return Err(NetworkError::InvalidMessage);
+    }
+    Ok(bytes.chunks_exact(size).map(T::read_le).collect())
+}
+
+/// Identifies a buffer that lives on the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufId {
+    /// The id that is assigned by the server.
+    pub id: u64,
+    /// The element count of the buffer.
+    pub len: usize,
+}
+
+/// A request that is sent from the client to the server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    /// Creates the device that hosts all following buffers of this connection.
+    CreateDevice {
+        /// The type of the device.
+        device: DeviceType,
+        /// The index of the device.
+        idx: u32,
+    },
+    /// Allocates a zeroed buffer.
+    Alloc {
+        /// The element type.
+        dtype: DType,
+        /// The element count.
+        len: usize,
+    },
+    /// Deallocates a buffer.
+    Dealloc {
+        /// The id of the buffer.
+        id: u64,
+    },
+    /// Overwrites the contents of a buffer.
+    Write {
+        /// The id of the buffer.
+        id: u64,
+        /// The element type of `data`.
+        dtype: DType,
+        /// The new contents in little endian bytes.
+        data: Vec<u8>,
+    },
+    /// Reads the contents of a buffer.
+    Read {
+        /// The id of the buffer.
+        id: u64,
+        /// The expected element type.
+        dtype: DType,
+    },
+    /// Sets all elements of a buffer to zero.
+    Clear {
+        /// The id of the buffer.
+        id: u64,
+    },
+    /// Copies `src[src_start..src_end]` to `dst[dst_start..]`.
+    CopySlice {
+        /// The id of the source buffer.
+        src: u64,
+        /// The start of the source range.
+        src_start: usize,
+        /// The end (exclusive) of the source range.
+        src_end: usize,
+        /// The id of the destination buffer.
+        dst: u64,
+        /// The start of the destination range.
+        dst_start: usize,
+    },
+    /// Applies a function to every element of a buffer and stores the results in a new buffer.
+    ApplyFn {
+        /// The id of the source buffer.
+        src: u64,
+        /// The function as generated by [`ToCLSource`](crate::ToCLSource), with `x` as the element.
+        source: String,
+    },
+}
+
+/// A response that is sent from the server to the client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response {
+    /// The request succeeded.
+    Ok,
+    /// A buffer was created.
+    Buf(BufId),
+    /// The contents of a buffer in little endian bytes.
+    Data(Vec<u8>),
+    /// The request failed on the server.
+    Err(String),
+}
+
+/// Errors that can occur while communicating with a custos server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    /// A malformed message was received.
+    InvalidMessage,
+    /// The server answered with an unexpected response.
+    UnexpectedResponse,
+    /// The server failed to process a request.
+    Remote(String),
+    /// No connection to the server could be established.
+    ConnectionFailed,
+    /// The server did not answer in time (see [`NetworkConfig::timeout`](crate::network::NetworkConfig::timeout)).
+    Timeout,
+    /// The connection to the server was lost. Use [`Network::reconnect`](crate::Network::reconnect) to establish a new one.
+    Disconnected,
+    /// The buffer was allocated on a previous connection, which freed it.
+    StaleBuffer,
+}
+
+impl NetworkError {
+    /// Classifies an I/O error that occurred on an established connection.
+    pub fn from_io(err: &io::Error) -> NetworkError {
+        match err.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => NetworkError::Timeout,
+            io::ErrorKind::InvalidData => NetworkError::InvalidMessage,
+            _ => NetworkError::Disconnected,
+        }
+    }
+}
+
+impl core::fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            NetworkError::InvalidMessage => write!(f, "Received a malformed network message."),
+            NetworkError::UnexpectedResponse => {
+                write!(f, "The server answered with an unexpected response.")
+            }
+            NetworkError::Remote(msg) => write!(f, "The server failed to process a request: {msg}"),
+            NetworkError::ConnectionFailed => write!(f, "Could not connect to the server."),
+            NetworkError::Timeout => write!(f, "The server did not answer in time."),
+            NetworkError::Disconnected => write!(f, "The connection to the server was lost."),
+            NetworkError::StaleBuffer => write!(
+                f,
+                "The buffer belongs to a previous connection and was freed by the server."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+impl From<NetworkError> for io::Error {
+    #[inline]
+    fn from(err: NetworkError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// Writes the fields of a message.
+#[derive(Default)]
+struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    #[inline]
+    fn u8(mut self, value: u8) -> Self {
+        self.bytes.push(value);
+        self
+    }
+
+    #[inline]
+    fn u64(mut self, value: u64) -> Self {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    #[inline]
+    fn bytes(self, value: &[u8]) -> Self {
+        let mut enc = self.u64(value.len() as u64);
+        enc.bytes.extend_from_slice(value);
+        enc
+    }
+}
+
+/// Reads the fields of a message.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], NetworkError> {
+        if self.bytes.len() < len {
+            return Err(NetworkError::InvalidMessage);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    #[inline]
+    fn u8(&mut self) -> Result<u8, NetworkError> {
+        Ok(self.take(1)?[0])
+    }
+
+    #[inline]
+    fn u64(&mut self) -> Result<u64, NetworkError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    #[inline]
+    fn usize(&mut self) -> Result<usize, NetworkError> {
+        usize::try_from(self.u64()?).map_err(|_| NetworkError::InvalidMessage)
+    }
+
+    #[inline]
+    fn bytes(&mut self) -> Result<Vec<u8>, NetworkError> {
+        let len = self.usize()?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    #[inline]
+    fn string(&mut self) -> Result<String, NetworkError> {
+        String::from_utf8(self.bytes()?).map_err(|_| NetworkError::InvalidMessage)
+    }
+
+    #[inline]
+    fn dtype(&mut self) -> Result<DType, NetworkError> {
+        DType::from_tag(self.u8()?).ok_or(NetworkError::InvalidMessage)
+    }
+
+    /// Fails if there are bytes left.
+    #[inline]
+    fn finish<T>(self, value: T) -> Result<T, NetworkError> {
+        if !self.bytes.is_empty() {
+            return Err(NetworkError::InvalidMessage);
+        }
+        Ok(value)
+    }
+}
+
+impl Request {
+    /// Encodes the request into a payload.
+    pub fn encode(&self) -> Vec<u8> {
+        let enc = Encoder::default();
+        let enc = match self {
+            Request::CreateDevice { device, idx } => enc.u8(0).u8(device.tag()).u64(*idx as u64),
+            Request::Alloc { dtype, len } => enc.u8(1).u8(dtype.tag()).u64(*len as u64),
+            Request::Dealloc { id } => enc.u8(2).u64(*id),
+            Request::Write { id, dtype, data } => enc.u8(3).u64(*id).u8(dtype.tag()).bytes(data),
+            Request::Read { id, dtype } => enc.u8(4).u64(*id).u8(dtype.tag()),
+            Request::Clear { id } => enc.u8(5).u64(*id),
+            Request::CopySlice {
+                src,
+                src_start,
+                src_end,
+                dst,
+                dst_start,
+            } => enc
+                .u8(6)
+                .u64(*src)
+                .u64(*src_start as u64)
+                .u64(*src_end as u64)
+                .u64(*dst)
+                .u64(*dst_start as u64),
+            Request::ApplyFn { src, source } => enc.u8(7).u64(*src).bytes(source.as_bytes()),
+        };
+        enc.bytes
+    }
+
+    /// Decodes a request from a payload.
+    pub fn decode(payload: &[u8]) -> Result<Request, NetworkError> {
+        let mut dec = Decoder { bytes: payload };
+
+        let request = match dec.u8()? {
+            0 => Request::CreateDevice {
+                device: DeviceType::from_tag(dec.u8()?).ok_or(NetworkError::InvalidMessage)?,
+                idx: u32::try_from(dec.u64()?).map_err(|_| NetworkError::InvalidMessage)?,
+            },
+            1 => Request::Alloc {
+                dtype: dec.dtype()?,
+                len: dec.usize()?,
+            },
+            2 => Request::Dealloc { id: dec.u64()? },
+            3 => Request::Write {
+                id: dec.u64()?,
+                dtype: dec.dtype()?,
+                data: dec.bytes()?,
+            },
+            4 => Request::Read {
+                id: dec.u64()?,
+                dtype: dec.dtype()?,
+            },
+            5 => Request::Clear { id: dec.u64()? },
+            6 => Request::CopySlice {
+                src: dec.u64()?,
+                src_start: dec.usize()?,
+                src_end: dec.usize()?,
+                dst: dec.u64()?,
+                dst_start: dec.usize()?,
+            },
+            7 => Request::ApplyFn {
+                src: dec.u64()?,
+                source: dec.string()?,
+            },
+            _ => return Err(NetworkError::InvalidMessage),
+        };
+        dec.finish(request)
+    }
+}
+
+impl Response {
+    /// Encodes the response into a payload.
+    pub fn encode(&self) -> Vec<u8> {
+        let enc = Encoder::default();
+        let enc = match self {
+            Response::Ok => enc.u8(0),
+            Response::Buf(buf) => enc.u8(1).u64(buf.id).u64(buf.len as u64),
+            Response::Data(data) => enc.u8(2).bytes(data),
+            Response::Err(msg) => enc.u8(3).bytes(msg.as_bytes()),
+        };
+        enc.bytes
+    }
+
+    /// Decodes a response from a payload.
+    pub fn decode(payload: &[u8]) -> Result<Response, NetworkError> {
+        let mut dec = Decoder { bytes: payload };
+
+        let response = match dec.u8()? {
+            0 => Response::Ok,
+            1 => Response::Buf(BufId {
+                id: dec.u64()?,
+                len: dec.usize()?,
+            }),
+            2 => Response::Data(dec.bytes()?),
+            3 => Response::Err(dec.string()?),
+            _ => return Err(NetworkError::InvalidMessage),
+        };
+        dec.finish(response)
+    }
+}
+
+/// Writes a payload as a frame.
+pub fn write_frame(stream: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    stream.write_all(&(payload.len() as u64).to_le_bytes())?;
+    stream.write_all(payload)?;
+    stream.flush()
+}
+
+/// Reads the payload of a frame.
+/// Returns `None` if the stream was closed before a new frame started.
+pub fn read_frame(stream: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 8];
+    match stream.read_exact(&mut len) {
+        Ok(()) => (),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = u64::from_le_bytes(len);
+
+    // the payload is not preallocated, hence a malformed length can't exhaust the memory
+    let mut payload = Vec::new();
+    stream.take(len).read_to_end(&mut payload)?;
+
+    if payload.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(Some(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_slice, encode_slice, read_frame, write_frame, DType, Request, Response};
+
+    #[test]
+    fn test_request_round_trip() {
+        let requests = [
+            Request::Alloc {
+                dtype: DType::F64,
+                len: 12,
+            },
+            Request::Write {
+                id: 3,
+                dtype: DType::I16,
+                data: encode_slice(&[1i16, -2, 3]),
+            },
+            Request::CopySlice {
+                src: 1,
+                src_start: 2,
+                src_end: 4,
+                dst: 5,
+                dst_start: 0,
+            },
+            Request::ApplyFn {
+                src: 9,
+                source: "((x + 1) * x)".into(),
+            },
+        ];
+
+        for request in requests {
+            assert_eq!(Request::decode(&request.encode()).unwrap(), request);
+        }
+    }
+
+    #[test]
+    fn test_response_frames() {
+        let mut stream = Vec::new();
+        write_frame(&mut stream, &Response::Data(vec![1, 2, 3]).encode()).unwrap();
+        write_frame(&mut stream, &Response::Err("no".into()).encode()).unwrap();
+
+        let mut stream = stream.as_slice();
+        let first = read_frame(&mut stream).unwrap().unwrap();
+        let second = read_frame(&mut stream).unwrap().unwrap();
+
+        assert_eq!(
+            Response::decode(&first).unwrap(),
+            Response::Data(vec![1, 2, 3])
+        );
+        assert_eq!(
+            Response::decode(&second).unwrap(),
+            Response::Err("no".into())
+        );
+        assert_eq!(read_frame(&mut stream).unwrap(), None);
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(Request::decode(&[]).is_err());
+        assert!(Request::decode(&[1, 200]).is_err());
+        assert!(Response::decode(&[0, 0]).is_err());
+        assert!(decode_slice::<f32>(&[0, 0, 0]).is_err());
+
+        let mut truncated = &[5, 0, 0, 0, 0, 0, 0, 0, 1, 2][..];
+        assert!(read_frame(&mut truncated).is_err());
+    }
+
+    #[test]
+    fn test_slice_encoding() {
+        let data = [1.5f32, -2., 0.25];
+        assert_eq!(decode_slice::<f32>(&encode_slice(&data)).unwrap(), data);
+    }
+}
@@ -0,0 +1,387 @@
+//! A server that hosts a local device for [`Network`](crate::Network) clients.
+//! Every connection gets its own device.
+
+use std::{
+    collections::HashMap,
+    net::{TcpListener, TcpStream},
+};
+
+use super::{
+    expr::{Expr, ExprValue},
+    protocol::{
+        decode_slice, encode_slice, read_frame, write_frame, AsDataType, BufId, DType, DeviceType,
+        Request, Response,
+    },
+};
+use crate::{Buffer, ClearBuf, CopySlice, WriteBuf, CPU};
+
+/// The default maximum size of a single buffer on the server: 1 GiB.
+pub const DEFAULT_MAX_ALLOC_SIZE: usize = 1024 * 1024 * 1024;
+
+/// Configures the limits of a server.
+/// # Example
+/// ```
+/// use custos::network::ServerConfig;
+///
+/// let config = ServerConfig {
+///     max_alloc_size: 64 * 1024 * 1024,
+/// };
+/// assert!(config.max_alloc_size < ServerConfig::default().max_alloc_size);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerConfig {
+    /// The maximum size of a single buffer in bytes.
+    /// Larger allocations are rejected, as a failing allocation would abort the server and all other connections.
+    pub max_alloc_size: usize,
+}
+
+impl Default for ServerConfig {
+    #[inline]
+    fn default() -> Self {
+        ServerConfig {
+            max_alloc_size: DEFAULT_MAX_ALLOC_SIZE,
+        }
+    }
+}
+
+/// Accepts connections and serves every client on its own thread with the default [`ServerConfig`].
+/// # Example
+/// ```no_run
+/// use std::net::TcpListener;
+///
+/// fn main() -> std::io::Result<()> {
+///     let listener = TcpListener::bind("127.0.0.1:11001")?;
+///     custos::network::serve(listener)
+/// }
+/// ```
+#[inline]
+pub fn serve(listener: TcpListener) -> std::io::Result<()> {
+    serve_with(listener, ServerConfig::default())
+}
+
+/// Accepts connections and serves every client on its own thread.
+pub fn serve_with(listener: TcpListener, config: ServerConfig) -> std::io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        std::thread::spawn(move || {
+            // a failing connection must not take the server down
+            let _ = handle_client_with(stream, config);
+        });
+    }
+    Ok(())
+}
+
+/// Answers the requests of a single client with the default [`ServerConfig`] until the connection is closed.
+#[inline]
+pub fn handle_client(stream: TcpStream) -> std::io::Result<()> {
+    handle_client_with(stream, ServerConfig::default())
+}
+
+/// Answers the requests of a single client until the connection is closed.
+pub fn handle_client_with(mut stream: TcpStream, config: ServerConfig) -> std::io::Result<()> {
+    stream.set_nodelay(true)?;
+
+    let device = CPU::new();
+    let mut host = None;
+
+    while let Some(payload) = read_frame(&mut stream)? {
+        let response = match Request::decode(&payload) {
+            Ok(Request::CreateDevice { device: kind, idx }) => match (kind, idx) {
+                (DeviceType::CPU, 0) => {
+                    host = Some(Host::new(&device, config));
+                    Response::Ok
+                }
+                _ => Response::Err(format!("The device {kind:?} {idx} is not available.")),
+            },
+            Ok(request) => match host.as_mut() {
+                Some(host) => host.process(request).unwrap_or_else(Response::Err),
+                None => Response::Err("No device was created.".into()),
+            },
+            Err(err) => Response::Err(err.to_string()),
+        };
+        write_frame(&mut stream, &response.encode())?;
+    }
+    Ok(())
+}
+
+macro_rules! hosted_buffer {
+    ($($dtype:ident: $t:ty),*) => {
+        /// A buffer of the hosting device with a runtime element type.
+        enum HostedBuffer<'a> {
+            $($dtype(Buffer<'a, $t, CPU>),)*
+        }
+
+        impl<'a> HostedBuffer<'a> {
+            fn new(device: &'a CPU, dtype: DType, len: usize) -> HostedBuffer<'a> {
+                match dtype {
+                    $(DType::$dtype => HostedBuffer::$dtype(Buffer::new(device, len)),)*
+                }
+            }
+
+            fn dtype(&self) -> DType {
+                match self {
+                    $(HostedBuffer::$dtype(_) => DType::$dtype,)*
+                }
+            }
+
+            fn len(&self) -> usize {
+                match self {
+                    $(HostedBuffer::$dtype(buf) => buf.len(),)*
+                }
+            }
+
+            fn write(&mut self, data: &[u8]) -> Result<(), String> {
+                match self {
+                    $(HostedBuffer::$dtype(buf) => write(buf, data),)*
+                }
+            }
+
+            fn read(&self) -> Vec<u8> {
+                match self {
+                    $(HostedBuffer::$dtype(buf) => encode_slice(buf.as_slice()),)*
+                }
+            }
+
+            fn clear(&mut self) {
+                match self {
+                    $(HostedBuffer::$dtype(buf) => buf.device().clear(buf),)*
+                }
+            }
+
+            fn copy_slice_to(
+                &self,
+                src_range: core::ops::Range<usize>,
+                dst: &mut HostedBuffer<'a>,
+                dst_start: usize,
+            ) -> Result<(), String> {
+                match (self, dst) {
+                    $((HostedBuffer::$dtype(src), HostedBuffer::$dtype(dst)) => {
+                        copy_slice_to(src, src_range, dst, dst_start)
+                    })*
+                    _ => Err("The element types of the buffers differ.".into()),
+                }
+            }
+
+            fn copy_within(
+                &mut self,
+                src_range: core::ops::Range<usize>,
+                dst_start: usize,
+            ) -> Result<(), String> {
+                match self {
+                    $(HostedBuffer::$dtype(buf) => copy_within(buf, src_range, dst_start),)*
+                }
+            }
+
+            fn apply_fn(&self, source: &str) -> Result<HostedBuffer<'a>, String> {
+                match self {
+                    $(HostedBuffer::$dtype(buf) => apply_fn(buf, source).map(HostedBuffer::$dtype),)*
+                }
+            }
+        }
+    };
+}
+
+hosted_buffer! {
+    U8: u8, U16: u16, U32: u32, U64: u64,
+    I8: i8, I16: i16, I32: i32, I64: i64,
+    F32: f32, F64: f64
+}
+
+fn write<T: AsDataType>(buf: &mut Buffer<T, CPU>, data: &[u8]) -> Result<(), String> {
+    let data = decode_slice::<T>(data).map_err(|err| err.to_string())?;
+    if data.len() != buf.len() {
+        return Err(format!(
+            "Expected {} elements, but received {}.",
+            buf.len(),
+            data.len()
+        ));
+    }
+    buf.device().write(buf, &data);
+    Ok(())
+}
+
+fn copy_slice_to<T: Copy>(
+    src: &Buffer<T, CPU>,
+    src_range: core::ops::Range<usize>,
+    dst: &mut Buffer<T, CPU>,
+    dst_start: usize,
+) -> Result<(), String> {
+    let dst_end = check_copy_bounds(src.len(), &src_range, dst.len(), dst_start)?;
+    dst.device()
+        .copy_slice_to(src, src_range, dst, dst_start..dst_end);
+    Ok(())
+}
+
+fn copy_within<T: Copy>(
+    buf: &mut Buffer<T, CPU>,
+    src_range: core::ops::Range<usize>,
+    dst_start: usize,
+) -> Result<(), String> {
+    check_copy_bounds(buf.len(), &src_range, buf.len(), dst_start)?;
+    buf.copy_within(src_range, dst_start);
+    Ok(())
+}
+
+/// Returns the end of the destination range.
+fn check_copy_bounds(
+    src_len: usize,
+    src_range: &core::ops::Range<usize>,
+    dst_len: usize,
+    dst_start: usize,
+) -> Result<usize, String> {
+    let out_of_bounds = || String::from("The copied range is out of bounds.");
+
+    if src_range.start > src_range.end || src_range.end > src_len {
+        return Err(out_of_bounds());
+    }
+
+    let dst_end = dst_start
+        .checked_add(src_range.len())
+        .ok_or_else(out_of_bounds)?;
+
+    if dst_end > dst_len {
+        return Err(out_of_bounds());
+    }
+    Ok(dst_end)
+}
+
+// The output is not retrieved from the cache of the device, as it is owned by the client and freed with a `Dealloc` request.
+fn apply_fn<'a, T: ExprValue>(
+    buf: &Buffer<'a, T, CPU>,
+    source: &str,
+) -> Result<Buffer<'a, T, CPU>, String> {
+    let expr = Expr::<T>::parse(source)?;
+
+    let mut out = Buffer::new(buf.device(), buf.len());
+    for (out, x) in out.iter_mut().zip(buf.iter()) {
+        *out = expr
+            .eval_at(*x)
+            .ok_or_else(|| format!("`{source}` is undefined for an element of the buffer."))?;
+    }
+    Ok(out)
+}
+
+/// The buffers of one connection.
+struct Host<'a> {
+    device: &'a CPU,
+    config: ServerConfig,
+    buffers: HashMap<u64, HostedBuffer<'a>>,
+    next_id: u64,
+}
+
+impl<'a> Host<'a> {
+    fn new(device: &'a CPU, config: ServerConfig) -> Host<'a> {
+        Host {
+            device,
+            config,
+            buffers: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    fn insert(&mut self, buf: HostedBuffer<'a>) -> Response {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let len = buf.len();
+        self.buffers.insert(id, buf);
+        Response::Buf(BufId { id, len })
+    }
+
+    fn get(&self, id: u64) -> Result<&HostedBuffer<'a>, String> {
+        self.buffers
+            .get(&id)
+            .ok_or_else(|| format!("The buffer {id} does not exist."))
+    }
+
+    fn get_mut(&mut self, id: u64) -> Result<&mut HostedBuffer<'a>, String> {
+        self.buffers
+            .get_mut(&id)
+            .ok_or_else(|| format!("The buffer {id} does not exist."))
+    }
+
+    fn get_typed(&self, id: u64, dtype: DType) -> Result<&HostedBuffer<'a>, String> {
+        let buf = self.get(id)?;
+        check_dtype(buf, dtype)?;
+        Ok(buf)
+    }
+
+    fn process(&mut self, request: Request) -> Result<Response, String> {
+        match request {
+            Request::CreateDevice { .. } => unreachable!("handled by the connection"),
+            Request::Alloc { dtype, len } => {
+                if len == 0 {
+                    return Err("Cannot allocate a buffer with a length of 0.".into());
+                }
+
+                let size = len.checked_mul(dtype.size());
+                if !matches!(size, Some(size) if size <= self.config.max_alloc_size) {
+                    return Err(format!(
+                        "Cannot allocate {len} elements of type {dtype:?}, the maximum size of a buffer is {} bytes.",
+                        self.config.max_alloc_size
+                    ));
+                }
+                Ok(self.insert(HostedBuffer::new(self.device, dtype, len)))
+            }
+            Request::Dealloc { id } => {
+                self.buffers
+                    .remove(&id)
+                    .ok_or_else(|| format!("The buffer {id} does not exist."))?;
+                Ok(Response::Ok)
+            }
+            Request::Write { id, dtype, data } => {
+                let buf = self.get_mut(id)?;
+                check_dtype(buf, dtype)?;
+                buf.write(&data)?;
+                Ok(Response::Ok)
+            }
+            Request::Read { id, dtype } => Ok(Response::Data(self.get_typed(id, dtype)?.read())),
+            Request::Clear { id } => {
+                self.get_mut(id)?.clear();
+                Ok(Response::Ok)
+            }
+            Request::CopySlice {
+                src,
+                src_start,
+                src_end,
+                dst,
+                dst_start,
+            } => {
+                let src_range = src_start..src_end;
+
+                if src == dst {
+                    self.get_mut(dst)?.copy_within(src_range, dst_start)?;
+                    return Ok(Response::Ok);
+                }
+
+                // the destination is removed temporarily, because the source is borrowed at the same time
+                let mut dst_buf = self
+                    .buffers
+                    .remove(&dst)
+                    .ok_or_else(|| format!("The buffer {dst} does not exist."))?;
+
+                let res = self
+                    .get(src)
+                    .and_then(|src| src.copy_slice_to(src_range, &mut dst_buf, dst_start));
+
+                self.buffers.insert(dst, dst_buf);
+                res.map(|_| Response::Ok)
+            }
+            Request::ApplyFn { src, source } => {
+                let out = self.get(src)?.apply_fn(&source)?;
+                Ok(self.insert(out))
+            }
+        }
+    }
+}
+
+#[inline]
+fn check_dtype(buf: &HostedBuffer, dtype: DType) -> Result<(), String> {
+    if buf.dtype() != dtype {
+        return Err(format!(
+            "The buffer has the element type {:?}, but {dtype:?} was requested.",
+            buf.dtype()
+        ));
+    }
+    Ok(())
+}
@@ -6,10 +6,10 @@ use min_cl::api::{
 
 use super::{chosen_cl_idx, enqueue_kernel, AsClCvoidPtr, CLPtr, KernelCacheCL};
 use crate::flag::AllocFlag;
-use crate::{cache::Cache, Alloc, Buffer, CloneBuf, Device, Error, CPU};
-use crate::{Addons, AddonsReturn, PtrConv, Shape};
+use crate::{cache::Cache, Alloc, Buffer, BuildOptions, CloneBuf, Device, Error, CPU};
+use crate::{Addons, AddonsReturn, PtrConv, Shape, Shared};
 
-use std::{cell::RefCell, fmt::Debug};
+use std::fmt::Debug;
 
 #[cfg(unified_cl)]
 use min_cl::api::unified_ptr;
@@ -31,7 +31,7 @@ use min_cl::api::unified_ptr;
 /// }
 /// ```
 pub struct OpenCL {
-    pub(crate) kernel_cache: RefCell<KernelCacheCL>,
+    pub(crate) kernel_cache: Shared<KernelCacheCL>,
     /// The underlying OpenCL device.
     pub inner: CLDevice,
     /// A [`CPU`] used for unified memory device switching.
@@ -40,6 +40,16 @@ pub struct OpenCL {
     pub addons: Addons<OpenCL>,
 }
 
+// Safety: All OpenCL API functions are thread-safe, except `clSetKernelArg`.
+// Kernel arguments are only set while the kernel cache is mutably borrowed (locked), see `enqueue_kernel`.
+// The gradient tape is not thread-safe, hence the device is only `Send + Sync` without the `autograd` feature.
+#[cfg(all(feature = "thread-safe", not(feature = "autograd")))]
+unsafe impl Send for OpenCL {}
+
+// Safety: see `Send`
+#[cfg(all(feature = "thread-safe", not(feature = "autograd")))]
+unsafe impl Sync for OpenCL {}
+
 /// Short form for `OpenCL`
 pub type CL = OpenCL;
 
@@ -66,6 +76,33 @@ impl OpenCL {
         self.addons = Default::default();
     }
 
+    /// Returns the build options that are used for every kernel of this device.
+    #[inline]
+    pub fn build_options(&self) -> BuildOptions {
+        self.kernel_cache.borrow().build_options().clone()
+    }
+
+    /// Sets the build options that are used for every kernel of this device.
+    /// Kernels launched afterwards are rebuilt with the new options.
+    /// # Example
+    /// ```
+    /// use custos::{BuildOptions, OpenCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = OpenCL::new(0)?;
+    ///     device.set_build_options(device.build_options().fast_math(true));
+    ///
+    ///     assert_eq!(device.build_options().opencl_args(), "-cl-fast-relaxed-math -cl-std=CL1.2");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn set_build_options(&self, build_options: BuildOptions) {
+        self.kernel_cache
+            .borrow_mut()
+            .set_build_options(build_options)
+    }
+
     /// Context of the OpenCL device.
     #[inline]
     pub fn ctx(&self) -> &Context {
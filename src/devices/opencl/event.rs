@@ -0,0 +1,59 @@
+use std::ffi::c_void;
+
+use min_cl::api::{
+    clWaitForEvents, cl_command_queue, cl_event, cl_int, cl_uint, CommandQueue, Event, OCLErrorKind,
+};
+
+const CL_EVENT_COMMAND_EXECUTION_STATUS: cl_uint = 0x11D3;
+const CL_COMPLETE: cl_int = 0x0;
+
+#[cfg_attr(target_os = "macos", link(name = "OpenCL", kind = "framework"))]
+#[cfg_attr(not(target_os = "macos"), link(name = "OpenCL"))]
+extern "system" {
+    fn clFlush(command_queue: cl_command_queue) -> cl_int;
+    fn clGetEventInfo(
+        event: cl_event,
+        param_name: cl_uint,
+        param_value_size: usize,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut usize,
+    ) -> cl_int;
+}
+
+fn to_result(value: cl_int) -> crate::Result<()> {
+    if value != 0 {
+        return Err(OCLErrorKind::from_value(value).into());
+    }
+    Ok(())
+}
+
+/// Issues all previously queued commands to the device. This does not block.
+pub fn flush(queue: &CommandQueue) -> crate::Result<()> {
+    to_result(unsafe { clFlush(queue.0) })
+}
+
+/// Returns `true` if the command of the `event` has finished. This does not block.
+/// An error is returned if the command was terminated abnormally.
+pub fn is_event_complete(event: &Event) -> crate::Result<bool> {
+    let mut status: cl_int = 0;
+    to_result(unsafe {
+        clGetEventInfo(
+            event.0,
+            CL_EVENT_COMMAND_EXECUTION_STATUS,
+            core::mem::size_of::<cl_int>(),
+            &mut status as *mut cl_int as *mut c_void,
+            core::ptr::null_mut(),
+        )
+    })?;
+
+    // a negative status is the error code of the failed command
+    if status < 0 {
+        return Err(OCLErrorKind::from_value(status).into());
+    }
+    Ok(status == CL_COMPLETE)
+}
+
+/// Blocks until the command of the `event` has finished.
+pub fn wait_for_event_ref(event: &Event) -> crate::Result<()> {
+    to_result(unsafe { clWaitForEvents(1, &event.0) })
+}
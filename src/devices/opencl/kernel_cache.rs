@@ -1,18 +1,83 @@
-use crate::{Error, OpenCL};
+use crate::{
+    BuildOptions, CompileError, Error, KernelCompiler, KernelDiskCache, KernelKey, KernelLang,
+    KernelTemplate, OpenCL, TemplateCache,
+};
 use min_cl::api::{
-    build_program, create_kernels_in_program, create_program_with_source, Kernel, OCLErrorKind,
+    build_program, create_kernel, create_kernels_in_program, create_program_with_source,
+    ffi::{
+        clGetProgramBuildInfo, clGetProgramInfo, cl_context, cl_device_id, cl_int, cl_program,
+        cl_uint,
+    },
+    CLIntDevice, Kernel, OCLErrorKind, Program,
+};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    ffi::c_void,
+    ptr::null_mut,
 };
-use std::collections::HashMap;
 
-#[derive(Debug, Default)]
+#[cfg(feature = "half")]
+use crate::{devices::kernel_source::contains_ident, CDatatype};
+
 /// This stores the previously compiled OpenCL kernels.
 pub struct KernelCacheCL {
     /// Uses the kernel source code to retrieve the corresponding `Kernel`.
+    /// Contains the kernels built with the build options of the cache, see [`KernelCacheCL::kernel`].
     pub kernel_cache: HashMap<String, Kernel>,
+    /// Contains the kernels built with per-call build options or entry points, see [`KernelCacheCL::kernel_with`].
+    pub custom_kernels: HashMap<KernelKey, Kernel>,
+    /// The built programs by source code and build arguments. Kernels of the same program share it.
+    programs: HashMap<(String, String), Program>,
+    build_options: BuildOptions,
+    /// The instantiated sources of [`KernelTemplate`]s, see [`KernelCacheCL::template_kernel`].
+    pub templates: TemplateCache,
+    /// If set, program binaries are stored on disk and reused by other processes.
+    /// By default, this is configured with [`KernelDiskCache::from_env`].
+    pub disk_cache: Option<KernelDiskCache>,
+}
+
+impl core::fmt::Debug for KernelCacheCL {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("KernelCacheCL")
+            .field("kernel_cache", &self.kernel_cache)
+            .field("custom_kernels", &self.custom_kernels)
+            .field("build_options", &self.build_options)
+            .field("templates", &self.templates)
+            .field("disk_cache", &self.disk_cache)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for KernelCacheCL {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            kernel_cache: HashMap::new(),
+            custom_kernels: HashMap::new(),
+            programs: HashMap::new(),
+            build_options: BuildOptions::new().std("CL1.2"), //-cl-single-precision-constant
+            templates: TemplateCache::new(KernelLang::OpenCL),
+            disk_cache: KernelDiskCache::from_env(),
+        }
+    }
 }
 
 impl KernelCacheCL {
+    /// The build options used for every kernel. Defaults to `-cl-std=CL1.2`.
+    #[inline]
+    pub fn build_options(&self) -> &BuildOptions {
+        &self.build_options
+    }
+
+    /// Sets the build options used for every kernel.
+    /// Kernels built with the previous options are not returned by [`KernelCacheCL::kernel`] anymore.
+    pub fn set_build_options(&mut self, build_options: BuildOptions) {
+        self.kernel_cache.clear();
+        self.build_options = build_options;
+    }
+
     /// Returns a cached kernel. If the kernel source code does not exist, a new kernel is created and cached.
+    /// The first kernel of the source code is used.
     ///
     /// # Example
     /// ```
@@ -39,35 +104,302 @@ impl KernelCacheCL {
         if self.kernel_cache.contains_key(src) {
             return Ok(self.kernel_cache.get(src).unwrap());
         }
-        /*if let Some(kernel) = self.kernel_cache.get(src) {
-            return Ok(kernel);
-        }*/
-
-        let program = create_program_with_source(device.ctx(), src)?;
-        build_program(&program, &[device.device()], Some("-cl-std=CL1.2"))?; //-cl-single-precision-constant
 
-        let kernel = create_kernels_in_program(&program)?
-            .into_iter()
-            .next()
-            .ok_or(OCLErrorKind::InvalidKernel)?;
+        let kernel = self.build_kernel(device, src, None, self.build_options.opencl_args())?;
 
         self.kernel_cache.insert(src.to_string(), kernel);
         Ok(self.kernel_cache.get(src).unwrap())
     }
+
+    /// Returns a cached kernel, which is built with the build options of the cache merged with `options` (see [`BuildOptions::merge`]).
+    /// `entry` selects the kernel function by name. If it is `None`, the first kernel of the source code is used.
+    ///
+    /// # Example
+    /// ```
+    /// use custos::{BuildOptions, OpenCL, opencl::KernelCacheCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = OpenCL::new(0)?;
+    ///     let mut kernel_cache = KernelCacheCL::default();
+    ///
+    ///     let src = "
+    ///         __kernel void fill(__global float* out) { out[get_global_id(0)] = VALUE; }
+    ///         __kernel void clear(__global float* out) { out[get_global_id(0)] = 0; }
+    ///     ";
+    ///     let options = BuildOptions::new().define("VALUE", 3.0);
+    ///
+    ///     let fill = kernel_cache.kernel_with(&device, src, Some("fill"), &options)?.0;
+    ///     let clear = kernel_cache.kernel_with(&device, src, Some("clear"), &options)?.0;
+    ///     assert_ne!(fill, clear);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn kernel_with(
+        &mut self,
+        device: &OpenCL,
+        src: &str,
+        entry: Option<&str>,
+        options: &BuildOptions,
+    ) -> Result<&Kernel, Error> {
+        let key = KernelKey {
+            src: src.to_string(),
+            entry: entry.map(ToString::to_string),
+            args: self.build_options.merge(options).opencl_args(),
+        };
+
+        if !self.custom_kernels.contains_key(&key) {
+            let kernel = self.build_kernel(device, src, entry, key.args.clone())?;
+            self.custom_kernels.insert(key.clone(), kernel);
+        }
+        Ok(&self.custom_kernels[&key])
+    }
+
+    /// Returns a cached kernel of the template instantiated for OpenCL (see [`KernelTemplate`]).
+    /// The instantiated source is cached as well, hence the template is only rendered once.
+    ///
+    /// # Example
+    /// ```
+    /// use custos::{KernelTemplate, OpenCL, opencl::KernelCacheCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = OpenCL::new(0)?;
+    ///     let mut kernel_cache = KernelCacheCL::default();
+    ///
+    ///     let template = KernelTemplate::new("__kernel void clear(__global ${T}* x) { x[get_global_id(0)] = 0; }");
+    ///
+    ///     let float = kernel_cache.template_kernel(&device, &template.clone().ty::<f32>("T"))?.0;
+    ///     let int = kernel_cache.template_kernel(&device, &template.ty::<i32>("T"))?.0;
+    ///     assert_ne!(float, int);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn template_kernel(
+        &mut self,
+        device: &OpenCL,
+        template: &KernelTemplate,
+    ) -> Result<&Kernel, Error> {
+        let src = self.templates.source(template)?.to_string();
+        self.kernel(device, &src)
+    }
+
+    /// Creates the kernel `entry` (or the first kernel) of a program, which is built if it was not built with the same arguments before.
+    fn build_kernel(
+        &mut self,
+        device: &OpenCL,
+        src: &str,
+        entry: Option<&str>,
+        args: String,
+    ) -> Result<Kernel, Error> {
+        let program = match self.programs.entry((src.to_string(), args)) {
+            Entry::Occupied(program) => program.into_mut(),
+            Entry::Vacant(vacant) => {
+                let compiler = ProgramCompiler {
+                    device,
+                    args: &vacant.key().1,
+                };
+                let program = match &self.disk_cache {
+                    Some(disk_cache) => disk_cache.load_or_compile(&compiler, src)?,
+                    None => compiler.build(src)?,
+                };
+                vacant.insert(program)
+            }
+        };
+
+        match entry {
+            Some(entry) => Ok(create_kernel(program, entry)?),
+            None => Ok(create_kernels_in_program(program)?
+                .into_iter()
+                .next()
+                .ok_or(OCLErrorKind::InvalidKernel)?),
+        }
+    }
+}
+
+/// Builds OpenCL programs for the device and converts them from and to their binary representation.
+struct ProgramCompiler<'a> {
+    device: &'a OpenCL,
+    args: &'a str,
+}
+
+impl ProgramCompiler<'_> {
+    /// Builds a program from source code. If the build fails, the returned [`CompileError`] contains the build log.
+    fn build(&self, src: &str) -> crate::Result<Program> {
+        let program = create_program_with_source(self.device.ctx(), &with_extensions(src))?;
+
+        if let Err(e) = build_program(&program, &[self.device.device()], Some(self.args)) {
+            return Err(CompileError {
+                reason: e.to_string(),
+                log: build_log(&program, self.device.device()),
+            }
+            .into());
+        }
+        Ok(program)
+    }
+}
+
+impl KernelCompiler for ProgramCompiler<'_> {
+    type Artifact = Program;
+
+    fn identity(&self) -> String {
+        let device = self.device.device();
+        format!(
+            "opencl|{}|{}|{}",
+            device.get_name().unwrap_or_default(),
+            device.get_version().unwrap_or_default(),
+            self.args
+        )
+    }
+
+    fn compile(&self, src: &str) -> crate::Result<(Program, Vec<u8>)> {
+        let program = self.build(src)?;
+        let binary = program_binary(&program)?;
+        Ok((program, binary))
+    }
+
+    fn load(&self, binary: &[u8]) -> crate::Result<Program> {
+        let program = create_program_with_binary(self.device, binary)?;
+        build_program(&program, &[self.device.device()], Some(self.args))?;
+        Ok(program)
+    }
+}
+
+const CL_PROGRAM_BINARY_SIZES: cl_uint = 0x1165;
+const CL_PROGRAM_BINARIES: cl_uint = 0x1166;
+const CL_PROGRAM_BUILD_LOG: cl_uint = 0x1183;
+
+#[cfg_attr(target_os = "macos", link(name = "OpenCL", kind = "framework"))]
+#[cfg_attr(not(target_os = "macos"), link(name = "OpenCL"))]
+extern "system" {
+    fn clCreateProgramWithBinary(
+        context: cl_context,
+        num_devices: cl_uint,
+        device_list: *const cl_device_id,
+        lengths: *const usize,
+        binaries: *const *const u8,
+        binary_status: *mut cl_int,
+        errcode_ret: *mut cl_int,
+    ) -> cl_program;
+}
+
+/// Returns the binary of a program built for a single device.
+fn program_binary(program: &Program) -> crate::Result<Vec<u8>> {
+    let mut size = 0usize;
+    let value = unsafe {
+        clGetProgramInfo(
+            program.0,
+            CL_PROGRAM_BINARY_SIZES,
+            core::mem::size_of::<usize>(),
+            &mut size as *mut usize as *mut c_void,
+            null_mut(),
+        )
+    };
+    if value != 0 {
+        return Err(OCLErrorKind::from_value(value).into());
+    }
+
+    let mut binary = vec![0u8; size];
+    let mut binaries = [binary.as_mut_ptr()];
+    let value = unsafe {
+        clGetProgramInfo(
+            program.0,
+            CL_PROGRAM_BINARIES,
+            core::mem::size_of_val(&binaries),
+            binaries.as_mut_ptr() as *mut c_void,
+            null_mut(),
+        )
+    };
+    if value != 0 {
+        return Err(OCLErrorKind::from_value(value).into());
+    }
+    Ok(binary)
+}
+
+/// Returns the build log of a program for the device. Returns an empty string if the log is not available.
+fn build_log(program: &Program, device: CLIntDevice) -> String {
+    let mut size = 0;
+    let value = unsafe {
+        clGetProgramBuildInfo(
+            program.0,
+            device.0,
+            CL_PROGRAM_BUILD_LOG,
+            0,
+            null_mut(),
+            &mut size,
+        )
+    };
+    if value != 0 {
+        return String::new();
+    }
+
+    let mut log = vec![0u8; size];
+    let value = unsafe {
+        clGetProgramBuildInfo(
+            program.0,
+            device.0,
+            CL_PROGRAM_BUILD_LOG,
+            size,
+            log.as_mut_ptr() as *mut c_void,
+            null_mut(),
+        )
+    };
+    if value != 0 {
+        return String::new();
+    }
+
+    let len = log.iter().position(|&c| c == 0).unwrap_or(log.len());
+    String::from_utf8_lossy(&log[..len]).into_owned()
+}
+
+fn create_program_with_binary(device: &OpenCL, binary: &[u8]) -> crate::Result<Program> {
+    let devices = [device.device().0];
+    let (mut binary_status, mut err) = (0, 0);
+
+    let program = unsafe {
+        clCreateProgramWithBinary(
+            device.ctx().0,
+            1,
+            devices.as_ptr(),
+            &binary.len(),
+            &binary.as_ptr(),
+            &mut binary_status,
+            &mut err,
+        )
+    };
+    if err != 0 {
+        return Err(OCLErrorKind::from_value(err).into());
+    }
+
+    let program = Program(program);
+    if binary_status != 0 {
+        return Err(OCLErrorKind::from_value(binary_status).into());
+    }
+    Ok(program)
+}
+
+/// Enables the `cl_khr_fp16` extension if the kernel source uses the element type `half` (`half::f16`).
+fn with_extensions(src: &str) -> std::borrow::Cow<str> {
+    #[cfg(feature = "half")]
+    if contains_ident(src, half::f16::as_c_type_str()) && !src.contains("cl_khr_fp16") {
+        return format!("#pragma OPENCL EXTENSION cl_khr_fp16 : enable\n{src}").into();
+    }
+    src.into()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::KernelCacheCL;
-    use crate::OpenCL;
-    use std::collections::HashMap;
+    use super::{create_program_with_binary, program_binary, KernelCacheCL, ProgramCompiler};
+    use crate::{
+        BuildOptions, CompileError, KernelCompiler, KernelTemplate, OpenCL, TemplateError,
+    };
+    use min_cl::api::create_kernels_in_program;
 
     #[test]
     fn test_kernel_cache() -> crate::Result<()> {
         let device = OpenCL::new(0)?;
 
         let mut kernel_cache = KernelCacheCL {
-            kernel_cache: HashMap::new(),
+            disk_cache: None,
+            ..Default::default()
         };
 
         /*let mut kernel_fn = || {
@@ -107,4 +439,127 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_program_binary_roundtrip() -> crate::Result<()> {
+        let device = OpenCL::new(0)?;
+        let compiler = ProgramCompiler {
+            device: &device,
+            args: "-cl-std=CL1.2",
+        };
+
+        let (_, binary) = compiler.compile("__kernel void foo(__global float* test) {}")?;
+        assert!(!binary.is_empty());
+
+        let program = create_program_with_binary(&device, &binary)?;
+        assert_eq!(program_binary(&program)?.len(), binary.len());
+
+        let loaded = compiler.load(&binary)?;
+        assert_eq!(create_kernels_in_program(&loaded)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_kernel_with_entry_and_options() -> crate::Result<()> {
+        let device = OpenCL::new(0)?;
+        let mut kernel_cache = KernelCacheCL {
+            disk_cache: None,
+            ..Default::default()
+        };
+
+        let src = "
+            __kernel void fill(__global float* out) { out[get_global_id(0)] = VALUE; }
+            __kernel void clear(__global float* out) { out[get_global_id(0)] = 0; }
+        ";
+
+        // `VALUE` is not defined
+        assert!(kernel_cache.kernel(&device, src).is_err());
+
+        let options = BuildOptions::new().define("VALUE", 2.0);
+        let fill = kernel_cache
+            .kernel_with(&device, src, Some("fill"), &options)?
+            .0;
+        let clear = kernel_cache
+            .kernel_with(&device, src, Some("clear"), &options)?
+            .0;
+        assert_ne!(fill, clear);
+        assert_eq!(
+            kernel_cache
+                .kernel_with(&device, src, Some("fill"), &options)?
+                .0,
+            fill
+        );
+
+        // both kernels share a program
+        assert_eq!(kernel_cache.programs.len(), 1);
+
+        let other = BuildOptions::new().define("VALUE", 3.0);
+        assert_ne!(
+            kernel_cache
+                .kernel_with(&device, src, Some("fill"), &other)?
+                .0,
+            fill
+        );
+        assert_eq!(kernel_cache.programs.len(), 2);
+
+        assert!(kernel_cache
+            .kernel_with(&device, src, Some("missing"), &options)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compile_error_contains_build_log() -> crate::Result<()> {
+        let device = OpenCL::new(0)?;
+        let mut kernel_cache = KernelCacheCL {
+            disk_cache: None,
+            ..Default::default()
+        };
+
+        let error = kernel_cache
+            .kernel(
+                &device,
+                "__kernel void foo(__global float* x) { x[0] = undeclared; }",
+            )
+            .unwrap_err();
+
+        let error = error.downcast_ref::<CompileError>().unwrap();
+        assert!(error.log.contains("undeclared"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_kernel() -> crate::Result<()> {
+        let device = OpenCL::new(0)?;
+        let mut kernel_cache = KernelCacheCL {
+            disk_cache: None,
+            ..Default::default()
+        };
+
+        let template = KernelTemplate::new(
+            "__kernel void add(__global ${T}* x, ${T} value) { x[get_global_id(0)] += value; }",
+        );
+
+        let float = template.clone().ty::<f32>("T");
+        let kernel = kernel_cache.template_kernel(&device, &float)?.0;
+        assert_eq!(kernel_cache.template_kernel(&device, &float)?.0, kernel);
+        assert_eq!(kernel_cache.templates.len(), 1);
+
+        let int = kernel_cache
+            .template_kernel(&device, &template.clone().ty::<i32>("T"))?
+            .0;
+        assert_ne!(int, kernel);
+        assert_eq!(kernel_cache.kernel_cache.len(), 2);
+
+        let error = kernel_cache
+            .template_kernel(&device, &template)
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<TemplateError>(),
+            Some(&TemplateError::Unresolved(vec!["T".into()]))
+        );
+        Ok(())
+    }
 }
@@ -1,5 +1,5 @@
-use crate::{number::Number, Buffer, OpenCL, Shape};
-use min_cl::api::{enqueue_nd_range_kernel, set_kernel_arg, OCLErrorKind};
+use crate::{number::Number, Buffer, BuildOptions, KernelTemplate, OpenCL, Shape};
+use min_cl::api::{enqueue_nd_range_kernel, set_kernel_arg, Kernel, OCLErrorKind};
 use std::{ffi::c_void, mem::size_of};
 
 /// Converts `Self` to a *const c_void.
@@ -134,7 +134,91 @@ pub fn enqueue_kernel(
 ) -> crate::Result<()> {
     let mut binding = device.kernel_cache.borrow_mut();
     let kernel = binding.kernel(device, src)?;
+    enqueue(device, kernel, gws, lws, args)
+}
+
+/// Executes a cached OpenCL kernel, which is built with the build options of the device merged with `options`.
+/// `entry` selects the kernel function by name. If it is `None`, the first kernel of the source code is used.
+/// # Example
+///
+/// ```
+/// use custos::{BuildOptions, OpenCL, Buffer, opencl::enqueue_kernel_with};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let mut buf = Buffer::<f32, _>::new(&device, 10);
+///
+///     let src = "
+///      __kernel void add(__global float* buf) {
+///         buf[get_global_id(0)] += NUM;
+///      }
+///      __kernel void sub(__global float* buf) {
+///         buf[get_global_id(0)] -= NUM;
+///      }
+///     ";
+///     let options = BuildOptions::new().define("NUM", 4.0);
+///     enqueue_kernel_with(&device, src, Some("sub"), &options, [buf.len(), 0, 0], None, &[&mut buf])?;
+///     
+///     assert_eq!(buf.read_to_vec(), [-4.0; 10]);    
+///
+///     Ok(())
+/// }
+/// ```
+pub fn enqueue_kernel_with(
+    device: &OpenCL,
+    src: &str,
+    entry: Option<&str>,
+    options: &BuildOptions,
+    gws: [usize; 3],
+    lws: Option<[usize; 3]>,
+    args: &[&dyn AsClCvoidPtr],
+) -> crate::Result<()> {
+    let mut binding = device.kernel_cache.borrow_mut();
+    let kernel = binding.kernel_with(device, src, entry, options)?;
+    enqueue(device, kernel, gws, lws, args)
+}
 
+/// Executes a cached OpenCL kernel, which is instantiated from a [`KernelTemplate`].
+/// # Example
+///
+/// ```
+/// use custos::{KernelTemplate, OpenCL, Buffer, opencl::enqueue_template};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let mut buf = Buffer::<i32, _>::new(&device, 10);
+///
+///     let template = KernelTemplate::new("
+///      __kernel void add(__global ${T}* buf, ${T} num) {
+///         buf[get_global_id(0)] += num;
+///      }
+///     ").ty::<i32>("T");
+///     enqueue_template(&device, &template, [buf.len(), 0, 0], None, &[&mut buf, &3])?;
+///     
+///     assert_eq!(buf.read_to_vec(), [3; 10]);    
+///
+///     Ok(())
+/// }
+/// ```
+pub fn enqueue_template(
+    device: &OpenCL,
+    template: &KernelTemplate,
+    gws: [usize; 3],
+    lws: Option<[usize; 3]>,
+    args: &[&dyn AsClCvoidPtr],
+) -> crate::Result<()> {
+    let mut binding = device.kernel_cache.borrow_mut();
+    let kernel = binding.template_kernel(device, template)?;
+    enqueue(device, kernel, gws, lws, args)
+}
+
+fn enqueue(
+    device: &OpenCL,
+    kernel: &Kernel,
+    gws: [usize; 3],
+    lws: Option<[usize; 3]>,
+    args: &[&dyn AsClCvoidPtr],
+) -> crate::Result<()> {
     let wd;
     if gws[0] == 0 {
         return Err(OCLErrorKind::InvalidGlobalWorkSize.into());
@@ -3,11 +3,13 @@
 use std::{ffi::c_void, ptr::null_mut};
 
 pub use cl_device::{OpenCL, CL};
+pub use event::*;
 pub use kernel_cache::*;
 pub use kernel_enqueue::*;
 
 //pub mod api;
 mod cl_device;
+mod event;
 mod kernel_cache;
 mod kernel_enqueue;
 
@@ -78,6 +80,12 @@ impl<T> ShallowCopy for CLPtr<T> {
     }
 }
 
+// Safety: OpenCL memory objects can be used from several threads and the host pointer is owned like a `Box<[T]>`.
+unsafe impl<T: Send> Send for CLPtr<T> {}
+
+// Safety: OpenCL memory objects can be used from several threads and the host pointer is owned like a `Box<[T]>`.
+unsafe impl<T: Sync> Sync for CLPtr<T> {}
+
 impl<T> PtrType for CLPtr<T> {
     #[inline]
     fn size(&self) -> usize {
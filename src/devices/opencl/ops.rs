@@ -6,11 +6,17 @@ use min_cl::api::{
 };
 
 use crate::{
-    bounds_to_range, prelude::Number, ApplyFunction, Buffer, CDatatype, ClearBuf, CopySlice,
-    Device, OpenCL, Read, Resolve, Shape, ToCLSource, ToMarker, UnaryGrad, WriteBuf,
+    bounds_to_range,
+    devices::kernel_source::complex_prelude,
+    optim::{scalar_markers, UpdateFn, SCALAR_MARKERS},
+    prelude::{Float, Number},
+    random::{philox_c_source, seed_key, Distribution},
+    ApplyFunction, Buffer, CDatatype, CastBuf, ClearBuf, Combiner, CopySlice, Device, FillBuf,
+    KernelLang, OpenCL, RandBuf, Read, ReadAsync, Resolve, Shape, ToCLSource, ToMarker, Transfer,
+    UnaryGrad, WriteAsync, WriteBuf,
 };
 
-use super::{enqueue_kernel, CLBuffer};
+use super::{enqueue_kernel, flush, is_event_complete, wait_for_event_ref, AsClCvoidPtr, CLBuffer};
 
 impl<T: CDatatype> ClearBuf<T> for OpenCL {
     #[inline]
@@ -53,6 +59,77 @@ pub fn try_cl_clear<T: CDatatype>(
     Ok(())
 }
 
+impl<T: CDatatype + Number, S: Shape> FillBuf<T, S> for OpenCL {
+    #[inline]
+    fn fill(&self, buf: &mut Buffer<T, Self, S>, value: T) {
+        try_cl_fill_range(self, buf, value, T::zero()).unwrap()
+    }
+
+    #[inline]
+    fn fill_range(&self, buf: &mut Buffer<T, Self, S>, start: T, step: T) {
+        try_cl_fill_range(self, buf, start, step).unwrap()
+    }
+
+    #[inline]
+    fn fill_eye(&self, buf: &mut Buffer<T, Self, S>, cols: usize) {
+        try_cl_fill_eye(self, buf, cols).unwrap()
+    }
+}
+
+/// Sets the i-th element of an OpenCL Buffer to `start + i * step`.
+/// # Example
+/// ```
+/// use custos::{OpenCL, Buffer, opencl::try_cl_fill_range};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let mut buf = Buffer::<i32, _>::new(&device, 4);
+///
+///     try_cl_fill_range(&device, &mut buf, 3, 2)?;
+///     assert_eq!(buf.read(), [3, 5, 7, 9]);
+///     Ok(())
+/// }
+/// ```
+pub fn try_cl_fill_range<T: CDatatype + Number, S: Shape>(
+    device: &OpenCL,
+    buf: &mut CLBuffer<T, S>,
+    start: T,
+    step: T,
+) -> crate::Result<()> {
+    let src = format!(
+        "
+        __kernel void fill_range(__global {datatype}* out, {datatype} start, {datatype} step) {{
+            size_t id = get_global_id(0);
+            out[id] = start + ({datatype}) id * step;
+        }}
+    ",
+        datatype = T::as_c_type_str()
+    );
+
+    enqueue_kernel(device, &src, [buf.len(), 0, 0], None, &[buf, &start, &step])?;
+    Ok(())
+}
+
+/// Sets the elements on the diagonal of a row-major OpenCL matrix with `cols` columns to one and all other elements to zero.
+pub fn try_cl_fill_eye<T: CDatatype, S: Shape>(
+    device: &OpenCL,
+    buf: &mut CLBuffer<T, S>,
+    cols: usize,
+) -> crate::Result<()> {
+    let src = format!(
+        "
+        __kernel void fill_eye(__global {datatype}* out) {{
+            size_t id = get_global_id(0);
+            out[id] = id / {cols} == id % {cols} ? 1 : 0;
+        }}
+    ",
+        datatype = T::as_c_type_str()
+    );
+
+    enqueue_kernel(device, &src, [buf.len(), 0, 0], None, &[buf])?;
+    Ok(())
+}
+
 impl<T, S: Shape> WriteBuf<T, S> for OpenCL {
     #[inline]
     fn write(&self, buf: &mut Buffer<T, OpenCL, S>, data: &[T]) {
@@ -144,6 +221,75 @@ fn try_read_cl_buf_to_vec<T: Clone + Default, S: Shape>(
     Ok(read)
 }
 
+impl<T: Clone + Default, S: Shape> ReadAsync<T, S> for OpenCL {
+    #[inline]
+    fn read_async<'a>(&'a self, buf: &'a Buffer<T, OpenCL, S>) -> Transfer<'a, Vec<T>> {
+        try_read_cl_buf_async(self, buf).unwrap_or_else(|e| Transfer::from_result(Err(e)))
+    }
+}
+
+/// Starts a non-blocking read of an OpenCL buffer.
+/// The returned [`Transfer`] polls the event of the read command.
+pub fn try_read_cl_buf_async<'a, T: Clone + Default, S: Shape>(
+    device: &'a OpenCL,
+    buf: &'a Buffer<T, OpenCL, S>,
+) -> crate::Result<Transfer<'a, Vec<T>>> {
+    let mut read = vec![T::default(); buf.len()];
+    // The heap allocation of `read` does not move, even if `read` is moved into the transfer.
+    let event = unsafe { enqueue_read_buffer(device.queue(), buf.cl_ptr(), &mut read, false)? };
+    flush(device.queue())?;
+
+    let mut read = Some(read);
+    Ok(Transfer::new(move |block| {
+        let complete = match block {
+            true => wait_for_event_ref(&event).map(|_| true),
+            false => is_event_complete(&event),
+        };
+        match complete {
+            Ok(true) => Some(Ok(read.take().unwrap())),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }))
+}
+
+impl<T: Clone, S: Shape> WriteAsync<T, S> for OpenCL {
+    #[inline]
+    fn write_async<'a>(
+        &'a self,
+        buf: &'a mut Buffer<T, OpenCL, S>,
+        data: &'a [T],
+    ) -> Transfer<'a, ()> {
+        try_write_cl_buf_async(self, buf, data).unwrap_or_else(|e| Transfer::from_result(Err(e)))
+    }
+}
+
+/// Starts a non-blocking write to an OpenCL buffer.
+/// `data` is copied first, hence the host memory stays valid even if the [`Transfer`] is leaked.
+pub fn try_write_cl_buf_async<'a, T: Clone, S: Shape>(
+    device: &'a OpenCL,
+    buf: &'a mut Buffer<T, OpenCL, S>,
+    data: &[T],
+) -> crate::Result<Transfer<'a, ()>> {
+    let data = data.to_vec();
+    let event = unsafe { enqueue_write_buffer(device.queue(), buf.cl_ptr(), &data, false)? };
+    flush(device.queue())?;
+
+    Ok(Transfer::new(move |block| {
+        // keeps the copied data alive until the write is complete
+        let _data = &data;
+        let complete = match block {
+            true => wait_for_event_ref(&event).map(|_| true),
+            false => is_event_complete(&event),
+        };
+        match complete {
+            Ok(true) => Some(Ok(())),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }))
+}
+
 impl<T, S> ApplyFunction<T, S> for OpenCL
 where
     T: CDatatype + Number,
@@ -153,7 +299,7 @@ where
     fn apply_fn<F>(
         &self,
         buf: &Buffer<T, Self, S>,
-        f: impl Fn(Resolve<T>) -> F,
+        f: impl Fn(Resolve<T>) -> F + Sync,
     ) -> Buffer<T, Self, S>
     where
         F: ToCLSource,
@@ -173,15 +319,17 @@ where
     T: CDatatype + Number,
     S: Shape,
 {
+    let operation = f("lhs[id]".to_marker());
     let src = format!(
-        "
+        "{complex_helpers}
         __kernel void apply_fn(__global const {datatype}* lhs, __global {datatype}* out) {{
             size_t id = get_global_id(0);
             out[id] = {operation};
         }}
     ",
+        complex_helpers = complex_prelude(operation.complex_c_type(), KernelLang::OpenCL),
         datatype = T::as_c_type_str(),
-        operation = f("lhs[id]".to_marker()).to_cl_source()
+        operation = operation.to_cl_source()
     );
 
     let out = device.retrieve::<T, S>(x.len(), x);
@@ -189,6 +337,128 @@ where
     Ok(out)
 }
 
+impl<T, U, S> CastBuf<T, U, S> for OpenCL
+where
+    T: CDatatype,
+    U: CDatatype,
+    S: Shape,
+{
+    #[inline]
+    fn cast(&self, buf: &Buffer<T, Self, S>) -> Buffer<U, Self, S> {
+        try_cl_cast(self, buf).unwrap()
+    }
+}
+
+/// A failable OpenCL version of [`cast`](CastBuf::cast).
+/// `bool` buffers are stored as `uchar`, because OpenCL does not allow `bool` pointers as kernel arguments.
+/// # Example
+/// ```
+/// use custos::{OpenCL, Buffer, opencl::try_cl_cast};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let buf = Buffer::from((&device, [1.5f32, 0., -3.2]));
+///
+///     let casted = try_cl_cast::<_, i32, ()>(&device, &buf)?;
+///     assert_eq!(casted.read(), [1, 0, -3]);
+///
+///     let mask = try_cl_cast::<_, bool, ()>(&device, &buf)?;
+///     assert_eq!(mask.read(), [true, false, true]);
+///     Ok(())
+/// }
+/// ```
+pub fn try_cl_cast<'a, T, U, S>(
+    device: &'a OpenCL,
+    x: &CLBuffer<T, S>,
+) -> crate::Result<CLBuffer<'a, U, S>>
+where
+    T: CDatatype,
+    U: CDatatype,
+    S: Shape,
+{
+    let cl_type = |c_type| if c_type == "bool" { "uchar" } else { c_type };
+
+    let operation = if U::as_c_type_str() == "bool" {
+        "x[id] != 0".to_string()
+    } else {
+        format!("({}) x[id]", U::as_c_type_str())
+    };
+
+    let src = format!(
+        "
+        __kernel void cast(__global const {datatype}* x, __global {out_datatype}* out) {{
+            size_t id = get_global_id(0);
+            out[id] = {operation};
+        }}
+    ",
+        datatype = cl_type(T::as_c_type_str()),
+        out_datatype = cl_type(U::as_c_type_str()),
+    );
+
+    let out = device.retrieve::<U, S>(x.len(), x);
+    enqueue_kernel(device, &src, [x.len(), 0, 0], None, &[x, &out])?;
+    Ok(out)
+}
+
+impl<T, S> RandBuf<T, S> for OpenCL
+where
+    T: CDatatype + Float,
+    S: Shape,
+{
+    #[inline]
+    fn rand(&self, buf: &mut Buffer<T, Self, S>, seed: u64, dist: Distribution<T>) {
+        try_cl_rand(self, buf, seed, dist).unwrap()
+    }
+}
+
+/// A failable OpenCL version of [`rand`](RandBuf::rand).
+/// Fills the buffer with values sampled from the [`Distribution`].
+/// # Example
+/// ```
+/// use custos::{OpenCL, Buffer, random::{self, Distribution}, opencl::try_cl_rand};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let mut buf = Buffer::<f32, _>::new(&device, 4);
+///
+///     try_cl_rand(&device, &mut buf, 42, Distribution::Uniform { lo: -1., hi: 1. })?;
+///     assert_eq!(buf.read()[3], random::uniform(42, 3, -1., 1.));
+///     Ok(())
+/// }
+/// ```
+pub fn try_cl_rand<T, S>(
+    device: &OpenCL,
+    buf: &mut CLBuffer<T, S>,
+    seed: u64,
+    dist: Distribution<T>,
+) -> crate::Result<()>
+where
+    T: CDatatype + Float,
+    S: Shape,
+{
+    // contracting to fma would change the results compared to the CPU
+    let src = format!(
+        "
+        #pragma OPENCL FP_CONTRACT OFF
+        {philox}
+        __kernel void rand(__global {datatype}* out, uint k0, uint k1, {datatype} a, {datatype} b) {{
+            size_t id = get_global_id(0);
+            uint bits[4] = {{(uint) id, (uint) ((ulong) id >> 32), 0, 0}};
+            philox4x32(bits, k0, k1);
+            out[id] = {sample};
+        }}
+    ",
+        philox = philox_c_source("uint", "ulong", "inline"),
+        datatype = T::as_c_type_str(),
+        sample = dist.sample_src(T::as_c_type_str(), false),
+    );
+
+    let [k0, k1] = seed_key(seed);
+    let (a, b) = dist.params();
+    enqueue_kernel(device, &src, [buf.len(), 0, 0], None, &[buf, &k0, &k1, &a, &b])?;
+    Ok(())
+}
+
 impl<T, S> UnaryGrad<T, S> for OpenCL
 where
     T: CDatatype + Number,
@@ -200,7 +470,7 @@ where
         lhs: &Buffer<T, Self, S>,
         lhs_grad: &mut Buffer<T, Self, S>,
         out: &Buffer<T, Self, S>,
-        lhs_grad_fn: impl Fn(Resolve<T>) -> F,
+        lhs_grad_fn: impl Fn(Resolve<T>) -> F + Sync,
     ) where
         F: ToCLSource,
     {
@@ -222,25 +492,93 @@ where
     F: ToCLSource,
     S: Shape,
 {
+    // built as an expression, so that complex gradients are multiplied and added correctly
+    let grad = Resolve::<T>::with_marker("lhs_grad[id]")
+        .add(Resolve::<T>::with_marker("out[id]").mul(lhs_grad_fn("lhs[id]".to_marker())));
     let src = format!(
-        "
+        "{complex_helpers}
         __kernel void add_unary_grad(__global const {datatype}* lhs, __global {datatype}* lhs_grad, __global const {datatype}* out) {{
             size_t id = get_global_id(0);
-            lhs_grad[id] += out[id] * {operation};
+            lhs_grad[id] = {grad};
         }}
     ",
+        complex_helpers = complex_prelude(grad.complex_c_type(), KernelLang::OpenCL),
         datatype = T::as_c_type_str(),
-        operation = lhs_grad_fn("lhs[id]".to_marker()).to_cl_source()
+        grad = grad.to_cl_source()
     );
 
     enqueue_kernel(device, &src, [lhs.len(), 0, 0], None, &[lhs, lhs_grad, out])?;
     Ok(())
 }
 
+impl<T, S> UpdateFn<T, S> for OpenCL
+where
+    T: CDatatype + Number,
+    S: Shape,
+{
+    #[inline]
+    fn update_fn<F, const N: usize>(
+        &self,
+        buf: &mut Buffer<T, Self, S>,
+        inputs: (&Buffer<T, Self, S>, &Buffer<T, Self, S>),
+        scalars: [T; N],
+        f: impl Fn(Resolve<T>, Resolve<T>, Resolve<T>, [Resolve<T>; N]) -> F + Sync,
+    ) where
+        F: ToCLSource,
+    {
+        try_cl_update_fn(self, buf, inputs, scalars, f).unwrap();
+    }
+}
+
+/// A failable OpenCL version of [`update_fn`](UpdateFn::update_fn).
+/// Updates `buf` in place with the result of `f`, which is called with the values of `buf`, `lhs`, `rhs` and the `scalars`.
+/// The scalars are passed as kernel arguments, hence the kernel is only compiled once for different values.
+pub fn try_cl_update_fn<T, S, F, const N: usize>(
+    device: &OpenCL,
+    buf: &mut Buffer<T, OpenCL, S>,
+    (lhs, rhs): (&Buffer<T, OpenCL, S>, &Buffer<T, OpenCL, S>),
+    scalars: [T; N],
+    f: impl Fn(Resolve<T>, Resolve<T>, Resolve<T>, [Resolve<T>; N]) -> F,
+) -> crate::Result<()>
+where
+    T: CDatatype + Number,
+    F: ToCLSource,
+    S: Shape,
+{
+    let datatype = T::as_c_type_str();
+    let scalar_params = SCALAR_MARKERS[..N]
+        .iter()
+        .map(|marker| format!(", const {datatype} {marker}"))
+        .collect::<String>();
+
+    let operation = f(
+        "buf[id]".to_marker(),
+        "lhs[id]".to_marker(),
+        "rhs[id]".to_marker(),
+        scalar_markers(),
+    );
+    let src = format!(
+        "{complex_helpers}
+        __kernel void update_fn(__global {datatype}* buf, __global const {datatype}* lhs, __global const {datatype}* rhs{scalar_params}) {{
+            size_t id = get_global_id(0);
+            buf[id] = {operation};
+        }}
+    ",
+        complex_helpers = complex_prelude(operation.complex_c_type(), KernelLang::OpenCL),
+        operation = operation.to_cl_source()
+    );
+
+    let mut args: Vec<&dyn AsClCvoidPtr> = vec![&*buf, lhs, rhs];
+    args.extend(scalars.iter().map(|scalar| scalar as &dyn AsClCvoidPtr));
+
+    enqueue_kernel(device, &src, [buf.len(), 0, 0], None, &args)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
-        opencl::{try_cl_add_unary_grad, try_cl_apply_fn},
+        opencl::{try_cl_add_unary_grad, try_cl_apply_fn, try_cl_update_fn},
         Buffer, Combiner, OpenCL,
     };
 
@@ -271,4 +609,31 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_cl_update_fn() -> crate::Result<()> {
+        let device = OpenCL::new(0)?;
+
+        let mut buf = Buffer::from((&device, [1, 2, 3, 4]));
+        let lhs = Buffer::from((&device, [2, 2, 2, 2]));
+        let rhs = Buffer::from((&device, [1, -1, 1, -1]));
+
+        try_cl_update_fn(&device, &mut buf, (&lhs, &rhs), [], |x, lhs, rhs, []| {
+            x.mul(lhs).add(rhs)
+        })?;
+        assert_eq!(buf.read(), [3, 3, 7, 7]);
+
+        // the same kernel is reused for different scalars
+        try_cl_update_fn(&device, &mut buf, (&lhs, &rhs), [2, 1], |x, lhs, _, [a, b]| {
+            x.mul(a).add(lhs.mul(b))
+        })?;
+        assert_eq!(buf.read(), [8, 8, 16, 16]);
+
+        try_cl_update_fn(&device, &mut buf, (&lhs, &rhs), [1, 3], |x, lhs, _, [a, b]| {
+            x.mul(a).add(lhs.mul(b))
+        })?;
+        assert_eq!(buf.read(), [14, 14, 22, 22]);
+
+        Ok(())
+    }
 }
@@ -1,10 +1,10 @@
-use std::{ffi::c_void, rc::Rc};
+use std::ffi::c_void;
 
 #[cfg(not(feature = "realloc"))]
 use crate::{AddGraph, AllocFlag, DeviceError, GraphReturn};
 
 use super::CLPtr;
-use crate::{Buffer, Ident, OpenCL, Shape, CPU};
+use crate::{Buffer, Ident, OpenCL, Shape, SharedPtr, CPU};
 use min_cl::api::{create_buffer, MemFlags};
 
 /// Returns an OpenCL pointer that is bound to the host pointer stored in the specified buffer.
@@ -25,7 +25,7 @@ pub unsafe fn to_cached_unified<T, S: Shape>(
 
     let old_ptr = device.addons.cache.borrow_mut().nodes.insert(
         Ident::new(no_drop.len()),
-        Rc::new(CLPtr {
+        SharedPtr::new(CLPtr {
             ptr: cl_ptr,
             host_ptr: no_drop.host_ptr() as *mut u8,
             len: no_drop.len(),
@@ -172,9 +172,9 @@ mod tests {
     #[cfg(not(feature = "realloc"))]
     #[test]
     fn test_cpu_to_unified_leak() -> crate::Result<()> {
-        use std::{collections::HashMap, hash::BuildHasherDefault, rc::Rc};
+        use std::{collections::HashMap, hash::BuildHasherDefault};
 
-        use crate::{range, set_count, Device, Ident, IdentHasher};
+        use crate::{range, set_count, Device, Ident, IdentHasher, SharedPtr};
 
         let cl_dev = OpenCL::new(0)?;
 
@@ -190,7 +190,7 @@ mod tests {
                 let mut hm = HashMap::<Ident, _, BuildHasherDefault<IdentHasher>>::default();
                 std::mem::swap(&mut cpu.addons.cache.borrow_mut().nodes, &mut hm);
                 for mut value in hm {
-                    let ptr = Rc::get_mut(&mut value.1).unwrap();
+                    let ptr = SharedPtr::get_mut(&mut value.1).unwrap();
                     ptr.ptr = std::ptr::null_mut();
                 }
                 cl_cpu_buf
@@ -0,0 +1,150 @@
+//! Interior mutability for the [`Addons`](crate::Addons) of a device.
+//!
+//! By default, [`Shared`] wraps a [`RefCell`](core::cell::RefCell) and cached pointers are stored in an [`Rc`](std::rc::Rc).
+//! If the `thread-safe` feature is enabled, [`Shared`] wraps a [`RwLock`](std::sync::RwLock) and an [`Arc`](std::sync::Arc) is used instead.
+//! This makes the [`CPU`](crate::CPU) and the `OpenCL` device `Send + Sync` (if the `autograd` feature is disabled),
+//! hence `Buffer`s of these devices can be moved across threads.
+
+use core::fmt::Debug;
+
+#[cfg(not(feature = "thread-safe"))]
+use core::cell::RefCell;
+
+#[cfg(feature = "thread-safe")]
+use std::sync::{PoisonError, RwLock};
+
+/// A shared reference to the value of a [`Shared`].
+#[cfg(not(feature = "thread-safe"))]
+pub type SharedRef<'a, T> = core::cell::Ref<'a, T>;
+
+/// A mutable reference to the value of a [`Shared`].
+#[cfg(not(feature = "thread-safe"))]
+pub type SharedRefMut<'a, T> = core::cell::RefMut<'a, T>;
+
+/// A shared reference to the value of a [`Shared`].
+#[cfg(feature = "thread-safe")]
+pub type SharedRef<'a, T> = std::sync::RwLockReadGuard<'a, T>;
+
+/// A mutable reference to the value of a [`Shared`].
+#[cfg(feature = "thread-safe")]
+pub type SharedRefMut<'a, T> = std::sync::RwLockWriteGuard<'a, T>;
+
+/// A reference counted pointer. Used for the pointers stored in a [`Cache`](crate::Cache).
+#[cfg(all(
+    not(feature = "thread-safe"),
+    any(not(feature = "no-std"), feature = "alloc")
+))]
+pub type SharedPtr<T> = alloc::rc::Rc<T>;
+
+/// A reference counted pointer. Used for the pointers stored in a [`Cache`](crate::Cache).
+#[cfg(feature = "thread-safe")]
+pub type SharedPtr<T> = std::sync::Arc<T>;
+
+/// A mutable memory location that is shared by everything that holds a reference to the device.
+/// This is a [`RefCell`](core::cell::RefCell) or, if the `thread-safe` feature is enabled, a [`RwLock`].
+///
+/// If the `thread-safe` feature is enabled, borrowing a value that is mutably borrowed by another thread blocks until it is released.
+/// Within the same thread, this would deadlock instead of panicking, as a [`RefCell`](core::cell::RefCell) does.
+/// # Example
+/// ```
+/// use custos::Shared;
+///
+/// let shared = Shared::new(vec![1, 2]);
+/// shared.borrow_mut().push(3);
+///
+/// assert_eq!(*shared.borrow(), [1, 2, 3]);
+/// ```
+#[derive(Default)]
+pub struct Shared<T> {
+    #[cfg(not(feature = "thread-safe"))]
+    inner: RefCell<T>,
+    #[cfg(feature = "thread-safe")]
+    inner: RwLock<T>,
+}
+
+impl<T> Shared<T> {
+    /// Creates a new `Shared` containing `value`.
+    #[inline]
+    pub fn new(value: T) -> Shared<T> {
+        Shared {
+            #[cfg(not(feature = "thread-safe"))]
+            inner: RefCell::new(value),
+            #[cfg(feature = "thread-safe")]
+            inner: RwLock::new(value),
+        }
+    }
+
+    /// Immutably borrows the wrapped value.
+    /// # Panics
+    /// If the `thread-safe` feature is disabled, this panics if the value is currently mutably borrowed.
+    #[inline]
+    pub fn borrow(&self) -> SharedRef<'_, T> {
+        #[cfg(not(feature = "thread-safe"))]
+        {
+            self.inner.borrow()
+        }
+
+        // A panic while the lock was held can not leave the addons in a state that is more broken
+        // than the state a `RefCell` would leave them in.
+        #[cfg(feature = "thread-safe")]
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Mutably borrows the wrapped value.
+    /// # Panics
+    /// If the `thread-safe` feature is disabled, this panics if the value is currently borrowed.
+    #[inline]
+    pub fn borrow_mut(&self) -> SharedRefMut<'_, T> {
+        #[cfg(not(feature = "thread-safe"))]
+        {
+            self.inner.borrow_mut()
+        }
+
+        #[cfg(feature = "thread-safe")]
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Consumes the `Shared`, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        #[cfg(not(feature = "thread-safe"))]
+        {
+            self.inner.into_inner()
+        }
+
+        #[cfg(feature = "thread-safe")]
+        self.inner
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Debug> Debug for Shared<T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+#[cfg(feature = "thread-safe")]
+#[cfg(test)]
+mod tests {
+    use super::Shared;
+
+    #[test]
+    fn test_shared_across_threads() {
+        let shared = Shared::new(0);
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        *shared.borrow_mut() += 1;
+                    }
+                });
+            }
+        });
+
+        assert_eq!(shared.into_inner(), 4000);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    number::Number,
+    shape::{AssertNonEmpty, AssertSameLen},
+    Buffer, Device, Dim1, Dim2, IsConstDim, MainMemory,
+};
+
+use super::Stack;
+
+/// Operations whose output shape is derived from the input shapes at compile time.
+/// As the inputs are only read, they may come from any [`MainMemory`] device.
+impl Stack {
+    /// Multiplies a `M x K` with a `K x N` matrix.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, Dim2, Stack};
+    ///
+    /// let lhs = Buffer::<_, _, Dim2<2, 3>>::from((&Stack, [1, 2, 3, 4, 5, 6]));
+    /// let rhs = Buffer::<_, _, Dim2<3, 2>>::from((&Stack, [1, 0, 0, 1, 1, 1]));
+    ///
+    /// let out = Stack.matmul(&lhs, &rhs);
+    /// assert_eq!(out.read(), [[4, 5], [10, 11]]);
+    /// ```
+    pub fn matmul<T, D, const M: usize, const K: usize, const N: usize>(
+        &self,
+        lhs: &Buffer<T, D, Dim2<M, K>>,
+        rhs: &Buffer<T, D, Dim2<K, N>>,
+    ) -> Buffer<T, Stack, Dim2<M, N>>
+    where
+        T: Number,
+        D: MainMemory,
+    {
+        let mut out = self.retrieve::<T, Dim2<M, N>>(M * N, (lhs, rhs));
+
+        for row in 0..M {
+            for k in 0..K {
+                let value = lhs[row * K + k];
+                for col in 0..N {
+                    out[row * N + col] += value * rhs[k * N + col];
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Transposes a `B x A` into an `A x B` matrix.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, Dim2, Stack};
+    ///
+    /// let buf = Buffer::<_, _, Dim2<2, 3>>::from((&Stack, [1, 2, 3, 4, 5, 6]));
+    ///
+    /// let out = Stack.transpose(&buf);
+    /// assert_eq!(out.read(), [[1, 4], [2, 5], [3, 6]]);
+    /// ```
+    pub fn transpose<T, D, const B: usize, const A: usize>(
+        &self,
+        buf: &Buffer<T, D, Dim2<B, A>>,
+    ) -> Buffer<T, Stack, Dim2<A, B>>
+    where
+        T: Copy + Default,
+        D: MainMemory,
+    {
+        let mut out = self.retrieve::<T, Dim2<A, B>>(A * B, buf);
+
+        for row in 0..B {
+            for col in 0..A {
+                out[col * B + row] = buf[row * A + col];
+            }
+        }
+
+        out
+    }
+
+    /// Copies the elements of `buf` into a buffer of shape `O`.
+    /// Fails to build if `I` and `O` do not contain the same amount of elements.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, Dim1, Dim2, Stack};
+    ///
+    /// let buf = Buffer::<_, _, Dim1<6>>::from((&Stack, [1, 2, 3, 4, 5, 6]));
+    ///
+    /// let out = Stack.reshape::<_, _, _, Dim2<3, 2>>(&buf);
+    /// assert_eq!(out.read(), [[1, 2], [3, 4], [5, 6]]);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use custos::{Buffer, Dim1, Dim2, Stack};
+    ///
+    /// let buf = Buffer::<_, _, Dim1<6>>::from((&Stack, [1, 2, 3, 4, 5, 6]));
+    /// let out = Stack.reshape::<_, _, _, Dim2<2, 2>>(&buf);
+    /// ```
+    pub fn reshape<T, D, I, O>(&self, buf: &Buffer<T, D, I>) -> Buffer<T, Stack, O>
+    where
+        T: Copy + Default,
+        D: MainMemory,
+        I: IsConstDim,
+        O: IsConstDim,
+    {
+        let () = AssertSameLen::<I, O>::OK;
+
+        let mut out = self.retrieve::<T, O>(O::LEN, buf);
+        out.copy_from_slice(buf);
+        out
+    }
+
+    /// Returns the sum of all elements.
+    #[inline]
+    pub fn sum<T: Number, D: MainMemory, S: IsConstDim>(&self, buf: &Buffer<T, D, S>) -> T {
+        buf.iter().copied().sum()
+    }
+
+    /// Returns the arithmetic mean of all elements.
+    /// Fails to build if `S` does not contain any elements.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, Dim1, Stack};
+    ///
+    /// let buf = Buffer::<_, _, Dim1<4>>::from((&Stack, [4., -1., 7., 2.]));
+    ///
+    /// assert_eq!(Stack.mean(&buf), 3.);
+    /// assert_eq!(Stack.max(&buf), 7.);
+    /// assert_eq!(Stack.min(&buf), -1.);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use custos::{Buffer, Dim1, Stack};
+    ///
+    /// let buf = Buffer::<f32, _, Dim1<0>>::from((&Stack, []));
+    /// Stack.mean(&buf);
+    /// ```
+    #[inline]
+    pub fn mean<T: Number, D: MainMemory, S: IsConstDim>(&self, buf: &Buffer<T, D, S>) -> T {
+        let () = AssertNonEmpty::<S>::OK;
+        self.sum(buf) / T::from_usize(S::LEN)
+    }
+
+    /// Returns the largest element.
+    /// Fails to build if `S` does not contain any elements.
+    #[inline]
+    pub fn max<T: Number, D: MainMemory, S: IsConstDim>(&self, buf: &Buffer<T, D, S>) -> T {
+        let () = AssertNonEmpty::<S>::OK;
+        buf.iter()
+            .copied()
+            .fold(buf[0], |acc, value| if value > acc { value } else { acc })
+    }
+
+    /// Returns the smallest element.
+    /// Fails to build if `S` does not contain any elements.
+    #[inline]
+    pub fn min<T: Number, D: MainMemory, S: IsConstDim>(&self, buf: &Buffer<T, D, S>) -> T {
+        let () = AssertNonEmpty::<S>::OK;
+        buf.iter()
+            .copied()
+            .fold(buf[0], |acc, value| if value < acc { value } else { acc })
+    }
+
+    /// Sums up the rows of a `B x A` matrix, resulting in a row vector with `A` elements.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, Dim2, Stack};
+    ///
+    /// let buf = Buffer::<_, _, Dim2<2, 3>>::from((&Stack, [1, 2, 3, 4, 5, 6]));
+    ///
+    /// assert_eq!(Stack.sum_rows(&buf).read(), [5, 7, 9]);
+    /// assert_eq!(Stack.sum_cols(&buf).read(), [6, 15]);
+    /// ```
+    pub fn sum_rows<T, D, const B: usize, const A: usize>(
+        &self,
+        buf: &Buffer<T, D, Dim2<B, A>>,
+    ) -> Buffer<T, Stack, Dim1<A>>
+    where
+        T: Number,
+        D: MainMemory,
+    {
+        let mut out = self.retrieve::<T, Dim1<A>>(A, buf);
+
+        for row in buf.chunks(A) {
+            for (value, x) in out.iter_mut().zip(row) {
+                *value += *x;
+            }
+        }
+
+        out
+    }
+
+    /// Sums up the columns of a `B x A` matrix, resulting in a column vector with `B` elements.
+    pub fn sum_cols<T, D, const B: usize, const A: usize>(
+        &self,
+        buf: &Buffer<T, D, Dim2<B, A>>,
+    ) -> Buffer<T, Stack, Dim1<B>>
+    where
+        T: Number,
+        D: MainMemory,
+    {
+        let mut out = self.retrieve::<T, Dim1<B>>(B, buf);
+
+        for (value, row) in out.iter_mut().zip(buf.chunks(A)) {
+            *value = row.iter().copied().sum();
+        }
+
+        out
+    }
+}
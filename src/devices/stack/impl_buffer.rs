@@ -27,7 +27,7 @@ impl<'a, T: Copy, const N: usize> From<&[T; N]> for Buffer<'a, T, Stack, N> {
 impl<'a, T, const N: usize> From<(Stack, [T; N])> for Buffer<'a, T, Stack, Dim1<N>> {
     fn from((_, array): (Stack, [T; N])) -> Self {
         Buffer {
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: None,
             ptr: StackArray::from_array(array),
             device: Some(&Stack),
@@ -38,7 +38,7 @@ impl<'a, T, const N: usize> From<(Stack, [T; N])> for Buffer<'a, T, Stack, Dim1<
 impl<'a, T, const N: usize> From<(&'a Stack, [T; N])> for Buffer<'a, T, Stack, Dim1<N>> {
     fn from((_, array): (&Stack, [T; N])) -> Self {
         Buffer {
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: None,
             ptr: StackArray::from_array(array),
             device: Some(&Stack),
@@ -53,7 +53,7 @@ impl<'a, T: Copy + Default, const A: usize, const B: usize, const N: usize> From
         let mut arr = StackArray::new();
         arr.copy_from_slice(&array);
         Buffer {
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: None,
             ptr: arr,
             device: Some(&Stack),
@@ -64,7 +64,7 @@ impl<'a, T: Copy + Default, const A: usize, const B: usize, const N: usize> From
 impl<'a, T: Copy, const N: usize> From<(Stack, &[T; N])> for Buffer<'a, T, Stack, Dim1<N>> {
     fn from((_, array): (Stack, &[T; N])) -> Self {
         Buffer {
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: None,
             ptr: StackArray::from_array(*array),
             device: Some(&Stack),
@@ -75,7 +75,7 @@ impl<'a, T: Copy, const N: usize> From<(Stack, &[T; N])> for Buffer<'a, T, Stack
 impl<'a, T: Copy, const N: usize> From<(&Stack, &[T; N])> for Buffer<'a, T, Stack, Dim1<N>> {
     fn from((_, array): (&Stack, &[T; N])) -> Self {
         Buffer {
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: None,
             ptr: StackArray::from_array(*array),
             device: Some(&Stack),
@@ -91,7 +91,7 @@ impl<'a, T: Copy + Default, const N: usize, const A: usize, const B: usize> From
         arr.copy_from_slice(array);
         Buffer {
             // TODO: is this correct
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: None,
             // ident: Some(Ident::new_bumped(arr.len())),
             ptr: arr,
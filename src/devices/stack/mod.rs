@@ -1,20 +1,14 @@
 //! The Stack module provides the Stack backend for custos.
 
+mod const_ops;
 mod impl_buffer;
 mod stack_device;
 
 pub use stack_device::*;
 
-use crate::{Buffer, ClearBuf, MainMemory, Shape};
+use crate::devices::host_ops::impl_host_ops;
 
-// #[impl_stack]
-impl<T: Default, D: MainMemory, S: Shape> ClearBuf<T, S, D> for Stack {
-    fn clear(&self, buf: &mut Buffer<T, D, S>) {
-        for value in buf {
-            *value = T::default();
-        }
-    }
-}
+impl_host_ops!(Stack);
 
 #[cfg(feature = "cpu")]
 #[cfg(test)]
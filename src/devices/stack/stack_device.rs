@@ -3,6 +3,9 @@ use crate::{
     Read, StackArray, WriteBuf,
 };
 
+#[cfg(not(feature = "no-std"))]
+use crate::{ReadAsync, Transfer, WriteAsync};
+
 /// A device that allocates memory on the stack.
 #[derive(Debug, Clone, Copy)]
 pub struct Stack;
@@ -93,7 +96,7 @@ where
         Buffer {
             ptr: buf.ptr,
             device: Some(&Stack),
-            #[cfg(not(feature = "no-std"))]
+            #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
             ident: buf.ident,
         }
     }
@@ -111,6 +114,27 @@ impl<T: Copy, S: Shape> WriteBuf<T, S> for Stack {
     }
 }
 
+#[cfg(not(feature = "no-std"))]
+impl<T: Copy, S: Shape> ReadAsync<T, S> for Stack {
+    #[inline]
+    fn read_async<'a>(&'a self, buf: &'a Buffer<T, Stack, S>) -> Transfer<'a, Vec<T>> {
+        Transfer::ready(buf.to_vec())
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl<T: Copy, S: Shape> WriteAsync<T, S> for Stack {
+    #[inline]
+    fn write_async<'a>(
+        &'a self,
+        buf: &'a mut Buffer<T, Stack, S>,
+        data: &'a [T],
+    ) -> Transfer<'a, ()> {
+        self.write(buf, data);
+        Transfer::ready(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(not(feature = "no-std"))]
@@ -1,6 +1,6 @@
 use wgpu::BindingResource;
 
-use crate::{Buffer, Shape};
+use crate::{Buffer, KernelTemplate, Shape};
 
 use super::WGPU;
 
@@ -28,8 +28,16 @@ impl<'a, T, S: Shape> AsBindingResource for &mut Buffer<'a, T, WGPU, S> {
     }
 }
 
+impl AsBindingResource for &dyn AsBindingResource {
+    fn as_binding_resource(&self) -> BindingResource {
+        (**self).as_binding_resource()
+    }
+}
+
 /// Launches a `WGPU` compute shader.
 ///
+/// # Panics
+/// If the shader cannot be created, see [`try_launch_shader`].
 /// # Example
 ///
 /// ```
@@ -85,8 +93,21 @@ impl<'a, T, S: Shape> AsBindingResource for &mut Buffer<'a, T, WGPU, S> {
 ///
 /// ```
 pub fn launch_shader(device: &WGPU, src: &str, gws: [u32; 3], args: &[impl AsBindingResource]) {
+    try_launch_shader(device, src, gws, args).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Like [`launch_shader`], but returns an error if the shader cannot be created.
+///
+/// # Errors
+/// [`DeviceError::WGPUShaderF16Unsupported`](crate::DeviceError::WGPUShaderF16Unsupported) if the shader uses `f16`, but the device does not support it.
+pub fn try_launch_shader(
+    device: &WGPU,
+    src: &str,
+    gws: [u32; 3],
+    args: &[impl AsBindingResource],
+) -> crate::Result<()> {
     let mut shader_cache = device.shader_cache.borrow_mut();
-    let shader = shader_cache.shader(&device.device, src);
+    let shader = shader_cache.shader(&device.device, src)?;
 
     let compute_pipeline =
         device
@@ -127,4 +148,51 @@ pub fn launch_shader(device: &WGPU, src: &str, gws: [u32; 3], args: &[impl AsBin
     }
 
     device.queue.submit(Some(encoder.finish()));
+    Ok(())
+}
+
+/// Launches a `WGPU` compute shader, which is instantiated from a [`KernelTemplate`].
+/// The instantiated source is cached, hence the template is only rendered once.
+///
+/// # Example
+///
+/// ```
+/// use custos::{Buffer, KernelTemplate, WGPU, wgpu::launch_shader_template};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = WGPU::new(wgpu::Backends::all())?;
+///     let mut buf = Buffer::from((&device, [1, 2, 3, 4]));
+///
+///     let template = KernelTemplate::new("
+///         @group(0) @binding(0)
+///         var<storage, read_write> buf: array<${T}>;
+///
+///         @compute @workgroup_size(1)
+///         fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+///             buf[global_id.x] *= ${FACTOR};
+///         }
+///     ")
+///     .ty::<i32>("T")
+///     .value("FACTOR", 3);
+///
+///     launch_shader_template(&device, &template, [buf.len() as u32, 1, 1], &[&mut buf])?;
+///     assert_eq!(buf.read(), [3, 6, 9, 12]);
+///
+///     Ok(())
+/// }
+/// ```
+pub fn launch_shader_template(
+    device: &WGPU,
+    template: &KernelTemplate,
+    gws: [u32; 3],
+    args: &[impl AsBindingResource],
+) -> crate::Result<()> {
+    let src = device
+        .shader_cache
+        .borrow_mut()
+        .templates
+        .source(template)?
+        .to_string();
+
+    try_launch_shader(device, &src, gws, args)
 }
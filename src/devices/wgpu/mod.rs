@@ -9,7 +9,49 @@ use core::fmt::Debug;
 pub use launch_shader::*;
 pub use wgpu_device::*;
 
-use crate::{Buffer, Shape};
+use crate::{
+    number::Float,
+    random::{philox_wgsl_source, seed_key, Distribution},
+    Buffer, Device, Shape,
+};
+
+/// The element types that can be stored in WGSL storage buffers.
+/// `bool` cannot be stored in storage buffers, `f64` and `bf16` do not exist in WGSL.
+pub trait WGSLDatatype: 'static {
+    /// Returns the equivalent WGSL type as a string
+    fn as_wgsl_type_str() -> &'static str;
+}
+
+impl WGSLDatatype for f32 {
+    #[inline]
+    fn as_wgsl_type_str() -> &'static str {
+        "f32"
+    }
+}
+
+impl WGSLDatatype for i32 {
+    #[inline]
+    fn as_wgsl_type_str() -> &'static str {
+        "i32"
+    }
+}
+
+impl WGSLDatatype for u32 {
+    #[inline]
+    fn as_wgsl_type_str() -> &'static str {
+        "u32"
+    }
+}
+
+/// Shaders using `f16` require the `SHADER_FLOAT16` feature of the device.
+/// The shader cache enables the extension and returns [`DeviceError::WGPUShaderF16Unsupported`](crate::DeviceError::WGPUShaderF16Unsupported) if the feature is missing.
+#[cfg(feature = "half")]
+impl WGSLDatatype for half::f16 {
+    #[inline]
+    fn as_wgsl_type_str() -> &'static str {
+        "f16"
+    }
+}
 
 /// Sets all the elements of a `WGPU` `Buffer` to zero / default.
 ///
@@ -26,7 +68,11 @@ use crate::{Buffer, Shape};
 ///     Ok(())
 /// }
 /// ```
-pub fn wgpu_clear<T: Default + Debug, S: Shape>(device: &WGPU, buf: &mut Buffer<T, WGPU, S>) {
+pub fn wgpu_clear<T, S>(device: &WGPU, buf: &mut Buffer<T, WGPU, S>)
+where
+    T: WGSLDatatype + Default + Debug,
+    S: Shape,
+{
     let src = format!(
         "@group(0)
         @binding(0)
@@ -38,17 +84,201 @@ pub fn wgpu_clear<T: Default + Debug, S: Shape>(device: &WGPU, buf: &mut Buffer<
             buf[global_id.x] = {zero:?};
         }}
         ",
-        datatype = std::any::type_name::<T>(),
+        datatype = T::as_wgsl_type_str(),
         zero = T::default()
     );
 
     launch_shader(device, &src, [buf.len() as u32, 1, 1], &[buf])
 }
 
+/// Casts the elements of a `WGPU` `Buffer` to another type.
+/// Both types must be representable in WGSL (see [`WGSLDatatype`]).
+///
+/// # Example
+/// ```
+/// use custos::{WGPU, Buffer, wgpu::wgpu_cast};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = WGPU::new(wgpu::Backends::all())?;
+///     let buf = Buffer::from((&device, [1.5f32, 0., -3.2]));
+///
+///     let casted = wgpu_cast::<_, i32, ()>(&device, &buf);
+///     assert_eq!(casted.read(), [1, 0, -3]);
+///     Ok(())
+/// }
+/// ```
+pub fn wgpu_cast<'a, T, U, S>(device: &'a WGPU, x: &Buffer<T, WGPU, S>) -> Buffer<'a, U, WGPU, S>
+where
+    T: WGSLDatatype,
+    U: WGSLDatatype,
+    S: Shape,
+{
+    let src = format!(
+        "@group(0)
+        @binding(0)
+        var<storage, read_write> x: array<{datatype}>;
+
+        @group(0)
+        @binding(1)
+        var<storage, read_write> out: array<{out_datatype}>;
+        
+        @compute
+        @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+            out[global_id.x] = {out_datatype}(x[global_id.x]);
+        }}
+        ",
+        datatype = T::as_wgsl_type_str(),
+        out_datatype = U::as_wgsl_type_str(),
+    );
+
+    let out = device.retrieve::<U, S>(x.len(), x);
+    // the buffers have different element types
+    let args: [&dyn AsBindingResource; 2] = [x, &out];
+    launch_shader(device, &src, [x.len() as u32, 1, 1], &args);
+    out
+}
+
+/// Sets the i-th element of a `WGPU` `Buffer` to `start + i * step`.
+/// The values are inserted into the shader source.
+///
+/// # Example
+/// ```
+/// use custos::{WGPU, Buffer, wgpu::wgpu_fill_range};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = WGPU::new(wgpu::Backends::all())?;
+///     let mut buf = Buffer::<i32, _>::new(&device, 4);
+///
+///     wgpu_fill_range(&device, &mut buf, 3, 2);
+///     assert_eq!(buf.read(), [3, 5, 7, 9]);
+///     Ok(())
+/// }
+/// ```
+pub fn wgpu_fill_range<T: WGSLDatatype + Debug, S: Shape>(
+    device: &WGPU,
+    buf: &mut Buffer<T, WGPU, S>,
+    start: T,
+    step: T,
+) {
+    let src = format!(
+        "@group(0)
+        @binding(0)
+        var<storage, read_write> out: array<{datatype}>;
+
+        @compute
+        @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+            out[global_id.x] = {datatype}({start:?}) + {datatype}(global_id.x) * {datatype}({step:?});
+        }}
+        ",
+        datatype = T::as_wgsl_type_str(),
+    );
+
+    launch_shader(device, &src, [buf.len() as u32, 1, 1], &[buf])
+}
+
+/// Sets the elements on the diagonal of a row-major `WGPU` matrix with `cols` columns to one and all other elements to zero.
+pub fn wgpu_fill_eye<T: WGSLDatatype, S: Shape>(
+    device: &WGPU,
+    buf: &mut Buffer<T, WGPU, S>,
+    cols: usize,
+) {
+    let src = format!(
+        "@group(0)
+        @binding(0)
+        var<storage, read_write> out: array<{datatype}>;
+
+        @compute
+        @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+            let id = global_id.x;
+            out[id] = select({datatype}(0), {datatype}(1), id / {cols}u == id % {cols}u);
+        }}
+        ",
+        datatype = T::as_wgsl_type_str(),
+    );
+
+    launch_shader(device, &src, [buf.len() as u32, 1, 1], &[buf])
+}
+
+/// Fills a `WGPU` `Buffer` with values sampled from the [`Distribution`].
+/// The seed and the parameters of the distribution are passed as buffers,
+/// hence only one shader per data type and kind of distribution is compiled.
+///
+/// # Example
+/// ```
+/// use custos::{WGPU, Buffer, random::{self, Distribution}, wgpu::wgpu_rand};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = WGPU::new(wgpu::Backends::all())?;
+///     let mut buf = Buffer::<f32, _>::new(&device, 4);
+///
+///     wgpu_rand(&device, &mut buf, 42, Distribution::Uniform { lo: -1., hi: 1. });
+///     assert_eq!(buf.read()[3], random::uniform(42, 3, -1., 1.));
+///     Ok(())
+/// }
+/// ```
+pub fn wgpu_rand<T: WGSLDatatype + Float, S: Shape>(
+    device: &WGPU,
+    buf: &mut Buffer<T, WGPU, S>,
+    seed: u64,
+    dist: Distribution<T>,
+) {
+    let datatype = T::as_wgsl_type_str();
+
+    let src = format!(
+        "{philox}
+        @group(0)
+        @binding(0)
+        var<storage, read_write> out: array<{datatype}>;
+
+        @group(0)
+        @binding(1)
+        var<storage, read> key: array<u32>;
+
+        @group(0)
+        @binding(2)
+        var<storage, read> params: array<{datatype}>;
+
+        @compute
+        @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+            let a = params[0];
+            let b = params[1];
+            let bits = philox4x32(vec4<u32>(global_id.x, 0u, 0u, 0u), key[0], key[1]);
+            out[global_id.x] = {sample};
+        }}
+        ",
+        philox = philox_wgsl_source(),
+        sample = dist.sample_src(datatype, true),
+    );
+
+    let (a, b) = dist.params();
+    let key = Buffer::<u32, WGPU>::from((device, seed_key(seed)));
+    let params = Buffer::<T, WGPU>::from((device, [a, b]));
+
+    launch_shader(
+        device,
+        &src,
+        [buf.len() as u32, 1, 1],
+        &[&*buf as &dyn AsBindingResource, &key, &params],
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Buffer, WGPU};
 
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_wgsl_datatype() {
+        use super::WGSLDatatype;
+
+        assert_eq!(f32::as_wgsl_type_str(), "f32");
+        assert_eq!(half::f16::as_wgsl_type_str(), "f16");
+    }
+
     #[test]
     fn test_wgpu_clear() -> crate::Result<()> {
         let device = WGPU::new(wgpu::Backends::all())?;
@@ -60,4 +290,15 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[should_panic(expected = "must match the length of the buffer")]
+    fn test_wgpu_write_wrong_len() {
+        use crate::WriteBuf;
+
+        let device = WGPU::new(wgpu::Backends::all()).unwrap();
+
+        let mut buf = Buffer::<f32, _>::new(&device, 3);
+        device.write(&mut buf, &[1., 2.]);
+    }
 }
@@ -2,27 +2,67 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use wgpu::ShaderModule;
 
-#[derive(Debug, Default)]
+use crate::{KernelLang, TemplateCache};
+
+#[cfg(feature = "half")]
+use crate::{devices::kernel_source::contains_ident, DeviceError};
+
+#[derive(Debug)]
 pub struct ShaderCache {
     shaders: HashMap<String, ShaderModule>,
+    /// The instantiated sources of [`KernelTemplate`](crate::KernelTemplate)s, see [`launch_shader_template`](super::launch_shader_template).
+    pub templates: TemplateCache,
+}
+
+impl Default for ShaderCache {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            shaders: HashMap::new(),
+            templates: TemplateCache::new(KernelLang::WGSL),
+        }
+    }
 }
 
 impl ShaderCache {
-    pub fn shader(&mut self, device: &wgpu::Device, src: &str) -> &ShaderModule {
-        self.add_shader(device, src);
-        self.shaders.get(src).unwrap()
+    /// Returns a cached shader module. If the source code was not compiled before, a new module is created and cached.
+    ///
+    /// # Errors
+    /// [`DeviceError::WGPUShaderF16Unsupported`] if the shader uses `f16`, but the device does not support it.
+    pub fn shader(&mut self, device: &wgpu::Device, src: &str) -> crate::Result<&ShaderModule> {
+        self.add_shader(device, src)?;
+        Ok(self.shaders.get(src).unwrap())
     }
 
-    fn add_shader(&mut self, device: &wgpu::Device, src: &str) {
+    fn add_shader(&mut self, device: &wgpu::Device, src: &str) -> crate::Result<()> {
         if self.shaders.get(src).is_some() {
-            return;
+            return Ok(());
         }
 
+        let source = with_extensions(device, src)?;
+
         let cs_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(src)),
+            source: wgpu::ShaderSource::Wgsl(source),
         });
 
         self.shaders.insert(src.to_string(), cs_module);
+        Ok(())
+    }
+}
+
+/// Enables the `f16` extension if the shader uses the element type `f16` (`half::f16`).
+#[cfg_attr(not(feature = "half"), allow(unused_variables))]
+fn with_extensions<'a>(device: &wgpu::Device, src: &'a str) -> crate::Result<Cow<'a, str>> {
+    #[cfg(feature = "half")]
+    if contains_ident(src, <half::f16 as super::WGSLDatatype>::as_wgsl_type_str())
+        && !src.contains("enable f16")
+    {
+        if !device.features().contains(wgpu::Features::SHADER_FLOAT16) {
+            return Err(DeviceError::WGPUShaderF16Unsupported.into());
+        }
+        return Ok(Cow::Owned(format!("enable f16;\n{src}")));
     }
+
+    Ok(Cow::Borrowed(src))
 }
@@ -1,13 +1,18 @@
 use core::{cell::RefCell, fmt::Debug, ptr::null_mut};
 
 use super::{
-    launch_shader, shader_cache::ShaderCache, wgpu_buffer::*, wgpu_clear, AsBindingResource,
+    launch_shader, shader_cache::ShaderCache, wgpu_buffer::*, wgpu_cast, wgpu_clear, wgpu_fill_eye,
+    wgpu_fill_range, wgpu_rand, AsBindingResource, WGSLDatatype,
 };
 
 use crate::{
-    flag::AllocFlag, Addons, AddonsReturn, Alloc, Cache, ClearBuf, Device, DeviceError, PtrConv,
-    PtrType, Read, Shape,
+    flag::AllocFlag,
+    number::{Float, Number},
+    random::Distribution,
+    Addons, AddonsReturn, Alloc, Cache, CastBuf, ClearBuf, Device, DeviceError, FillBuf, PtrConv,
+    PtrType, RandBuf, Read, ReadAsync, Shape, Transfer, WriteBuf,
 };
+use std::sync::mpsc::TryRecvError;
 use wgpu::{Adapter, Backends, Queue};
 
 /// Used to perform calculations with an WGPU capable device.
@@ -51,10 +56,14 @@ impl WGPU {
             pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
                 .ok_or(DeviceError::WGPUDeviceReturn)?;
 
+        // f16 shaders are only supported if the adapter offers them
+        let features = wgpu::Features::MAPPABLE_PRIMARY_BUFFERS
+            | (adapter.features() & wgpu::Features::SHADER_FLOAT16);
+
         let (device, queue) = pollster::block_on(adapter.request_device(
             &wgpu::DeviceDescriptor {
                 label: None,
-                features: wgpu::Features::MAPPABLE_PRIMARY_BUFFERS,
+                features,
                 limits: wgpu::Limits::downlevel_defaults(),
             },
             None,
@@ -190,7 +199,7 @@ impl PtrConv for WGPU {
     }
 }
 
-impl<T: Default + Debug, S: Shape> ClearBuf<T, S> for WGPU {
+impl<T: WGSLDatatype + Default + Debug, S: Shape> ClearBuf<T, S> for WGPU {
     /// Sets all the elements of a `WGPU` `Buffer` to zero / default.
     /// # Example
     /// ```
@@ -210,6 +219,37 @@ impl<T: Default + Debug, S: Shape> ClearBuf<T, S> for WGPU {
     }
 }
 
+impl<T: WGSLDatatype, U: WGSLDatatype, S: Shape> CastBuf<T, U, S> for WGPU {
+    #[inline]
+    fn cast(&self, buf: &crate::Buffer<T, Self, S>) -> crate::Buffer<U, Self, S> {
+        wgpu_cast(self, buf)
+    }
+}
+
+impl<T: WGSLDatatype + Number, S: Shape> FillBuf<T, S> for WGPU {
+    #[inline]
+    fn fill(&self, buf: &mut crate::Buffer<T, Self, S>, value: T) {
+        wgpu_fill_range(self, buf, value, T::zero())
+    }
+
+    #[inline]
+    fn fill_range(&self, buf: &mut crate::Buffer<T, Self, S>, start: T, step: T) {
+        wgpu_fill_range(self, buf, start, step)
+    }
+
+    #[inline]
+    fn fill_eye(&self, buf: &mut crate::Buffer<T, Self, S>, cols: usize) {
+        wgpu_fill_eye(self, buf, cols)
+    }
+}
+
+impl<T: WGSLDatatype + Float, S: Shape> RandBuf<T, S> for WGPU {
+    #[inline]
+    fn rand(&self, buf: &mut crate::Buffer<T, Self, S>, seed: u64, dist: Distribution<T>) {
+        wgpu_rand(self, buf, seed, dist)
+    }
+}
+
 impl<T: Default + Clone> Read<T> for WGPU {
     type Read<'a> = Vec<T>
     where
@@ -246,3 +286,73 @@ impl<T: Default + Clone> Read<T> for WGPU {
         read
     }
 }
+
+impl<T> WriteBuf<T> for WGPU {
+    #[inline]
+    fn write(&self, buf: &mut crate::Buffer<T, Self>, data: &[T]) {
+        assert_eq!(
+            buf.len(),
+            data.len(),
+            "The length of the data must match the length of the buffer."
+        );
+        self.queue
+            .write_buffer(unsafe { buf.ptr.buf() }, 0, slice_u8_cast(data));
+    }
+
+    fn write_buf(&self, dst: &mut crate::Buffer<T, Self>, src: &crate::Buffer<T, Self>) {
+        assert_eq!(
+            dst.len(),
+            src.len(),
+            "The buffers must have the same length."
+        );
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        encoder.copy_buffer_to_buffer(
+            unsafe { src.ptr.buf() },
+            0,
+            unsafe { dst.ptr.buf() },
+            0,
+            (src.len() * core::mem::size_of::<T>()) as u64,
+        );
+        self.queue.submit(Some(encoder.finish()));
+    }
+}
+
+/// Operations without a WGSL implementation, e.g. [`ApplyFunction`](crate::ApplyFunction), are executed on the CPU.
+#[cfg(feature = "cpu")]
+impl crate::exec_on_cpu::CPUFallback for WGPU {}
+
+impl<T: Default + Clone> ReadAsync<T> for WGPU {
+    fn read_async<'a>(&'a self, buf: &'a crate::Buffer<T, Self>) -> Transfer<'a, Vec<T>> {
+        self.queue.submit(None);
+
+        let buf = unsafe { buf.ptr.buf() };
+        let (sender, receiver) = std::sync::mpsc::channel();
+        buf.slice(..).map_async(wgpu::MapMode::Read, move |v| {
+            let _ = sender.send(v);
+        });
+
+        Transfer::new(move |block| {
+            let maintain = match block {
+                true => wgpu::Maintain::Wait,
+                false => wgpu::Maintain::Poll,
+            };
+            self.device.poll(maintain);
+
+            // the callback was called during the poll if the buffer is mapped
+            match receiver.try_recv() {
+                Ok(Ok(())) => (),
+                Err(TryRecvError::Empty) if !block => return None,
+                _ => return Some(Err(DeviceError::WGPUMapFailed.into())),
+            }
+
+            let data = buf.slice(..).get_mapped_range();
+            let read = slice_gen_cast::<T>(&data).to_vec();
+            drop(data);
+            buf.unmap();
+            Some(Ok(read))
+        })
+    }
+}
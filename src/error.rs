@@ -34,6 +34,14 @@ pub struct Error {}
 #[cfg(feature = "no-std")]
 pub type Result<T> = core::result::Result<T, Error>;
 
+#[cfg(feature = "no-std")]
+impl From<DeviceError> for Error {
+    #[inline]
+    fn from(_err: DeviceError) -> Self {
+        Error {}
+    }
+}
+
 /// 'generic' device errors that can occur on any device.
 #[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum DeviceError {
@@ -49,6 +57,12 @@ pub enum DeviceError {
     WGPUDeviceReturn,
     /// The 'cpu' feature is disabled. Hence this CPU can't be created.
     CPUDeviceNotAvailable,
+    /// A WGPU buffer could not be mapped for reading.
+    WGPUMapFailed,
+    /// The buffer does not contain the amount of elements required by the target shape.
+    ShapeLengthMismatch,
+    /// The shader uses `f16`, but the WGPU device does not support the `SHADER_FLOAT16` feature.
+    WGPUShaderF16Unsupported,
 }
 
 impl DeviceError {
@@ -67,6 +81,13 @@ impl DeviceError {
             DeviceError::CPUDeviceNotAvailable => {
                 "The 'cpu' feature is disabled. Hence this CPU can't be created."
             }
+            DeviceError::WGPUMapFailed => "A WGPU buffer could not be mapped for reading.",
+            DeviceError::ShapeLengthMismatch => {
+                "The buffer does not contain the amount of elements required by the target shape."
+            }
+            DeviceError::WGPUShaderF16Unsupported => {
+                "The shader uses f16, but the WGPU device does not support the SHADER_FLOAT16 feature."
+            }
         }
     }
 }
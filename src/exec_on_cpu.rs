@@ -1,14 +1,22 @@
 //! This module includes macros and functions for executing operations on the CPU.
 //! They move the supplied (CUDA, OpenCL, WGPU, ...) `Buffer`s to the CPU and execute the operation on the CPU.
 //! Most of the time, you should actually implement the operation for the device natively, as it is typically faster.
+//!
+//! The resulting `Buffer`s are retrieved from the cache of the original device and are therefore part of its graph.
+//! The `*_may_grad` variants additionally add a gradient function, which is executed on the `CPU` as well, to the [`Tape`](crate::Tape).
 
 #[cfg(feature = "opencl")]
 mod cl_may_unified;
+#[cfg(not(feature = "no-std"))]
+mod fallback;
+
+#[cfg(not(feature = "no-std"))]
+pub use fallback::*;
 
 #[cfg(feature = "opencl")]
 pub use cl_may_unified::*;
 
-use crate::{Alloc, Buffer, Device, Read, WriteBuf, CPU};
+use crate::{Alloc, Buffer, Device, MayTapeReturn, Read, WriteBuf, CPU};
 
 /// Moves a `Buffer` stored on device `D` to a `CPU` `Buffer`
 /// and executes the unary operation `F` with a `CPU` on the newly created `CPU` `Buffer`.
@@ -51,8 +59,79 @@ where
 {
     let cpu = CPU::new();
     let cpu_buf = Buffer::<T, CPU>::from((&cpu, x.read_to_vec()));
-    Ok(Buffer::from((device, f(&cpu, &cpu_buf))))
-    // TODO add new node to graph
+    let cpu_out = f(&cpu, &cpu_buf);
+    Ok(write_to_retrieved(device, &cpu_out, x))
+}
+
+/// Same as [`cpu_exec_unary`], but additionally adds the gradient function `grad_fn` to the [`Tape`](crate::Tape) if the `autograd` feature is enabled.
+/// `grad_fn` is executed on the `CPU` and receives `x`, the gradient of `x` and the gradient of the output.
+/// Without the `autograd` feature, `grad_fn` is ignored.
+///
+/// # Example
+#[cfg_attr(all(feature = "cpu", feature = "autograd"), doc = "```")]
+#[cfg_attr(not(all(feature = "cpu", feature = "autograd")), doc = "```ignore")]
+/// use custos::{exec_on_cpu::cpu_exec_unary_may_grad, Buffer, Device, CPU};
+///
+/// let device = CPU::new();
+/// let buf = Buffer::from((&device, [1., 2., 3.]));
+///
+/// let out = cpu_exec_unary_may_grad(
+///     &device,
+///     &buf,
+///     |cpu, x| {
+///         let mut out = cpu.retrieve(x.len(), ());
+///         for (out, x) in out.iter_mut().zip(x.iter()) {
+///             *out = x * x;
+///         }
+///         out
+///     },
+///     |_cpu, x, x_grad, out_grad| {
+///         for ((x_grad, x), out_grad) in x_grad.iter_mut().zip(x.iter()).zip(out_grad.iter()) {
+///             *x_grad += 2. * x * out_grad;
+///         }
+///     },
+/// )
+/// .unwrap();
+///
+/// assert_eq!(out.read(), [1., 4., 9.]);
+///
+/// out.backward();
+/// assert_eq!(buf.grad().read(), [2., 4., 6.]);
+/// ```
+pub fn cpu_exec_unary_may_grad<'a, T, D, F, G>(
+    device: &'a D,
+    x: &Buffer<T, D>,
+    f: F,
+    grad_fn: G,
+) -> crate::Result<Buffer<'a, T, D>>
+where
+    T: Clone + Default + 'static,
+    F: for<'b> Fn(&'b CPU, &Buffer<'_, T, CPU>) -> Buffer<'b, T, CPU>,
+    G: Fn(&CPU, &Buffer<T, CPU>, &mut Buffer<T, CPU>, &Buffer<T, CPU>) + 'static,
+    D: Read<T> + WriteBuf<T> + for<'c> Alloc<'c, T> + MayTapeReturn + 'static,
+{
+    let out = cpu_exec_unary(device, x, f)?;
+
+    #[cfg(feature = "autograd")]
+    {
+        let ids = (x.id(), out.id());
+        device.tape_mut().add_grad_fn(move |grads, device| {
+            let (x, x_grad, out_grad) = grads.get_double::<T, (), ()>(device, ids);
+
+            let cpu = CPU::new();
+            crate::to_cpu!(cpu, x, out_grad);
+            crate::to_cpu_mut!(cpu, x_grad, cpu_x_grad);
+
+            grad_fn(&cpu, &x, &mut cpu_x_grad, &out_grad);
+
+            device.write(x_grad, &cpu_x_grad);
+        });
+    }
+
+    #[cfg(not(feature = "autograd"))]
+    let _ = grad_fn;
+
+    Ok(out)
 }
 
 /// Moves a single `Buffer` stored on another device to a `CPU` `Buffer`s and executes an operation on the `CPU`.
@@ -118,8 +197,63 @@ where
     let cpu = CPU::new();
     let cpu_lhs = Buffer::<T, CPU>::from((&cpu, lhs.read_to_vec()));
     let cpu_rhs = Buffer::<T, CPU>::from((&cpu, rhs.read_to_vec()));
-    Buffer::from((device, f(&cpu, &cpu_lhs, &cpu_rhs)))
-    // TODO add new node to graph
+    let cpu_out = f(&cpu, &cpu_lhs, &cpu_rhs);
+    write_to_retrieved(device, &cpu_out, (lhs, rhs))
+}
+
+/// Same as [`cpu_exec_binary`], but additionally adds the gradient function `grad_fn` to the [`Tape`](crate::Tape) if the `autograd` feature is enabled.
+/// `grad_fn` is executed on the `CPU` and receives `lhs`, `rhs`, their gradients and the gradient of the output.
+/// Without the `autograd` feature, `grad_fn` is ignored.
+pub fn cpu_exec_binary_may_grad<'a, T, D, F, G>(
+    device: &'a D,
+    lhs: &Buffer<T, D>,
+    rhs: &Buffer<T, D>,
+    f: F,
+    grad_fn: G,
+) -> Buffer<'a, T, D>
+where
+    T: Clone + Default + 'static,
+    F: for<'b> Fn(&'b CPU, &Buffer<'_, T, CPU>, &Buffer<'_, T, CPU>) -> Buffer<'b, T, CPU>,
+    G: Fn(
+            &CPU,
+            &Buffer<T, CPU>,
+            &Buffer<T, CPU>,
+            &mut Buffer<T, CPU>,
+            &mut Buffer<T, CPU>,
+            &Buffer<T, CPU>,
+        ) + 'static,
+    D: Read<T> + WriteBuf<T> + for<'c> Alloc<'c, T> + MayTapeReturn + 'static,
+{
+    let out = cpu_exec_binary(device, lhs, rhs, f);
+
+    #[cfg(feature = "autograd")]
+    {
+        let ids = (lhs.id(), rhs.id(), out.id());
+        device.tape_mut().add_grad_fn(move |grads, device| {
+            let (lhs, rhs, lhs_grad, rhs_grad, out_grad) = grads.get_triple::<T, ()>(device, ids);
+
+            let cpu = CPU::new();
+            crate::to_cpu!(cpu, lhs, rhs, out_grad);
+            crate::to_cpu_mut!(cpu, lhs_grad, cpu_lhs_grad, rhs_grad, cpu_rhs_grad);
+
+            grad_fn(
+                &cpu,
+                &lhs,
+                &rhs,
+                &mut cpu_lhs_grad,
+                &mut cpu_rhs_grad,
+                &out_grad,
+            );
+
+            device.write(lhs_grad, &cpu_lhs_grad);
+            device.write(rhs_grad, &cpu_rhs_grad);
+        });
+    }
+
+    #[cfg(not(feature = "autograd"))]
+    let _ = grad_fn;
+
+    out
 }
 
 /// Inplace version of [cpu_exec_binary]
@@ -144,6 +278,21 @@ where
     Ok(())
 }
 
+/// Retrieves a `Buffer` from the cache of `device`, which is added to the graph as a child of `add_node`,
+/// and writes the result of a `CPU` operation to it.
+fn write_to_retrieved<'a, T, D>(
+    device: &'a D,
+    cpu_out: &Buffer<T, CPU>,
+    add_node: impl crate::AddGraph,
+) -> Buffer<'a, T, D>
+where
+    D: WriteBuf<T> + for<'c> Alloc<'c, T>,
+{
+    let mut out = device.retrieve(cpu_out.len(), add_node);
+    device.write(&mut out, cpu_out);
+    out
+}
+
 /// Moves `Buffer`s to `CPU` `Buffer`s.
 /// The name of the new `CPU` `Buffer`s are provided by the user.
 /// The new `Buffer`s are declared as mutable.
@@ -278,6 +427,81 @@ where
 
 #[cfg(test)]
 mod tests {
+    #[cfg(all(feature = "cpu", not(feature = "realloc")))]
+    #[test]
+    fn test_cpu_exec_binary_adds_node_and_reuses_cache() {
+        use crate::{exec_on_cpu::cpu_exec_binary, get_count, set_count, Buffer, Device, CPU};
+
+        let device = CPU::new();
+
+        let lhs = Buffer::from((&device, [1, 2, 3, 4]));
+        let rhs = Buffer::from((&device, [4, 3, 2, 1]));
+
+        fn add<'a>(
+            cpu: &'a CPU,
+            lhs: &Buffer<i32, CPU>,
+            rhs: &Buffer<i32, CPU>,
+        ) -> Buffer<'a, i32, CPU> {
+            let mut out = cpu.retrieve(lhs.len(), ());
+            for ((out, lhs), rhs) in out.iter_mut().zip(lhs.iter()).zip(rhs.iter()) {
+                *out = lhs + rhs;
+            }
+            out
+        }
+
+        let count = get_count();
+        let out = cpu_exec_binary(&device, &lhs, &rhs, add);
+        assert_eq!(out.read(), [5, 5, 5, 5]);
+
+        #[cfg(feature = "opt-cache")]
+        {
+            use crate::GraphReturn;
+
+            let node = *device.graph().nodes.last().unwrap();
+            assert_eq!(node.deps, [lhs.id().idx, rhs.id().idx]);
+        }
+
+        unsafe { set_count(count) };
+        let out2 = cpu_exec_binary(&device, &lhs, &rhs, add);
+        assert_eq!(out.ptr.ptr, out2.ptr.ptr);
+    }
+
+    #[cfg(all(feature = "cpu", feature = "autograd"))]
+    #[test]
+    fn test_cpu_exec_binary_may_grad() {
+        use crate::{exec_on_cpu::cpu_exec_binary_may_grad, Buffer, Device, CPU};
+
+        let device = CPU::new();
+
+        let lhs = Buffer::from((&device, [1., 2., 3.]));
+        let rhs = Buffer::from((&device, [4., 5., 6.]));
+
+        let out = cpu_exec_binary_may_grad(
+            &device,
+            &lhs,
+            &rhs,
+            |cpu, lhs, rhs| {
+                let mut out = cpu.retrieve(lhs.len(), ());
+                for ((out, lhs), rhs) in out.iter_mut().zip(lhs.iter()).zip(rhs.iter()) {
+                    *out = lhs * rhs;
+                }
+                out
+            },
+            |_cpu, lhs, rhs, lhs_grad, rhs_grad, out_grad| {
+                for i in 0..out_grad.len() {
+                    lhs_grad[i] += rhs[i] * out_grad[i];
+                    rhs_grad[i] += lhs[i] * out_grad[i];
+                }
+            },
+        );
+        assert_eq!(out.read(), [4., 10., 18.]);
+
+        out.backward();
+
+        assert_eq!(lhs.grad().read(), [4., 5., 6.]);
+        assert_eq!(rhs.grad().read(), [1., 2., 3.]);
+    }
+
     #[cfg(feature = "opencl")]
     #[test]
     fn test_to_cpu_macro() {
@@ -32,9 +32,7 @@ where
         });
 
         // convert host ptr / CPU buffer into a host ptr + OpenCL ptr buffer
-        return unsafe {
-            construct_buffer(device, no_drop, /*buf.node.idx*/ ())
-        };
+        return unsafe { construct_buffer(device, no_drop, x) };
     }
 
     #[cfg(feature = "realloc")]
@@ -47,7 +45,6 @@ where
             }),
         )));
     }
-    // TODO: add to graph?:     convert.node = device.graph().add(convert.len(), matrix.node.idx);
     cpu_exec_unary(device, x, f)
 }
 
@@ -108,9 +105,7 @@ where
         );
 
         // convert host ptr / CPU buffer into a host ptr + OpenCL ptr buffer
-        return unsafe {
-            construct_buffer(device, no_drop, /*buf.node.idx*/ ())
-        };
+        return unsafe { construct_buffer(device, no_drop, (lhs, rhs)) };
     }
 
     #[cfg(feature = "realloc")]
@@ -0,0 +1,176 @@
+//! Devices that implement [`CPUFallback`] execute [`ApplyFunction`], [`UnaryGrad`], [`UpdateFn`] and [`CopySlice`] on the [`CPU`].
+//! This allows writing a single codepath for all devices, even if a device does not implement an operation natively.
+
+use core::{
+    cell::RefCell,
+    ops::{Range, RangeBounds},
+};
+use std::{thread_local, vec::Vec};
+
+use super::{cpu_exec_binary_mut, cpu_exec_unary};
+use crate::{
+    bounds_to_range, optim::UpdateFn, Alloc, ApplyFunction, Buffer, CopySlice, Device, Eval,
+    MayToCLSource, Read, Resolve, UnaryGrad, WriteBuf, CPU,
+};
+
+/// Marks a device that executes the operations it does not implement natively on the [`CPU`].
+///
+/// A device implementing this trait gains implementations of [`ApplyFunction`], [`UnaryGrad`] (and thus [`UnaryElementWiseMayGrad`](crate::UnaryElementWiseMayGrad)), [`UpdateFn`] and [`CopySlice`].
+/// The `Buffer`s are read to the host, the operation is executed with a [`CPU`] and the result is written back (see [`exec_on_cpu`](crate::exec_on_cpu)).
+/// Hence, the device must not implement these operations itself.
+///
+/// Every operation that fell back to the `CPU` is recorded (see [`fallbacks`]).
+pub trait CPUFallback: Device {}
+
+/// An operation of a device that was executed on the [`CPU`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fallback {
+    /// The type name of the device.
+    pub device: &'static str,
+    /// The name of the operation.
+    pub op: &'static str,
+}
+
+thread_local! {
+    static FALLBACKS: RefCell<Vec<Fallback>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns all distinct operations that fell back to the [`CPU`] on the current thread, in order of their first occurrence.
+/// # Example
+/// ```
+/// use custos::exec_on_cpu::{clear_fallbacks, fallbacks};
+///
+/// clear_fallbacks();
+/// assert!(fallbacks().is_empty());
+/// ```
+pub fn fallbacks() -> Vec<Fallback> {
+    FALLBACKS.with(|fallbacks| fallbacks.borrow().clone())
+}
+
+/// Clears the recorded fallbacks of the current thread.
+pub fn clear_fallbacks() {
+    FALLBACKS.with(|fallbacks| fallbacks.borrow_mut().clear());
+}
+
+/// Records that the operation `op` of device `D` was executed on the [`CPU`].
+pub fn log_fallback<D>(op: &'static str) {
+    let fallback = Fallback {
+        device: core::any::type_name::<D>(),
+        op,
+    };
+
+    FALLBACKS.with(|fallbacks| {
+        let mut fallbacks = fallbacks.borrow_mut();
+        if !fallbacks.contains(&fallback) {
+            fallbacks.push(fallback);
+        }
+    });
+}
+
+impl<T, D> ApplyFunction<T> for D
+where
+    T: Clone + Default,
+    D: CPUFallback + Read<T> + WriteBuf<T> + for<'a> Alloc<'a, T>,
+    CPU: ApplyFunction<T>,
+{
+    fn apply_fn<F>(&self, buf: &Buffer<T, D>, f: impl Fn(Resolve<T>) -> F + Sync) -> Buffer<T, D>
+    where
+        F: Eval<T> + MayToCLSource,
+    {
+        log_fallback::<D>("apply_fn");
+        cpu_exec_unary(self, buf, |cpu, buf| cpu.apply_fn(buf, &f)).unwrap()
+    }
+}
+
+impl<T, D> UnaryGrad<T> for D
+where
+    T: Clone + Default,
+    D: CPUFallback + Read<T> + WriteBuf<T>,
+    CPU: UnaryGrad<T>,
+{
+    fn add_unary_grad<F>(
+        &self,
+        lhs: &Buffer<T, D>,
+        lhs_grad: &mut Buffer<T, D>,
+        out_grad: &Buffer<T, D>,
+        lhs_grad_fn: impl Fn(Resolve<T>) -> F + Sync,
+    ) where
+        F: Eval<T> + MayToCLSource,
+    {
+        log_fallback::<D>("add_unary_grad");
+
+        let device = self;
+        let cpu = CPU::new();
+        crate::cpu_exec_mut!(
+            device, cpu, lhs, out_grad WRITE_TO<lhs_grad, cpu_lhs_grad>
+            cpu.add_unary_grad(&lhs, &mut cpu_lhs_grad, &out_grad, lhs_grad_fn)
+        );
+    }
+}
+
+impl<T, D> UpdateFn<T> for D
+where
+    T: Clone + Default,
+    D: CPUFallback + Read<T> + WriteBuf<T>,
+    CPU: UpdateFn<T>,
+{
+    fn update_fn<F, const N: usize>(
+        &self,
+        buf: &mut Buffer<T, D>,
+        (lhs, rhs): (&Buffer<T, D>, &Buffer<T, D>),
+        scalars: [T; N],
+        f: impl Fn(Resolve<T>, Resolve<T>, Resolve<T>, [Resolve<T>; N]) -> F + Sync,
+    ) where
+        F: Eval<T> + MayToCLSource,
+    {
+        log_fallback::<D>("update_fn");
+
+        let device = self;
+        let cpu = CPU::new();
+        crate::cpu_exec_mut!(
+            device, cpu, lhs, rhs WRITE_TO<buf, cpu_buf>
+            cpu.update_fn(&mut cpu_buf, (&lhs, &rhs), scalars, f)
+        );
+    }
+}
+
+impl<T, D> CopySlice<T> for D
+where
+    T: Clone + Default,
+    D: CPUFallback + Read<T> + WriteBuf<T>,
+    CPU: CopySlice<T>,
+{
+    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, D>,
+        source_range: SR,
+        dest: &mut Buffer<T, D>,
+        dest_range: DR,
+    ) {
+        log_fallback::<D>("copy_slice_to");
+
+        let source_range = bounds_to_range(source_range, source.len());
+        let dest_range = bounds_to_range(dest_range, dest.len());
+
+        cpu_exec_binary_mut(self, dest, source, |cpu, dest, source| {
+            cpu.copy_slice_to(source, source_range.clone(), dest, dest_range.clone())
+        })
+        .unwrap();
+    }
+
+    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
+        &self,
+        source: &Buffer<T, D>,
+        dest: &mut Buffer<T, D>,
+        ranges: I,
+    ) {
+        log_fallback::<D>("copy_slice_all");
+
+        let ranges = ranges.into_iter().collect::<Vec<_>>();
+
+        cpu_exec_binary_mut(self, dest, source, |cpu, dest, source| {
+            cpu.copy_slice_all(source, dest, ranges.iter().cloned())
+        })
+        .unwrap();
+    }
+}
@@ -1,7 +1,10 @@
-use core::{hash::BuildHasherDefault, marker::PhantomData};
-use std::collections::{HashMap, HashSet};
+#[cfg(feature = "no-std")]
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+use core::marker::PhantomData;
+#[cfg(not(feature = "no-std"))]
+use std::collections::HashSet;
 
-use crate::{get_count, AddGraph, CacheTrace, GlobalCount, Ident, IdentHasher, Node, NodeIdx};
+use crate::{get_ident_idx, AddGraph, CacheTrace, GlobalCount, Ident, IdentMap, Node, NodeIdx};
 
 /// A graph of [`Node`]s.
 /// It is typically built up during the forward process. (calling `device.retrieve(.., (lhs, rhs))`)
@@ -10,14 +13,14 @@ pub struct Graph<IdxFrom: NodeIdx> {
     /// The nodes in the graph.
     pub nodes: Vec<Node>,
     /// Translates the index to a [`Node`] in the graph, to an index in the cache / global count.
-    pub idx_trans: HashMap<usize, usize, BuildHasherDefault<IdentHasher>>,
+    pub idx_trans: IdentMap<usize, usize>,
     _pd: PhantomData<IdxFrom>,
 }
 
 impl NodeIdx for GlobalCount {
     #[inline]
     fn idx(_nodes: &[Node]) -> usize {
-        get_count()
+        get_ident_idx()
     }
 }
 
@@ -32,7 +35,7 @@ impl<IdxFrom: NodeIdx> Graph<IdxFrom> {
     pub fn new() -> Self {
         Self {
             nodes: Vec::new(),
-            idx_trans: HashMap::default(),
+            idx_trans: IdentMap::default(),
             _pd: PhantomData,
         }
     }
@@ -77,7 +80,10 @@ impl<IdxFrom: NodeIdx> Graph<IdxFrom> {
     /// Unique meaning that no two [`CacheTrace`]s share some same [`Node`].
     pub fn cache_traces(&self) -> Vec<CacheTrace> {
         let mut traces = vec![];
+        #[cfg(not(feature = "no-std"))]
         let mut visited_nodes = HashSet::new();
+        #[cfg(feature = "no-std")]
+        let mut visited_nodes = BTreeSet::new();
 
         for node in self.nodes.iter().filter(|node| !node.is_leaf()) {
             if visited_nodes.contains(node) {
@@ -92,7 +98,7 @@ impl<IdxFrom: NodeIdx> Graph<IdxFrom> {
 
             traces.push(CacheTrace {
                 cache_id: Ident {
-                    idx: node.idx,
+                    idx: *self.idx_trans.get(&node.idx).unwrap(),
                     len: node.len,
                 },
                 use_cache_ids: trace
@@ -1,7 +1,9 @@
-#[cfg(not(feature = "no-std"))]
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
 use crate::Ident;
+#[cfg(all(feature = "no-std", feature = "alloc"))]
+use alloc::vec::Vec;
 
-use core::cell::{Ref, RefMut};
+use crate::{SharedRef, SharedRefMut};
 
 #[cfg(feature = "opt-cache")]
 use crate::{CacheReturn, DeviceError};
@@ -12,10 +14,10 @@ pub use node::*;
 mod add_graph;
 mod node;
 
-#[cfg(not(feature = "no-std"))]
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
 mod graph_struct;
 
-#[cfg(not(feature = "no-std"))]
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
 pub use graph_struct::*;
 
 /// Returns the next index for a [`Node`].
@@ -31,24 +33,24 @@ pub trait NodeIdx {
 #[derive(Debug, Default)]
 pub struct GlobalCount;
 
-#[cfg(feature = "no-std")]
+#[cfg(all(feature = "no-std", not(feature = "alloc")))]
 impl NodeIdx for GlobalCount {}
 
 /// A dummy graph for no-std.
-#[cfg(feature = "no-std")]
+#[cfg(all(feature = "no-std", not(feature = "alloc")))]
 pub struct Graph<IdxFrom: NodeIdx> {
     _p: core::marker::PhantomData<IdxFrom>,
 }
 
-#[cfg(feature = "no-std")]
+#[cfg(all(feature = "no-std", not(feature = "alloc")))]
 impl<IdxFrom: NodeIdx> Graph<IdxFrom> {
-    /// This function will panic. Disable the `no-std` feature to use this function.
+    /// This function will panic. Disable the `no-std` feature or enable the `alloc` feature to use this function.
     #[inline]
     pub fn add_leaf(&mut self, _len: usize) -> Node {
         unimplemented!("Not available in no-std mode")
     }
 
-    /// This function will panic. Disable the `no-std` feature to use this function.
+    /// This function will panic. Disable the `no-std` feature or enable the `alloc` feature to use this function.
     #[inline]
     pub fn add_node(&mut self, _len: usize, _lhs_idx: usize, _rhs_idx: usize) -> Node {
         unimplemented!("Not available in no-std mode")
@@ -56,7 +58,7 @@ impl<IdxFrom: NodeIdx> Graph<IdxFrom> {
 }
 
 /// A `CacheTrace` is a list of nodes that shows which [`Buffer`](crate::Buffer)s could use the same cache.
-#[cfg(not(feature = "no-std"))]
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CacheTrace {
     /// This identifier is the common cache index / ident. All the other idents in `use_cache_ids` can use this ident to share memory.
@@ -68,9 +70,9 @@ pub struct CacheTrace {
 /// Returns a mutable reference to the graph.
 pub trait GraphReturn<IdxFrom: NodeIdx = GlobalCount> {
     /// Returns a reference to [`Graph`].
-    fn graph(&self) -> Ref<Graph<IdxFrom>>;
+    fn graph(&self) -> SharedRef<Graph<IdxFrom>>;
     /// Returns a mutable reference to [`Graph`].
-    fn graph_mut(&self) -> RefMut<Graph<IdxFrom>>;
+    fn graph_mut(&self) -> SharedRefMut<Graph<IdxFrom>>;
 }
 
 /// Optimizes [`Graph`] and [`Cache`](crate::Cache) to achive a lower memory footprint.
@@ -446,6 +448,7 @@ mod tests {
         use crate::{Buffer, Device, GraphReturn, CPU};
 
         let device = CPU::new();
+        let offset = crate::thread_ident_offset();
 
         let w1 = Buffer::from((&device, [1; 10 * 64]));
         let b1 = Buffer::from((&device, [1; 64]));
@@ -480,25 +483,61 @@ mod tests {
             cts,
             [
                 CacheTrace {
-                    cache_id: Ident { idx: 10, len: 6400 },
+                    cache_id: Ident {
+                        idx: offset + 10,
+                        len: 6400
+                    },
                     use_cache_ids: vec![
                         //   Ident { idx: 0, len: 6400 },
-                        Ident { idx: 11, len: 6400 },
-                        Ident { idx: 12, len: 6400 },
-                        Ident { idx: 13, len: 6400 },
-                        Ident { idx: 14, len: 6400 },
-                        Ident { idx: 15, len: 6400 },
-                        Ident { idx: 16, len: 6400 },
-                        Ident { idx: 17, len: 6400 },
-                        Ident { idx: 18, len: 6400 }
+                        Ident {
+                            idx: offset + 11,
+                            len: 6400
+                        },
+                        Ident {
+                            idx: offset + 12,
+                            len: 6400
+                        },
+                        Ident {
+                            idx: offset + 13,
+                            len: 6400
+                        },
+                        Ident {
+                            idx: offset + 14,
+                            len: 6400
+                        },
+                        Ident {
+                            idx: offset + 15,
+                            len: 6400
+                        },
+                        Ident {
+                            idx: offset + 16,
+                            len: 6400
+                        },
+                        Ident {
+                            idx: offset + 17,
+                            len: 6400
+                        },
+                        Ident {
+                            idx: offset + 18,
+                            len: 6400
+                        }
                     ]
                 },
                 CacheTrace {
-                    cache_id: Ident { idx: 19, len: 100 },
+                    cache_id: Ident {
+                        idx: offset + 19,
+                        len: 100
+                    },
                     use_cache_ids: vec![
                         //   Ident { idx: 0, len: 6400 },
-                        Ident { idx: 20, len: 100 },
-                        Ident { idx: 21, len: 100 },
+                        Ident {
+                            idx: offset + 20,
+                            len: 100
+                        },
+                        Ident {
+                            idx: offset + 21,
+                            len: 100
+                        },
                     ]
                 }
             ]
@@ -512,6 +551,7 @@ mod tests {
         use crate::{Buffer, Device, GraphReturn, CPU};
 
         let device = CPU::new();
+        let offset = crate::thread_ident_offset();
 
         // idx: 0, deps: []
         let x: Buffer = device.buffer([1.; 1000]);
@@ -534,12 +574,30 @@ mod tests {
             traces,
             vec![
                 CacheTrace {
-                    cache_id: Ident { idx: 2, len: 1000 },
-                    use_cache_ids: vec![Ident { idx: 5, len: 1000 }, Ident { idx: 6, len: 1000 },]
+                    cache_id: Ident {
+                        idx: offset + 2,
+                        len: 1000
+                    },
+                    use_cache_ids: vec![
+                        Ident {
+                            idx: offset + 5,
+                            len: 1000
+                        },
+                        Ident {
+                            idx: offset + 6,
+                            len: 1000
+                        },
+                    ]
                 },
                 CacheTrace {
-                    cache_id: Ident { idx: 3, len: 1000 },
-                    use_cache_ids: vec![Ident { idx: 4, len: 1000 },]
+                    cache_id: Ident {
+                        idx: offset + 3,
+                        len: 1000
+                    },
+                    use_cache_ids: vec![Ident {
+                        idx: offset + 4,
+                        len: 1000
+                    },]
                 }
             ]
         );
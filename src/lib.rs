@@ -53,6 +53,9 @@
 //! [tests]: https://github.com/elftausend/custos/tree/main/tests
 use core::ffi::c_void;
 
+#[cfg(any(not(feature = "no-std"), feature = "alloc"))]
+extern crate alloc;
+
 //pub use libs::*;
 pub use buffer::*;
 pub use count::*;
@@ -77,6 +80,15 @@ pub use devices::wgpu::WGPU;
 #[cfg(feature = "stack")]
 pub use devices::stack::Stack;
 
+#[cfg(feature = "alloc")]
+pub use devices::heap::Heap;
+
+#[cfg(feature = "arena")]
+pub use devices::arena::BumpArena;
+
+#[cfg(all(feature = "mock", not(feature = "no-std")))]
+pub use devices::mock::{Mock, MockCall};
+
 #[cfg(feature = "network")]
 pub use devices::network::Network;
 
@@ -99,6 +111,8 @@ pub mod flag;
 mod graph;
 mod op_traits;
 mod shape;
+#[cfg(not(feature = "no-std"))]
+mod transfer;
 mod two_way_ops;
 mod unary;
 
@@ -108,8 +122,14 @@ pub mod static_api;
 #[cfg(feature = "autograd")]
 pub mod autograd;
 pub mod number;
+pub mod optim;
+pub mod random;
+#[cfg(not(feature = "no-std"))]
+pub mod serialize;
 pub use op_traits::*;
 pub use shape::*;
+#[cfg(not(feature = "no-std"))]
+pub use transfer::*;
 pub use two_way_ops::*;
 
 #[cfg(feature = "autograd")]
@@ -224,7 +244,7 @@ pub trait Device: Sized + 'static {
 
     /// Removes a `Buffer` with the provided [`Ident`] from the cache.
     /// This function is internally called when a `Buffer` with [`AllocFlag`] `None` is dropped.
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     #[inline]
     fn remove(&self, ident: Ident) {
         Self::Cache::remove(self, ident);
@@ -232,7 +252,7 @@ pub trait Device: Sized + 'static {
 
     /// Adds a pointer that was allocated by [`Alloc`] to the cache and returns a new corresponding [`Ident`].
     /// This function is internally called when a `Buffer` with [`AllocFlag`] `None` is created.
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     #[inline]
     fn add_to_cache<T, S: Shape>(&self, ptr: &Self::Ptr<T, S>) -> Option<Ident> {
         Self::Cache::add_to_cache(self, ptr)
@@ -391,7 +411,7 @@ pub mod prelude {
     #[cfg(feature = "cpu")]
     pub use crate::{exec_on_cpu::*, CPU};
 
-    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(not(feature = "no-std"), feature = "alloc"))]
     pub use crate::{cache::CacheReturn, get_count, set_count, Cache};
 
     #[cfg(feature = "opencl")]
@@ -405,6 +425,15 @@ pub mod prelude {
     #[cfg(feature = "stack")]
     pub use crate::stack::Stack;
 
+    #[cfg(feature = "alloc")]
+    pub use crate::heap::Heap;
+
+    #[cfg(feature = "arena")]
+    pub use crate::arena::BumpArena;
+
+    #[cfg(all(feature = "mock", not(feature = "no-std")))]
+    pub use crate::mock::Mock;
+
     #[cfg(feature = "network")]
     pub use crate::network::{Network, NetworkArray};
 
@@ -8,6 +8,12 @@ use core::{
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
 };
 
+mod complex;
+#[cfg(feature = "half")]
+mod half_float;
+
+pub use complex::Complex;
+
 /// A trait that returns the default / zero of a value.
 pub trait Zero {
     /// Returns zero or the default.
@@ -89,10 +95,91 @@ typical_number_impl! {
     isize, u8, u16, u32, u64, u128, usize
 }
 
+/// Converts a value to another primitive type, like the `as` keyword does.
+/// Numbers are converted to `bool` by comparing them with zero.
+/// # Example
+/// ```
+/// use custos::number::CastAs;
+///
+/// let x: i32 = 3.7f32.cast_as();
+/// assert_eq!(x, 3);
+///
+/// let mask: bool = 0.5f32.cast_as();
+/// assert!(mask);
+/// ```
+pub trait CastAs<U> {
+    /// Converts `self` to `U`.
+    fn cast_as(self) -> U;
+}
+
+macro_rules! cast_as_impl {
+    ($($t:ident),*) => {
+        cast_as_impl!(@from [$($t),*] [$($t),*]);
+
+        $(
+            impl CastAs<$t> for bool {
+                #[inline]
+                #[allow(clippy::unnecessary_cast)]
+                fn cast_as(self) -> $t {
+                    self as u8 as $t
+                }
+            }
+
+            impl CastAs<bool> for $t {
+                #[inline]
+                fn cast_as(self) -> bool {
+                    self != 0 as $t
+                }
+            }
+        )*
+    };
+    (@from [$($from:ident),*] $to:tt) => {
+        $(
+            cast_as_impl!(@to $from $to);
+        )*
+    };
+    (@to $from:ident [$($to:ident),*]) => {
+        $(
+            impl CastAs<$to> for $from {
+                #[inline]
+                #[allow(clippy::unnecessary_cast)]
+                fn cast_as(self) -> $to {
+                    self as $to
+                }
+            }
+        )*
+    };
+}
+
+cast_as_impl! {
+    f32, f64, i8, i16, i32, i64, i128,
+    isize, u8, u16, u32, u64, u128, usize
+}
+
+impl CastAs<bool> for bool {
+    #[inline]
+    fn cast_as(self) -> bool {
+        self
+    }
+}
+
 /// Numeric is a trait that is implemented for all numeric types.
 pub trait Numeric:
     Sized + Default + Copy + PartialOrd + PartialEq + core::fmt::Debug + core::fmt::Display
 {
+    /// Returns the C vector type (e.g. `float2`), if `Self` is a complex number.
+    /// Generated kernel source code uses this to emit complex multiplications and divisions.
+    #[inline]
+    fn complex_c_type() -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the number as a literal for generated kernel source code.
+    #[cfg(not(feature = "no-std"))]
+    #[inline]
+    fn to_cl_literal(&self) -> String {
+        self.to_string()
+    }
 }
 
 impl Numeric for bool {}
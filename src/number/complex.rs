@@ -0,0 +1,388 @@
+use core::{
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
+};
+
+use crate::CDatatype;
+
+use super::{Float, Number, Numeric, One, Two};
+
+/// A complex number in cartesian form.
+/// The memory layout matches the vector types `float2` / `double2` of OpenCL and CUDA.
+/// # Example
+/// ```
+/// use custos::number::Complex;
+///
+/// let a = Complex::new(1., 2.);
+/// let b = Complex::new(3., -1.);
+///
+/// assert_eq!(a * b, Complex::new(5., 5.));
+/// ```
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Complex<T> {
+    /// The real part.
+    pub re: T,
+    /// The imaginary part.
+    pub im: T,
+}
+
+impl<T> Complex<T> {
+    /// Creates a new complex number.
+    #[inline]
+    pub const fn new(re: T, im: T) -> Self {
+        Complex { re, im }
+    }
+
+    /// Reinterprets an interleaved slice `[re0, im0, re1, im1, ..]` as a slice of complex numbers.
+    /// Panics if the length of the slice is odd.
+    /// # Example
+    /// ```
+    /// use custos::number::Complex;
+    ///
+    /// let complex = Complex::from_interleaved(&[1., 2., 3., 4.]);
+    /// assert_eq!(complex, [Complex::new(1., 2.), Complex::new(3., 4.)]);
+    /// ```
+    #[inline]
+    pub fn from_interleaved(slice: &[T]) -> &[Complex<T>] {
+        assert_eq!(
+            slice.len() % 2,
+            0,
+            "An interleaved slice must have an even length."
+        );
+        // Safety: Complex<T> is repr(C) and consists of two T's
+        unsafe { core::slice::from_raw_parts(slice.as_ptr().cast(), slice.len() / 2) }
+    }
+
+    /// Reinterprets a mutable interleaved slice `[re0, im0, re1, im1, ..]` as a slice of complex numbers.
+    /// Panics if the length of the slice is odd.
+    #[inline]
+    pub fn from_interleaved_mut(slice: &mut [T]) -> &mut [Complex<T>] {
+        assert_eq!(
+            slice.len() % 2,
+            0,
+            "An interleaved slice must have an even length."
+        );
+        // Safety: Complex<T> is repr(C) and consists of two T's
+        unsafe { core::slice::from_raw_parts_mut(slice.as_mut_ptr().cast(), slice.len() / 2) }
+    }
+
+    /// Reinterprets a slice of complex numbers as an interleaved slice `[re0, im0, re1, im1, ..]`.
+    /// # Example
+    /// ```
+    /// use custos::number::Complex;
+    ///
+    /// let complex = [Complex::new(1., 2.), Complex::new(3., 4.)];
+    /// assert_eq!(Complex::as_interleaved(&complex), [1., 2., 3., 4.]);
+    /// ```
+    #[inline]
+    pub fn as_interleaved(slice: &[Complex<T>]) -> &[T] {
+        // Safety: Complex<T> is repr(C) and consists of two T's
+        unsafe { core::slice::from_raw_parts(slice.as_ptr().cast(), slice.len() * 2) }
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Complex<T> {
+    /// Returns the complex conjugate.
+    #[inline]
+    pub fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+}
+
+impl<T: Number> Complex<T> {
+    /// Returns the squared absolute value.
+    #[inline]
+    pub fn norm_sqr(self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+}
+
+impl<T: Float> Complex<T> {
+    /// Returns `e` raised to the power of `self`.
+    #[inline]
+    pub fn exp(self) -> Self {
+        let r = self.re.exp();
+        Complex::new(r * self.im.cos(), r * self.im.sin())
+    }
+
+    /// Returns the sine of `self`.
+    #[inline]
+    pub fn sin(self) -> Self {
+        let (cosh, sinh) = cosh_sinh(self.im);
+        Complex::new(self.re.sin() * cosh, self.re.cos() * sinh)
+    }
+
+    /// Returns the cosine of `self`.
+    #[inline]
+    pub fn cos(self) -> Self {
+        let (cosh, sinh) = cosh_sinh(self.im);
+        Complex::new(self.re.cos() * cosh, -(self.re.sin() * sinh))
+    }
+
+    /// Returns the tangent of `self`.
+    #[inline]
+    pub fn tan(self) -> Self {
+        self.sin() / self.cos()
+    }
+}
+
+#[inline]
+fn cosh_sinh<T: Float>(x: T) -> (T, T) {
+    let (exp, exp_neg) = (x.exp(), (-x).exp());
+    ((exp + exp_neg) / T::two(), (exp - exp_neg) / T::two())
+}
+
+impl<T: Number> Add for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl<T: Number> Sub for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl<T: Number> Mul for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Complex::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl<T: Number> Div for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self {
+        let denom = rhs.norm_sqr();
+        Complex::new(
+            (self.re * rhs.re + self.im * rhs.im) / denom,
+            (self.im * rhs.re - self.re * rhs.im) / denom,
+        )
+    }
+}
+
+/// The remainder of the truncated (component-wise) complex division.
+impl<T: Number> Rem for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn rem(self, rhs: Self) -> Self {
+        let quot = self / rhs;
+        let one = T::one();
+        let trunc = Complex::new(quot.re - quot.re % one, quot.im - quot.im % one);
+        self - rhs * trunc
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Neg for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+macro_rules! complex_ref_and_assign_ops {
+    ($($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident),*) => {
+        $(
+            impl<'a, T: Number> $op<&'a Complex<T>> for Complex<T> {
+                type Output = Self;
+
+                #[inline]
+                fn $fn(self, rhs: &'a Complex<T>) -> Self {
+                    $op::$fn(self, *rhs)
+                }
+            }
+
+            impl<T: Number> $op_assign for Complex<T> {
+                #[inline]
+                fn $fn_assign(&mut self, rhs: Self) {
+                    *self = $op::$fn(*self, rhs);
+                }
+            }
+        )*
+    };
+}
+
+complex_ref_and_assign_ops! {
+    Add, add, AddAssign, add_assign,
+    Sub, sub, SubAssign, sub_assign,
+    Mul, mul, MulAssign, mul_assign,
+    Div, div, DivAssign, div_assign,
+    Rem, rem, RemAssign, rem_assign
+}
+
+impl<T: Number> Sum for Complex<T> {
+    #[inline]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Complex::default(), |acc, x| acc + x)
+    }
+}
+
+impl<T: Number> One for Complex<T> {
+    #[inline]
+    fn one() -> Self {
+        Complex::new(T::one(), T::zero())
+    }
+}
+
+impl<T: Number> Two for Complex<T> {
+    #[inline]
+    fn two() -> Self {
+        Complex::new(T::two(), T::zero())
+    }
+}
+
+impl<T: Number> Display for Complex<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.im < T::zero() {
+            write!(f, "{}{}i", self.re, self.im)
+        } else {
+            write!(f, "{}+{}i", self.re, self.im)
+        }
+    }
+}
+
+macro_rules! complex_impl {
+    ($($t:ident: $c_type:literal),*) => {
+        $(
+            impl Numeric for Complex<$t> {
+                #[inline]
+                fn complex_c_type() -> Option<&'static str> {
+                    Some($c_type)
+                }
+
+                #[cfg(not(feature = "no-std"))]
+                #[inline]
+                fn to_cl_literal(&self) -> String {
+                    format!("{}_new({}, {})", $c_type, self.re.to_cl_literal(), self.im.to_cl_literal())
+                }
+            }
+
+            impl Number for Complex<$t> {
+                #[inline]
+                fn from_usize(value: usize) -> Self {
+                    Complex::new(value as $t, 0.)
+                }
+
+                #[inline]
+                fn from_u64(value: u64) -> Self {
+                    Complex::new(value as $t, 0.)
+                }
+
+                #[inline]
+                fn as_usize(&self) -> usize {
+                    self.re as usize
+                }
+
+                #[inline]
+                fn as_f64(&self) -> f64 {
+                    self.re as f64
+                }
+
+                #[inline]
+                fn max(self, rhs: Self) -> Self {
+                    if self > rhs {
+                        self
+                    } else {
+                        rhs
+                    }
+                }
+            }
+
+            impl CDatatype for Complex<$t> {
+                #[inline]
+                fn as_c_type_str() -> &'static str {
+                    $c_type
+                }
+            }
+        )*
+    };
+}
+
+complex_impl!(f32: "float2", f64: "double2");
+
+#[cfg(test)]
+mod tests {
+    use super::Complex;
+
+    #[test]
+    fn test_complex_arithmetic() {
+        let a = Complex::new(1f32, 2.);
+        let b = Complex::new(3f32, -1.);
+
+        assert_eq!(a + b, Complex::new(4., 1.));
+        assert_eq!(a - b, Complex::new(-2., 3.));
+        assert_eq!(a * b, Complex::new(5., 5.));
+        assert_eq!((a * b) / b, a);
+        assert_eq!(-a, Complex::new(-1., -2.));
+        assert_eq!(a.conj(), Complex::new(1., -2.));
+    }
+
+    #[test]
+    fn test_complex_interleaved() {
+        let mut data = [1f64, 2., 3., 4.];
+
+        let complex = Complex::from_interleaved_mut(&mut data);
+        complex[1] *= Complex::new(0., 1.);
+
+        assert_eq!(data, [1., 2., -4., 3.]);
+        assert_eq!(
+            Complex::as_interleaved(Complex::from_interleaved(&data)),
+            data
+        );
+    }
+
+    #[cfg(not(feature = "no-std"))]
+    #[test]
+    fn test_complex_elementary_functions() {
+        let close = |lhs: Complex<f64>, rhs: Complex<f64>| {
+            assert!(
+                (lhs.re - rhs.re).abs() < 1e-12 && (lhs.im - rhs.im).abs() < 1e-12,
+                "{lhs} != {rhs}"
+            )
+        };
+        let a = Complex::new(0.5f64, -1.5);
+
+        close(
+            Complex::new(0., core::f64::consts::PI).exp(),
+            Complex::new(-1., 0.),
+        );
+        close(
+            a.sin(),
+            Complex::new(
+                0.5f64.sin() * 1.5f64.cosh(),
+                -(0.5f64.cos() * 1.5f64.sinh()),
+            ),
+        );
+        close(
+            a.cos(),
+            Complex::new(0.5f64.cos() * 1.5f64.cosh(), 0.5f64.sin() * 1.5f64.sinh()),
+        );
+        close(a.tan() * a.cos(), a.sin());
+    }
+
+    #[cfg(not(feature = "no-std"))]
+    #[test]
+    fn test_complex_display() {
+        assert_eq!(Complex::new(1f32, -2.).to_string(), "1-2i");
+        assert_eq!(Complex::new(1f32, 2.).to_string(), "1+2i");
+    }
+}
@@ -0,0 +1,205 @@
+//! [`Number`] and [`Float`] implementations for the half precision types of the `half` crate.
+//! The math functions are evaluated in `f32`.
+
+use half::{bf16, f16};
+
+use super::{CastAs, Float, Number, Numeric, One, Two};
+
+macro_rules! half_impl {
+    ($($t:ident),*) => {
+        $(
+            impl Numeric for $t {}
+
+            impl One for $t {
+                #[inline]
+                fn one() -> $t {
+                    $t::ONE
+                }
+            }
+
+            impl Two for $t {
+                #[inline]
+                fn two() -> $t {
+                    $t::from_f32(2.)
+                }
+            }
+
+            impl Number for $t {
+                #[inline]
+                fn from_usize(value: usize) -> $t {
+                    $t::from_f32(value as f32)
+                }
+
+                #[inline]
+                fn from_u64(value: u64) -> $t {
+                    $t::from_f32(value as f32)
+                }
+
+                #[inline]
+                fn as_usize(&self) -> usize {
+                    self.to_f32() as usize
+                }
+
+                #[inline]
+                fn as_f64(&self) -> f64 {
+                    self.to_f64()
+                }
+
+                #[inline]
+                fn max(self, rhs: Self) -> Self {
+                    if self > rhs {
+                        self
+                    } else {
+                        rhs
+                    }
+                }
+            }
+
+            impl Float for $t {
+                #[inline]
+                fn exp(&self) -> $t {
+                    $t::from_f32(Float::exp(&self.to_f32()))
+                }
+
+                #[inline]
+                fn powf(&self, rhs: $t) -> $t {
+                    $t::from_f32(Float::powf(&self.to_f32(), rhs.to_f32()))
+                }
+
+                #[inline]
+                fn powi(&self, rhs: i32) -> $t {
+                    $t::from_f32(Float::powi(&self.to_f32(), rhs))
+                }
+
+                #[inline]
+                fn tanh(&self) -> $t {
+                    $t::from_f32(Float::tanh(&self.to_f32()))
+                }
+
+                #[inline]
+                fn sin(&self) -> $t {
+                    $t::from_f32(Float::sin(&self.to_f32()))
+                }
+
+                #[inline]
+                fn cos(&self) -> $t {
+                    $t::from_f32(Float::cos(&self.to_f32()))
+                }
+
+                #[inline]
+                fn tan(&self) -> $t {
+                    $t::from_f32(Float::tan(&self.to_f32()))
+                }
+
+                #[inline]
+                fn as_generic(value: f64) -> $t {
+                    $t::from_f64(value)
+                }
+
+                #[inline]
+                fn sqrt(&self) -> $t {
+                    $t::from_f32(Float::sqrt(&self.to_f32()))
+                }
+
+                #[inline]
+                fn log(&self, base: $t) -> $t {
+                    $t::from_f32(Float::log(&self.to_f32(), base.to_f32()))
+                }
+
+                #[inline]
+                fn ln(&self) -> $t {
+                    $t::from_f32(Float::ln(&self.to_f32()))
+                }
+
+                #[inline]
+                fn abs(&self) -> $t {
+                    $t::from_bits(self.to_bits() & 0x7fff)
+                }
+            }
+        )*
+    };
+}
+
+half_impl!(f16, bf16);
+
+macro_rules! half_cast_as_impl {
+    ($half:ident: $($t:ident),*) => {
+        $(
+            impl CastAs<$t> for $half {
+                #[inline]
+                fn cast_as(self) -> $t {
+                    self.to_f32() as $t
+                }
+            }
+
+            impl CastAs<$half> for $t {
+                #[inline]
+                fn cast_as(self) -> $half {
+                    $half::from_f32(self as f32)
+                }
+            }
+        )*
+
+        impl CastAs<bool> for $half {
+            #[inline]
+            fn cast_as(self) -> bool {
+                self.to_f32() != 0.
+            }
+        }
+
+        impl CastAs<$half> for bool {
+            #[inline]
+            fn cast_as(self) -> $half {
+                $half::from_f32(self as u8 as f32)
+            }
+        }
+
+        impl CastAs<$half> for $half {
+            #[inline]
+            fn cast_as(self) -> $half {
+                self
+            }
+        }
+    };
+}
+
+half_cast_as_impl!(f16: f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+half_cast_as_impl!(bf16: f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl CastAs<bf16> for f16 {
+    #[inline]
+    fn cast_as(self) -> bf16 {
+        bf16::from_f32(self.to_f32())
+    }
+}
+
+impl CastAs<f16> for bf16 {
+    #[inline]
+    fn cast_as(self) -> f16 {
+        f16::from_f32(self.to_f32())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use half::{bf16, f16};
+
+    use crate::number::{Float, Number, Two};
+
+    #[test]
+    fn test_f16_number() {
+        let x = f16::from_f32(1.5);
+        assert_eq!(x + f16::two(), f16::from_f32(3.5));
+        assert_eq!(f16::from_usize(3), f16::from_f32(3.));
+        assert_eq!(Float::abs(&f16::from_f32(-2.)), f16::from_f32(2.));
+        assert_eq!(Float::sqrt(&f16::from_f32(4.)), f16::from_f32(2.));
+    }
+
+    #[test]
+    fn test_bf16_number() {
+        let x = bf16::from_f32(-1.5);
+        assert_eq!(Float::abs(&x), bf16::from_f32(1.5));
+        assert_eq!(x.as_f64(), -1.5);
+        assert_eq!(Float::powi(&bf16::from_f32(3.), 2), bf16::from_f32(9.));
+    }
+}
@@ -1,6 +1,6 @@
 use core::ops::{Bound, Range, RangeBounds};
 
-use crate::{shape::Shape, Alloc, Buffer, Device};
+use crate::{random::Distribution, shape::Shape, Alloc, Buffer, Device};
 
 /// Trait for implementing the clear() operation for the compute devices.
 pub trait ClearBuf<T, S: Shape = (), D: Device = Self> {
@@ -172,6 +172,47 @@ pub trait WriteBuf<T, S: Shape = (), D: Device = Self>: Device {
     fn write_buf(&self, dst: &mut Buffer<T, D, S>, src: &Buffer<T, D, S>);
 }
 
+/// Trait for reading buffers without blocking the host.
+/// The returned [`Transfer`](crate::Transfer) completes once the data is available on the host.
+#[cfg(not(feature = "no-std"))]
+pub trait ReadAsync<T, S: Shape = (), D: Device = Self>: Device {
+    /// Starts reading the data of the `Buffer` into a vector.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, ReadAsync};
+    ///
+    /// let device = CPU::new();
+    /// let a = Buffer::from((&device, [1., 2., 3., 3., 2., 1.,]));
+    /// let read = device.read_async(&a);
+    /// assert_eq!(read.wait().unwrap(), [1., 2., 3., 3., 2., 1.,]);
+    /// ```
+    fn read_async<'a>(&'a self, buf: &'a Buffer<T, D, S>) -> crate::Transfer<'a, Vec<T>>;
+}
+
+/// Trait for writing data to buffers without blocking the host.
+/// The returned [`Transfer`](crate::Transfer) completes once the data was written to the buffer.
+#[cfg(not(feature = "no-std"))]
+pub trait WriteAsync<T, S: Shape = (), D: Device = Self>: Device {
+    /// Starts writing `data` to the buffer.
+    /// The buffer is borrowed until the transfer is complete.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, WriteAsync};
+    ///
+    /// let device = CPU::new();
+    /// let mut buf: Buffer<i32> = Buffer::new(&device, 4);
+    /// device.write_async(&mut buf, &[9, 3, 2, -4]).wait().unwrap();
+    /// assert_eq!(buf.as_slice(), &[9, 3, 2, -4])
+    /// ```
+    fn write_async<'a>(
+        &'a self,
+        buf: &'a mut Buffer<T, D, S>,
+        data: &'a [T],
+    ) -> crate::Transfer<'a, ()>;
+}
+
 /// This trait is used to clone a buffer based on a specific device type.
 pub trait CloneBuf<'a, T, S: Shape = ()>: Sized + Device {
     /// Creates a deep copy of the specified buffer.
@@ -190,6 +231,111 @@ pub trait CloneBuf<'a, T, S: Shape = ()>: Sized + Device {
     fn clone_buf(&'a self, buf: &Buffer<'a, T, Self, S>) -> Buffer<'a, T, Self, S>;
 }
 
+/// Trait for casting the elements of a `Buffer` to another type.
+/// Masks produced by comparison [`Combiner`](crate::Combiner)s can be cast to and from `bool`.
+pub trait CastBuf<T, U, S: Shape = (), D: Device = Self>: Device {
+    /// Casts every element of the `Buffer` from `T` to `U`, like the `as` keyword does.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, CastBuf};
+    ///
+    /// let device = CPU::new();
+    /// let buf = Buffer::from((&device, [1, -2, 3, 0]));
+    ///
+    /// let casted: Buffer<f32> = device.cast(&buf);
+    /// assert_eq!(casted.read(), [1., -2., 3., 0.]);
+    ///
+    /// let mask: Buffer<bool> = device.cast(&buf);
+    /// assert_eq!(mask.read(), [true, true, true, false]);
+    /// ```
+    fn cast(&self, buf: &Buffer<T, D, S>) -> Buffer<U, Self, S>;
+}
+
+/// Trait for filling a `Buffer` on the device.
+/// It is used by the constructors [`Buffer::full`], [`Buffer::ones`], [`Buffer::arange`], [`Buffer::linspace`] and [`Buffer::eye`].
+pub trait FillBuf<T, S: Shape = (), D: Device = Self>: Device {
+    /// Sets all elements of the `Buffer` to `value`.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, FillBuf};
+    ///
+    /// let device = CPU::new();
+    /// let mut buf = Buffer::<i32>::new(&device, 4);
+    /// device.fill(&mut buf, 3);
+    ///
+    /// assert_eq!(buf.read(), [3; 4]);
+    /// ```
+    fn fill(&self, buf: &mut Buffer<T, D, S>, value: T);
+
+    /// Sets the i-th element of the `Buffer` to `start + i * step`.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, FillBuf};
+    ///
+    /// let device = CPU::new();
+    /// let mut buf = Buffer::<i32>::new(&device, 4);
+    /// device.fill_range(&mut buf, 1, 2);
+    ///
+    /// assert_eq!(buf.read(), [1, 3, 5, 7]);
+    /// ```
+    fn fill_range(&self, buf: &mut Buffer<T, D, S>, start: T, step: T);
+
+    /// Sets the elements on the diagonal of a row-major matrix with `cols` columns to one and all other elements to zero.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, FillBuf};
+    ///
+    /// let device = CPU::new();
+    /// let mut buf = Buffer::<i32>::new(&device, 6);
+    /// device.fill_eye(&mut buf, 3);
+    ///
+    /// assert_eq!(buf.read(), [1, 0, 0, 0, 1, 0]);
+    /// ```
+    fn fill_eye(&self, buf: &mut Buffer<T, D, S>, cols: usize);
+}
+
+/// Trait for filling a `Buffer` with random values.
+/// The values only depend on the seed and the position of an element, hence every device produces the same values (see [`random`](crate::random)).
+pub trait RandBuf<T, S: Shape = (), D: Device = Self>: Device {
+    /// Fills the `Buffer` with values sampled from the given [`Distribution`].
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{CPU, Buffer, RandBuf, random::{self, Distribution}};
+    ///
+    /// let device = CPU::new();
+    /// let mut buf = Buffer::<f32>::new(&device, 4);
+    ///
+    /// let dist = Distribution::Uniform { lo: -1., hi: 1. };
+    /// device.rand(&mut buf, 42, dist);
+    ///
+    /// assert_eq!(buf[2], random::uniform(42, 2, -1., 1.));
+    /// ```
+    fn rand(&self, buf: &mut Buffer<T, D, S>, seed: u64, dist: Distribution<T>);
+
+    /// Fills the `Buffer` with uniformly distributed values in [lo, hi).
+    #[inline]
+    fn rand_uniform(&self, buf: &mut Buffer<T, D, S>, seed: u64, lo: T, hi: T) {
+        self.rand(buf, seed, Distribution::Uniform { lo, hi })
+    }
+
+    /// Fills the `Buffer` with normally distributed values.
+    #[inline]
+    fn rand_normal(&self, buf: &mut Buffer<T, D, S>, seed: u64, mean: T, std: T) {
+        self.rand(buf, seed, Distribution::Normal { mean, std })
+    }
+
+    /// Fills the `Buffer` with ones (probability `p`) and zeros.
+    #[inline]
+    fn rand_bernoulli(&self, buf: &mut Buffer<T, D, S>, seed: u64, p: T) {
+        self.rand(buf, seed, Distribution::Bernoulli { p })
+    }
+}
+
 /// Convert a possibly-indefinite [`RangeBounds`] into a [`Range`] with a start and stop index.
 #[inline]
 pub(crate) fn bounds_to_range<B: RangeBounds<usize>>(bounds: B, len: usize) -> Range<usize> {
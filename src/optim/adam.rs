@@ -0,0 +1,110 @@
+use crate::{prelude::Float, Alloc, Buffer, ClearBuf, Combiner, PtrConv, Shape};
+
+use super::{Optimizer, ParamStates, UpdateFn};
+
+/// The Adam optimizer (Kingma & Ba, 2015):
+///
+/// ```text
+/// m = beta1 * m + (1 - beta1) * grad
+/// v = beta2 * v + (1 - beta2) * grad^2
+/// param = param - lr * (m / (1 - beta1^t)) / (sqrt(v / (1 - beta2^t)) + epsilon)
+/// ```
+///
+/// `t` is the number of updates of a parameter.
+#[derive(Debug)]
+pub struct Adam<T> {
+    /// The learning rate.
+    pub lr: T,
+    /// The decay rate of the first moment `m`.
+    pub beta1: T,
+    /// The decay rate of the second moment `v`.
+    pub beta2: T,
+    /// Added to the denominator for numerical stability.
+    pub epsilon: T,
+    states: ParamStates<2>,
+}
+
+impl<T: Float> Adam<T> {
+    /// Creates a new `Adam` optimizer with the learning rate `lr`
+    /// and the default values `beta1 = 0.9`, `beta2 = 0.999` and `epsilon = 1e-8`.
+    #[inline]
+    pub fn new(lr: T) -> Adam<T> {
+        Adam {
+            lr,
+            beta1: T::as_generic(0.9),
+            beta2: T::as_generic(0.999),
+            epsilon: T::as_generic(1e-8),
+            states: ParamStates::default(),
+        }
+    }
+}
+
+impl<T> Adam<T> {
+    /// Returns the first and second moments `m` and `v` of `param`, which are owned by the optimizer.
+    /// Register them in a [`ParamRegistry`](crate::serialize::ParamRegistry) to save and restore them, together with [`Adam::updates`].
+    #[inline]
+    pub fn moments<'a, D, S>(&mut self, param: &Buffer<'a, T, D, S>) -> [Buffer<'a, T, D, S>; 2]
+    where
+        D: PtrConv + ClearBuf<T, S> + Alloc<'a, T, S>,
+        D::Ptr<T, S>: 'static,
+        S: Shape,
+    {
+        self.states.get(param.device(), param.id()).0
+    }
+
+    /// Returns the number of updates `t` of `param`, which is used for the bias correction of the moments.
+    #[inline]
+    pub fn updates<'a, D, S>(&mut self, param: &Buffer<'a, T, D, S>) -> &mut u64
+    where
+        D: PtrConv + ClearBuf<T, S> + Alloc<'a, T, S>,
+        D::Ptr<T, S>: 'static,
+        S: Shape,
+    {
+        self.states.get::<T, D, S>(param.device(), param.id()).1
+    }
+}
+
+impl<T, D, S> Optimizer<T, D, S> for Adam<T>
+where
+    T: Float + Sync,
+    D: UpdateFn<T, S> + PtrConv + ClearBuf<T, S> + for<'a> Alloc<'a, T, S>,
+    D::Ptr<T, S>: 'static,
+    S: Shape,
+{
+    fn update(&mut self, param: &mut Buffer<T, D, S>, grad: &Buffer<T, D, S>) {
+        let (beta1, beta2, epsilon) = (self.beta1, self.beta2, self.epsilon);
+
+        let device = param.device();
+        let ([mut m, mut v], updates) = self.states.get(device, param.id());
+        *updates += 1;
+
+        // the bias corrections change with every update, hence they are passed as scalars
+        let t = (*updates).min(i32::MAX as u64) as i32;
+        let lr = self.lr / (T::one() - beta1.powi(t));
+        let v_correction = T::one() / (T::one() - beta2.powi(t));
+        let half = T::as_generic(0.5);
+
+        device.update_fn(
+            &mut m,
+            (grad, grad),
+            [beta1, T::one() - beta1],
+            |m, grad, _, [beta1, one_minus_beta1]| m.mul(beta1).add(grad.mul(one_minus_beta1)),
+        );
+        device.update_fn(
+            &mut v,
+            (grad, grad),
+            [beta2, T::one() - beta2],
+            |v, grad, _, [beta2, one_minus_beta2]| {
+                v.mul(beta2).add(grad.mul(grad).mul(one_minus_beta2))
+            },
+        );
+        device.update_fn(
+            param,
+            (&m, &v),
+            [lr, v_correction, epsilon],
+            move |param, m, v, [lr, v_correction, epsilon]| {
+                param.sub(m.mul(lr).div(v.mul(v_correction).pow(half).add(epsilon)))
+            },
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Optimizers that update parameters in place using their gradients.
+//!
+//! The updates are element-wise kernels generated via [`Combiner`](crate::Combiner) (see [`UpdateFn`]).
+//! Optimizers with state, e.g. [`Momentum`] and [`Adam`], own their state buffers, which are allocated outside of the [`Cache`](crate::Cache) of the device.
+//!
+//! # Example
+#![cfg_attr(all(feature = "cpu", feature = "macro"), doc = "```")]
+#![cfg_attr(not(all(feature = "cpu", feature = "macro")), doc = "```ignore")]
+//! use custos::{optim::{Optimizer, Sgd}, Buffer, CPU};
+//!
+//! let device = CPU::new();
+//!
+//! let mut param = Buffer::from((&device, [1., 2., 3.]));
+//! let grad = Buffer::from((&device, [1., -1., 0.5]));
+//!
+//! Sgd::new(0.1).update(&mut param, &grad);
+//! assert_eq!(param.read(), [0.9, 2.1, 2.95]);
+//! ```
+
+#[cfg(not(feature = "no-std"))]
+mod adam;
+#[cfg(not(feature = "no-std"))]
+mod momentum;
+mod sgd;
+
+#[cfg(not(feature = "no-std"))]
+pub use adam::*;
+#[cfg(not(feature = "no-std"))]
+pub use momentum::*;
+pub use sgd::*;
+
+#[cfg(not(feature = "no-std"))]
+use core::{any::Any, hash::BuildHasherDefault};
+#[cfg(not(feature = "no-std"))]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "no-std"))]
+use crate::{flag::AllocFlag, Alloc, ClearBuf, Ident, IdentHasher, PtrConv};
+use crate::{Buffer, Device, Eval, MayToCLSource, Resolve, Shape};
+
+/// The names of the scalars in the kernels generated by [`UpdateFn`]. A kernel supports up to 8 scalars.
+pub const SCALAR_MARKERS: [&str; 8] = ["s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7"];
+
+/// Returns the markers of `N` scalars, see [`SCALAR_MARKERS`].
+///
+/// # Panics
+/// If `N` is greater than 8.
+pub fn scalar_markers<T: Default, const N: usize>() -> [Resolve<T>; N] {
+    assert!(
+        N <= SCALAR_MARKERS.len(),
+        "update_fn supports up to {} scalars, got {N}",
+        SCALAR_MARKERS.len()
+    );
+    core::array::from_fn(|idx| Resolve::with_marker(SCALAR_MARKERS[idx]))
+}
+
+/// Updates a buffer in place using the values of two other buffers.
+pub trait UpdateFn<T, S: Shape = (), D: Device = Self>: Device {
+    /// Sets every value of `buf` to the result of `f`,
+    /// which is called with the value of `buf`, the values of `lhs` and `rhs` at the same index and the `scalars`.
+    ///
+    /// Devices that compile kernels pass the `scalars` as kernel arguments.
+    /// Values that change between calls, e.g. a bias correction that depends on the step, should be passed as scalars instead of being captured by `f`.
+    /// Otherwise, a new kernel is compiled for every value.
+    /// # Example
+    #[cfg_attr(all(feature = "cpu", feature = "macro"), doc = "```")]
+    #[cfg_attr(not(all(feature = "cpu", feature = "macro")), doc = "```ignore")]
+    /// use custos::{optim::UpdateFn, Buffer, Combiner, CPU};
+    ///
+    /// let device = CPU::new();
+    ///
+    /// let mut buf = Buffer::from((&device, [1., 2., 3.]));
+    /// let lhs = Buffer::from((&device, [2., 2., 2.]));
+    /// let rhs = Buffer::from((&device, [1., 0., -1.]));
+    ///
+    /// device.update_fn(&mut buf, (&lhs, &rhs), [0.5], |x, lhs, rhs, [scale]| {
+    ///     x.mul(lhs).add(rhs).mul(scale)
+    /// });
+    /// assert_eq!(buf.read(), [1.5, 2., 2.5]);
+    /// ```
+    fn update_fn<F, const N: usize>(
+        &self,
+        buf: &mut Buffer<T, D, S>,
+        inputs: (&Buffer<T, D, S>, &Buffer<T, D, S>),
+        scalars: [T; N],
+        f: impl Fn(Resolve<T>, Resolve<T>, Resolve<T>, [Resolve<T>; N]) -> F + Sync,
+    ) where
+        F: Eval<T> + MayToCLSource;
+}
+
+/// Updates parameters in place using their gradients.
+pub trait Optimizer<T, D: Device, S: Shape = ()> {
+    /// Updates `param` using its gradient `grad`.
+    fn update(&mut self, param: &mut Buffer<T, D, S>, grad: &Buffer<T, D, S>);
+
+    /// Updates `param` using its gradient, which is stored in the [`Tape`](crate::Tape) of the device.
+    /// Does nothing if no gradient was allocated for `param`, e.g. if it was not part of the last `backward` pass.
+    #[cfg(feature = "autograd")]
+    fn step(&mut self, param: &mut Buffer<T, D, S>)
+    where
+        T: 'static,
+        D: crate::TapeReturn,
+    {
+        let device = param.device();
+        let tape = device.tape();
+
+        if let Some(grad) = tape.grads.may_get_ref::<T, S>(param.id()) {
+            self.update(param, grad);
+        }
+    }
+}
+
+/// The type-erased, owned pointers of the state buffers and the number of updates of a parameter.
+#[cfg(not(feature = "no-std"))]
+type ParamState<const N: usize> = ([Box<dyn Any>; N], u64);
+
+/// The state buffers of every parameter, which are owned by the optimizer.
+#[cfg(not(feature = "no-std"))]
+#[derive(Debug, Default)]
+struct ParamStates<const N: usize> {
+    states: HashMap<Ident, ParamState<N>, BuildHasherDefault<IdentHasher>>,
+}
+
+#[cfg(not(feature = "no-std"))]
+impl<const N: usize> ParamStates<N> {
+    /// Returns the state buffers and the number of updates of `param`.
+    ///
+    /// The state buffers are allocated and cleared on first use.
+    /// They are not added to the cache of the device, hence they do not take the cache identifiers of other buffers,
+    /// e.g. of buffers retrieved inside a [`range`](crate::range).
+    ///
+    /// # Panics
+    /// If the state of `param` was created for another data type, shape or device.
+    fn get<'a, T, D, S>(
+        &mut self,
+        device: &'a D,
+        param: Ident,
+    ) -> ([Buffer<'a, T, D, S>; N], &mut u64)
+    where
+        D: PtrConv + ClearBuf<T, S> + Alloc<'a, T, S>,
+        D::Ptr<T, S>: 'static,
+        S: Shape,
+    {
+        let wrap = |ptr: &D::Ptr<T, S>| Buffer {
+            ptr: unsafe { D::convert(ptr, AllocFlag::Wrapper) },
+            device: Some(device),
+            ident: None,
+        };
+
+        let (ptrs, updates) = self.states.entry(param).or_insert_with(|| {
+            let ptrs = core::array::from_fn(|_| {
+                let ptr = device.alloc(param.len, AllocFlag::None);
+                device.clear(&mut wrap(&ptr));
+                Box::new(ptr) as Box<dyn Any>
+            });
+            (ptrs, 0)
+        });
+
+        let bufs = core::array::from_fn(|idx| {
+            wrap(
+                ptrs[idx]
+                    .downcast_ref()
+                    .expect("The state of this parameter was created for another buffer type."),
+            )
+        });
+        (bufs, updates)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{prelude::Float, Alloc, Buffer, ClearBuf, Combiner, PtrConv, Shape};
+
+use super::{Optimizer, ParamStates, UpdateFn};
+
+/// Stochastic gradient descent with momentum:
+///
+/// ```text
+/// velocity = momentum * velocity + grad
+/// param = param - lr * velocity
+/// ```
+#[derive(Debug)]
+pub struct Momentum<T> {
+    /// The learning rate.
+    pub lr: T,
+    /// The factor of the previous velocity.
+    pub momentum: T,
+    states: ParamStates<1>,
+}
+
+impl<T> Momentum<T> {
+    /// Creates a new `Momentum` optimizer with the learning rate `lr` and the factor `momentum`, e.g. `0.9`.
+    #[inline]
+    pub fn new(lr: T, momentum: T) -> Momentum<T> {
+        Momentum {
+            lr,
+            momentum,
+            states: ParamStates::default(),
+        }
+    }
+
+    /// Returns the velocity of `param`, which is owned by the optimizer.
+    /// Register it in a [`ParamRegistry`](crate::serialize::ParamRegistry) to save and restore it.
+    #[inline]
+    pub fn velocity<'a, D, S>(&mut self, param: &Buffer<'a, T, D, S>) -> Buffer<'a, T, D, S>
+    where
+        D: PtrConv + ClearBuf<T, S> + Alloc<'a, T, S>,
+        D::Ptr<T, S>: 'static,
+        S: Shape,
+    {
+        let ([velocity], _) = self.states.get(param.device(), param.id());
+        velocity
+    }
+}
+
+impl<T, D, S> Optimizer<T, D, S> for Momentum<T>
+where
+    T: Float + Sync,
+    D: UpdateFn<T, S> + PtrConv + ClearBuf<T, S> + for<'a> Alloc<'a, T, S>,
+    D::Ptr<T, S>: 'static,
+    S: Shape,
+{
+    fn update(&mut self, param: &mut Buffer<T, D, S>, grad: &Buffer<T, D, S>) {
+        let device = param.device();
+        let mut velocity = self.velocity(param);
+
+        device.update_fn(
+            &mut velocity,
+            (grad, grad),
+            [self.momentum],
+            |velocity, grad, _, [momentum]| velocity.mul(momentum).add(grad),
+        );
+        device.update_fn(
+            param,
+            (&velocity, &velocity),
+            [self.lr],
+            |param, velocity, _, [lr]| param.sub(velocity.mul(lr)),
+        );
+    }
+}
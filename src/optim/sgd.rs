@@ -0,0 +1,33 @@
+use crate::{prelude::Float, Buffer, Combiner, Shape};
+
+use super::{Optimizer, UpdateFn};
+
+/// Stochastic gradient descent: `param = param - lr * grad`
+#[derive(Debug, Clone, Copy)]
+pub struct Sgd<T> {
+    /// The learning rate.
+    pub lr: T,
+}
+
+impl<T> Sgd<T> {
+    /// Creates a new `Sgd` optimizer with the learning rate `lr`.
+    #[inline]
+    pub fn new(lr: T) -> Sgd<T> {
+        Sgd { lr }
+    }
+}
+
+impl<T, D, S> Optimizer<T, D, S> for Sgd<T>
+where
+    T: Float + Sync,
+    D: UpdateFn<T, S>,
+    S: Shape,
+{
+    fn update(&mut self, param: &mut Buffer<T, D, S>, grad: &Buffer<T, D, S>) {
+        param
+            .device()
+            .update_fn(param, (grad, grad), [self.lr], |param, grad, _, [lr]| {
+                param.sub(grad.mul(lr))
+            });
+    }
+}
@@ -0,0 +1,303 @@
+//! Counter-based random number generation.
+//!
+//! All devices use the Philox4x32-10 generator of Salmon et al. ("Parallel Random Numbers: As Easy as 1, 2, 3").
+//! The i-th element of a buffer is generated with the counter `[i_lo, i_hi, 0, 0]` and the seed as key.
+//! Therefore, filling a buffer with the same seed produces the same values on every device.
+
+use crate::number::Float;
+
+const PHILOX_M0: u32 = 0xD251_1F53;
+const PHILOX_M1: u32 = 0xCD9E_8D57;
+const PHILOX_W0: u32 = 0x9E37_79B9;
+const PHILOX_W1: u32 = 0xBB67_AE85;
+
+/// 2^-24, converts the upper 24 bits of a `u32` to a float in [0, 1).
+const UNIT_SCALE: f64 = 5.960_464_477_539_063e-8;
+
+/// The Philox4x32-10 block function.
+/// # Example
+/// ```
+/// use custos::random::philox4x32;
+///
+/// assert_eq!(
+///     philox4x32([0; 4], [0; 2]),
+///     [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]
+/// );
+/// ```
+pub fn philox4x32(mut ctr: [u32; 4], key: [u32; 2]) -> [u32; 4] {
+    let [mut k0, mut k1] = key;
+
+    for _ in 0..10 {
+        let p0 = PHILOX_M0 as u64 * ctr[0] as u64;
+        let p1 = PHILOX_M1 as u64 * ctr[2] as u64;
+
+        ctr = [
+            (p1 >> 32) as u32 ^ ctr[1] ^ k0,
+            p1 as u32,
+            (p0 >> 32) as u32 ^ ctr[3] ^ k1,
+            p0 as u32,
+        ];
+
+        k0 = k0.wrapping_add(PHILOX_W0);
+        k1 = k1.wrapping_add(PHILOX_W1);
+    }
+    ctr
+}
+
+/// Returns the random bits of the element at position `idx`.
+#[inline]
+pub fn philox_bits(seed: u64, idx: usize) -> [u32; 4] {
+    let idx = idx as u64;
+    philox4x32(
+        [idx as u32, (idx >> 32) as u32, 0, 0],
+        [seed as u32, (seed >> 32) as u32],
+    )
+}
+
+/// Converts random bits to a float in [0, 1).
+#[inline]
+pub fn unit<T: Float>(bits: u32) -> T {
+    T::from_u64((bits >> 8) as u64) * T::as_generic(UNIT_SCALE)
+}
+
+/// The value of the element at position `idx` of a buffer filled with [`RandBuf::rand_uniform`](crate::RandBuf::rand_uniform).
+#[inline]
+pub fn uniform<T: Float>(seed: u64, idx: usize, lo: T, hi: T) -> T {
+    lo + (hi - lo) * unit::<T>(philox_bits(seed, idx)[0])
+}
+
+/// The value of the element at position `idx` of a buffer filled with [`RandBuf::rand_normal`](crate::RandBuf::rand_normal).
+/// Uses the Box-Muller transform.
+#[inline]
+pub fn normal<T: Float>(seed: u64, idx: usize, mean: T, std: T) -> T {
+    let bits = philox_bits(seed, idx);
+
+    // in (0, 1], ln(0) is undefined
+    let u1 = T::from_u64(((bits[0] >> 8) + 1) as u64) * T::as_generic(UNIT_SCALE);
+    let u2 = unit::<T>(bits[1]);
+
+    let radius = (T::as_generic(-2.) * u1.ln()).sqrt();
+    mean + std * radius * (T::as_generic(core::f64::consts::TAU) * u2).cos()
+}
+
+/// The value of the element at position `idx` of a buffer filled with [`RandBuf::rand_bernoulli`](crate::RandBuf::rand_bernoulli).
+#[inline]
+pub fn bernoulli<T: Float>(seed: u64, idx: usize, p: T) -> T {
+    if unit::<T>(philox_bits(seed, idx)[0]) < p {
+        T::one()
+    } else {
+        T::zero()
+    }
+}
+
+/// Splits the seed into the two 32 bit key words used by the kernels.
+#[inline]
+pub fn seed_key(seed: u64) -> [u32; 2] {
+    [seed as u32, (seed >> 32) as u32]
+}
+
+/// The distribution of random values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution<T> {
+    /// Uniform in [lo, hi).
+    Uniform {
+        /// The lower bound (inclusive).
+        lo: T,
+        /// The upper bound (exclusive).
+        hi: T,
+    },
+    /// Normal with the given mean and standard deviation.
+    Normal {
+        /// The mean of the distribution.
+        mean: T,
+        /// The standard deviation of the distribution.
+        std: T,
+    },
+    /// 1 with probability `p`, otherwise 0.
+    Bernoulli {
+        /// The probability of a 1.
+        p: T,
+    },
+}
+
+impl<T: Float> Distribution<T> {
+    /// Returns the value of the element at position `idx` for the given seed.
+    #[inline]
+    pub fn sample(&self, seed: u64, idx: usize) -> T {
+        match *self {
+            Distribution::Uniform { lo, hi } => uniform(seed, idx, lo, hi),
+            Distribution::Normal { mean, std } => normal(seed, idx, mean, std),
+            Distribution::Bernoulli { p } => bernoulli(seed, idx, p),
+        }
+    }
+
+    /// Returns the two parameters of the distribution (`p` is padded with zero).
+    #[inline]
+    pub fn params(&self) -> (T, T) {
+        match *self {
+            Distribution::Uniform { lo, hi } => (lo, hi),
+            Distribution::Normal { mean, std } => (mean, std),
+            Distribution::Bernoulli { p } => (p, T::zero()),
+        }
+    }
+
+    /// Returns the source code that computes a sample from the random bits `bits` and the parameters `a` and `b`.
+    /// `ty` is the C (or WGSL) type of `T`.
+    #[cfg(not(feature = "no-std"))]
+    pub fn sample_src(&self, ty: &str, wgsl: bool) -> String {
+        // WGSL has no C-style casts
+        let cast = |value: &str| {
+            if wgsl {
+                format!("{ty}({value})")
+            } else {
+                format!("(({ty}) ({value}))")
+            }
+        };
+        let unit_scale = cast("5.9604644775390625e-8");
+
+        match self {
+            Distribution::Uniform { .. } => format!(
+                "a + (b - a) * ({} * {unit_scale})",
+                cast("bits[0] >> 8u")
+            ),
+            Distribution::Normal { .. } => format!(
+                "a + b * sqrt({minus_two} * log({u1} * {unit_scale})) * cos({tau} * ({u2} * {unit_scale}))",
+                minus_two = cast("-2.0"),
+                u1 = cast("(bits[0] >> 8u) + 1u"),
+                u2 = cast("bits[1] >> 8u"),
+                tau = cast("6.283185307179586"),
+            ),
+            Distribution::Bernoulli { .. } => {
+                let unit = format!("{} * {unit_scale}", cast("bits[0] >> 8u"));
+                if wgsl {
+                    format!("select({ty}(0), {ty}(1), {unit} < a)")
+                } else {
+                    format!("({unit} < a) ? 1 : 0")
+                }
+            }
+        }
+    }
+}
+
+/// The Philox4x32-10 block function in C source code, usable in OpenCL and CUDA kernels.
+/// `uint` / `ulong` are the names of the 32 / 64 bit unsigned integer types and `qualifier` is prepended to the function definition.
+#[cfg(not(feature = "no-std"))]
+pub fn philox_c_source(uint: &str, ulong: &str, qualifier: &str) -> String {
+    format!(
+        "
+        {qualifier} void philox4x32({uint}* ctr, {uint} k0, {uint} k1) {{
+            for (int i = 0; i < 10; i++) {{
+                {ulong} p0 = ({ulong}) {PHILOX_M0}u * ctr[0];
+                {ulong} p1 = ({ulong}) {PHILOX_M1}u * ctr[2];
+                {uint} c1 = ctr[1];
+                {uint} c3 = ctr[3];
+                ctr[0] = ({uint}) (p1 >> 32) ^ c1 ^ k0;
+                ctr[1] = ({uint}) p1;
+                ctr[2] = ({uint}) (p0 >> 32) ^ c3 ^ k1;
+                ctr[3] = ({uint}) p0;
+                k0 += {PHILOX_W0}u;
+                k1 += {PHILOX_W1}u;
+            }}
+        }}
+    "
+    )
+}
+
+/// The Philox4x32-10 block function in WGSL source code.
+/// WGSL has no 64 bit integers, hence the high part of the product is computed with 16 bit limbs.
+#[cfg(not(feature = "no-std"))]
+pub fn philox_wgsl_source() -> String {
+    format!(
+        "
+        fn mulhi(a: u32, b: u32) -> u32 {{
+            let a_lo = a & 0xFFFFu;
+            let a_hi = a >> 16u;
+            let b_lo = b & 0xFFFFu;
+            let b_hi = b >> 16u;
+
+            let lo_lo = a_lo * b_lo;
+            let hi_lo = a_hi * b_lo;
+            let lo_hi = a_lo * b_hi;
+            let hi_hi = a_hi * b_hi;
+
+            let cross = (lo_lo >> 16u) + (hi_lo & 0xFFFFu) + (lo_hi & 0xFFFFu);
+            return hi_hi + (hi_lo >> 16u) + (lo_hi >> 16u) + (cross >> 16u);
+        }}
+
+        fn philox4x32(counter: vec4<u32>, key0: u32, key1: u32) -> vec4<u32> {{
+            var ctr = counter;
+            var k0 = key0;
+            var k1 = key1;
+            for (var i = 0; i < 10; i++) {{
+                let lo0 = {PHILOX_M0}u * ctr.x;
+                let hi0 = mulhi({PHILOX_M0}u, ctr.x);
+                let lo1 = {PHILOX_M1}u * ctr.z;
+                let hi1 = mulhi({PHILOX_M1}u, ctr.z);
+                ctr = vec4<u32>(hi1 ^ ctr.y ^ k0, lo1, hi0 ^ ctr.w ^ k1, lo0);
+                k0 += {PHILOX_W0}u;
+                k1 += {PHILOX_W1}u;
+            }}
+            return ctr;
+        }}
+    "
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{philox4x32, Distribution};
+
+    // known answer tests of the Random123 reference implementation
+    #[test]
+    fn test_philox_kat() {
+        assert_eq!(
+            philox4x32([0; 4], [0; 2]),
+            [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]
+        );
+        assert_eq!(
+            philox4x32([u32::MAX; 4], [u32::MAX; 2]),
+            [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd]
+        );
+        assert_eq!(
+            philox4x32(
+                [0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344],
+                [0xa4093822, 0x299f31d0]
+            ),
+            [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]
+        );
+    }
+
+    #[test]
+    fn test_distribution_ranges() {
+        let uniform = Distribution::Uniform { lo: -2f32, hi: 3. };
+        let bernoulli = Distribution::Bernoulli { p: 0.25f64 };
+
+        let mut ones = 0.;
+        for idx in 0..10000 {
+            let x = uniform.sample(42, idx);
+            assert!((-2. ..3.).contains(&x));
+            ones += bernoulli.sample(42, idx);
+        }
+        assert!((ones / 10000. - 0.25f64).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_normal_moments() {
+        let normal = Distribution::Normal {
+            mean: 1f64,
+            std: 2.,
+        };
+
+        let (mut sum, mut sum_sq) = (0., 0.);
+        for idx in 0..10000 {
+            let x = normal.sample(7, idx);
+            sum += x;
+            sum_sq += x * x;
+        }
+        let mean = sum / 10000.;
+        let var = sum_sq / 10000. - mean * mean;
+
+        assert!((mean - 1.).abs() < 0.1);
+        assert!((var.sqrt() - 2.).abs() < 0.1);
+    }
+}
@@ -0,0 +1,269 @@
+use std::io::{self, Read, Write};
+
+use super::{BufferData, SerializeError};
+
+/// The magic bytes at the start of a saved buffer.
+pub const MAGIC: [u8; 6] = *b"CUSTOS";
+
+/// The current version of the binary format.
+pub const FORMAT_VERSION: u16 = 1;
+
+const LITTLE_ENDIAN: u8 = 0;
+const BIG_ENDIAN: u8 = 1;
+
+/// An element type that can be converted to and from bytes.
+pub trait BinaryElement: Copy {
+    /// Appends the bytes of the element in native byte order.
+    fn extend_bytes(self, bytes: &mut Vec<u8>);
+
+    /// Creates an element from `size_of::<Self>()` bytes in the given byte order.
+    fn from_bytes(bytes: &[u8], big_endian: bool) -> Self;
+}
+
+macro_rules! impl_binary_element {
+    ($($t:ty),*) => {
+        $(
+            impl BinaryElement for $t {
+                #[inline]
+                fn extend_bytes(self, bytes: &mut Vec<u8>) {
+                    bytes.extend_from_slice(&self.to_ne_bytes())
+                }
+
+                #[inline]
+                fn from_bytes(bytes: &[u8], big_endian: bool) -> Self {
+                    let bytes = bytes.try_into().unwrap();
+                    match big_endian {
+                        true => <$t>::from_be_bytes(bytes),
+                        false => <$t>::from_le_bytes(bytes),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_element!(f32, f64, i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+
+#[cfg(feature = "half")]
+impl_binary_element!(half::f16, half::bf16);
+
+impl BinaryElement for bool {
+    #[inline]
+    fn extend_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.push(self as u8)
+    }
+
+    #[inline]
+    fn from_bytes(bytes: &[u8], _big_endian: bool) -> Self {
+        bytes[0] != 0
+    }
+}
+
+/// Converts the elements to bytes in the given byte order.
+pub(crate) fn elements_to_bytes<T: BinaryElement>(data: &[T], big_endian: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(core::mem::size_of_val(data));
+    for value in data {
+        value.extend_bytes(&mut bytes);
+    }
+
+    if big_endian != cfg!(target_endian = "big") {
+        for value in bytes.chunks_exact_mut(core::mem::size_of::<T>()) {
+            value.reverse();
+        }
+    }
+    bytes
+}
+
+/// Converts bytes in the given byte order to elements.
+/// Trailing bytes that do not form a complete element are ignored.
+pub(crate) fn elements_from_bytes<T: BinaryElement>(bytes: &[u8], big_endian: bool) -> Vec<T> {
+    bytes
+        .chunks_exact(core::mem::size_of::<T>())
+        .map(|bytes| T::from_bytes(bytes, big_endian))
+        .collect()
+}
+
+pub(crate) fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u64(reader: &mut impl Read) -> crate::Result<usize> {
+    let value = u64::from_le_bytes(read_array(reader)?);
+    Ok(usize::try_from(value).map_err(|_| SerializeError::InvalidData)?)
+}
+
+impl<T: BinaryElement> BufferData<T> {
+    /// Writes the buffer data in a versioned binary format.
+    ///
+    /// | Bytes | Content |
+    /// | --- | --- |
+    /// | 6 | [`MAGIC`] |
+    /// | 2 | [`FORMAT_VERSION`] |
+    /// | 1 | Byte order of the elements (0: little endian, 1: big endian) |
+    /// | 1 | Size of an element in bytes |
+    /// | 1 + n | Length n and the ASCII bytes of the data type |
+    /// | 1 + 8 * d | Number of dimensions d and the dimensions |
+    /// | 8 | Number of elements |
+    /// | ... | Elements |
+    ///
+    /// All header integers are little endian.
+    /// The elements are written in the byte order of the host and converted while loading if required.
+    pub fn write_to(&self, mut writer: impl Write) -> crate::Result<()> {
+        let dtype = self.dtype.as_bytes();
+        if dtype.len() > u8::MAX as usize || self.dims.len() > u8::MAX as usize {
+            return Err(SerializeError::InvalidData.into());
+        }
+
+        let mut bytes = Vec::with_capacity(32 + core::mem::size_of_val(self.data.as_slice()));
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+
+        let endianness = match cfg!(target_endian = "big") {
+            true => BIG_ENDIAN,
+            false => LITTLE_ENDIAN,
+        };
+        bytes.push(endianness);
+        bytes.push(core::mem::size_of::<T>() as u8);
+
+        bytes.push(dtype.len() as u8);
+        bytes.extend_from_slice(dtype);
+
+        bytes.push(self.dims.len() as u8);
+        for dim in &self.dims {
+            bytes.extend_from_slice(&(*dim as u64).to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.data.len() as u64).to_le_bytes());
+        bytes.extend(elements_to_bytes(&self.data, endianness == BIG_ENDIAN));
+
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Reads buffer data that was written by [`write_to`](BufferData::write_to).
+    ///
+    /// # Errors
+    /// Returns an I/O error or a [`SerializeError`] if the format is invalid.
+    /// The data type is not checked, see [`BufferData::check`].
+    pub fn read_from(mut reader: impl Read) -> crate::Result<BufferData<T>> {
+        if read_array(&mut reader)? != MAGIC {
+            return Err(SerializeError::InvalidMagic.into());
+        }
+
+        let version = u16::from_le_bytes(read_array(&mut reader)?);
+        if version != FORMAT_VERSION {
+            return Err(SerializeError::UnsupportedVersion(version).into());
+        }
+
+        let [endianness, size] = read_array(&mut reader)?;
+        let big_endian = match endianness {
+            LITTLE_ENDIAN => false,
+            BIG_ENDIAN => true,
+            _ => return Err(SerializeError::InvalidData.into()),
+        };
+        if size as usize != core::mem::size_of::<T>() {
+            return Err(SerializeError::InvalidData.into());
+        }
+
+        let [dtype_len] = read_array(&mut reader)?;
+        let mut dtype = vec![0; dtype_len as usize];
+        reader.read_exact(&mut dtype)?;
+        let dtype = String::from_utf8(dtype).map_err(|_| SerializeError::InvalidData)?;
+
+        let [dim_count] = read_array(&mut reader)?;
+        let dims = (0..dim_count)
+            .map(|_| read_u64(&mut reader))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let len = read_u64(&mut reader)?;
+        let byte_len = len
+            .checked_mul(size as usize)
+            .ok_or(SerializeError::InvalidData)?;
+
+        // does not allocate `byte_len` bytes upfront, as the length may be corrupted
+        let mut bytes = Vec::new();
+        reader.take(byte_len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != byte_len {
+            return Err(SerializeError::InvalidData.into());
+        }
+
+        Ok(BufferData {
+            dtype,
+            dims,
+            data: elements_from_bytes(&bytes, big_endian),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialize::{BufferData, SerializeError, FORMAT_VERSION};
+
+    fn data() -> BufferData<i32> {
+        BufferData {
+            dtype: "int".into(),
+            dims: vec![2, 2],
+            data: vec![1, -2, 3, i32::MAX],
+        }
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut bytes = Vec::new();
+        data().write_to(&mut bytes).unwrap();
+
+        assert_eq!(BufferData::<i32>::read_from(&bytes[..]).unwrap(), data());
+    }
+
+    #[test]
+    fn test_binary_big_endian_data() {
+        let mut bytes = Vec::new();
+        data().write_to(&mut bytes).unwrap();
+
+        // header: magic (6), version (2), endianness (1), size (1), dtype (1 + 3), dims (1 + 16), len (8)
+        let offset = 6 + 2 + 1 + 1 + 4 + 17 + 8;
+        bytes[8] = 1;
+        for (idx, value) in data().data.iter().enumerate() {
+            let start = offset + idx * 4;
+            bytes[start..start + 4].copy_from_slice(&value.to_be_bytes());
+        }
+
+        assert_eq!(BufferData::<i32>::read_from(&bytes[..]).unwrap(), data());
+    }
+
+    #[test]
+    fn test_binary_invalid() {
+        let mut bytes = Vec::new();
+        data().write_to(&mut bytes).unwrap();
+
+        let err = |bytes: &[u8]| {
+            BufferData::<i32>::read_from(bytes)
+                .unwrap_err()
+                .downcast::<SerializeError>()
+                .map(|err| *err)
+                .ok()
+        };
+
+        let mut invalid = bytes.clone();
+        invalid[0] = b'X';
+        assert_eq!(err(&invalid), Some(SerializeError::InvalidMagic));
+
+        let mut invalid = bytes.clone();
+        invalid[6..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert_eq!(
+            err(&invalid),
+            Some(SerializeError::UnsupportedVersion(FORMAT_VERSION + 1))
+        );
+
+        // truncated data
+        assert_eq!(
+            err(&bytes[..bytes.len() - 1]),
+            Some(SerializeError::InvalidData)
+        );
+
+        // wrong element size
+        assert!(BufferData::<i64>::read_from(&bytes[..]).is_err());
+    }
+}
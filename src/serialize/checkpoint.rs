@@ -0,0 +1,300 @@
+use std::io::{Read, Write};
+
+use crate::{Alloc, Buffer, CDatatype, Device, Ident, MayTapeReturn, Shape, WriteBuf};
+
+use super::{SafeTensors, SafeTensorsElement, SafeTensorsWriter, SerializeError};
+
+type Tensors<'w> = SafeTensorsWriter<&'w mut dyn Write>;
+
+/// Saves and restores a registered buffer without knowing its element type and shape.
+trait Entry<D> {
+    fn save(&self, name: &str, tensors: &mut Tensors);
+
+    /// Checks that the saved tensor can be restored into the registered buffer.
+    fn check(&self, name: &str, dims: &[usize], tensors: &SafeTensors) -> crate::Result<()>;
+
+    fn restore(&mut self, device: &D, name: &str, tensors: &SafeTensors) -> crate::Result<()>;
+
+    #[cfg(feature = "autograd")]
+    fn save_grad(&self, device: &D, name: &str, tensors: &mut Tensors);
+
+    #[cfg(feature = "autograd")]
+    fn restore_grad(&self, device: &D, name: &str, tensors: &SafeTensors) -> crate::Result<()>;
+}
+
+impl<T, D, S> Entry<D> for Buffer<'_, T, D, S>
+where
+    T: SafeTensorsElement + CDatatype + Default,
+    D: crate::Read<T, S> + WriteBuf<T, S> + for<'a> Alloc<'a, T, S> + MayTapeReturn,
+    S: Shape,
+{
+    #[inline]
+    fn save(&self, name: &str, tensors: &mut Tensors) {
+        tensors.add(name, self);
+    }
+
+    fn check(&self, name: &str, dims: &[usize], tensors: &SafeTensors) -> crate::Result<()> {
+        let data = tensors.data::<T>(name)?;
+        data.check::<S>()?;
+
+        if data.dims != dims {
+            return Err(SerializeError::ShapeMismatch {
+                expected: dims.to_vec(),
+                found: data.dims,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    fn restore(&mut self, device: &D, name: &str, tensors: &SafeTensors) -> crate::Result<()> {
+        device.write(self, &tensors.data::<T>(name)?.data);
+        Ok(())
+    }
+
+    #[cfg(feature = "autograd")]
+    fn save_grad(&self, device: &D, name: &str, tensors: &mut Tensors) {
+        let Some(ident) = self.ident else {
+            return;
+        };
+
+        let tape = device.tape();
+        if let Some(grad) = tape.grads.may_get_ref::<T, S>(ident) {
+            tensors.add(name, grad);
+        }
+    }
+
+    #[cfg(feature = "autograd")]
+    fn restore_grad(&self, device: &D, name: &str, tensors: &SafeTensors) -> crate::Result<()> {
+        let Some(ident) = self.ident else {
+            return Ok(());
+        };
+
+        let data = tensors.data::<T>(name)?;
+        let mut tape = device.tape_mut();
+        let grad = tape.grads.get_mut::<T, S>(device, ident);
+        device.write(grad, &data.data);
+        Ok(())
+    }
+}
+
+struct Registered<'a, D> {
+    name: String,
+    ident: Option<Ident>,
+    dims: Vec<usize>,
+    /// Parameters are saved together with their gradients, other state is saved alone.
+    is_param: bool,
+    buf: &'a mut dyn Entry<D>,
+}
+
+/// A registry of named parameters and state buffers, e.g. the state of an optimizer.
+///
+/// [`Ident`]s, which key the gradients of a [`Tape`](crate::Tape), differ between runs.
+/// The registry maps stable names to the buffers of the current run,
+/// hence their contents can be saved to a checkpoint and restored in another run, even on another device.
+///
+/// The registry borrows the registered buffers mutably, so it is usually created right before saving or restoring.
+/// Checkpoints are safetensors files, which contain the tensors `param/{name}`, `grad/{name}` and `state/{name}`.
+///
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use custos::{serialize::ParamRegistry, Buffer, CPU};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = CPU::new();
+///     let mut weights = Buffer::<f32>::from((&device, [1., 2., 3.]));
+///
+///     let mut checkpoint = Vec::new();
+///     let mut params = ParamRegistry::new();
+///     params.register("weights", &mut weights);
+///     params.save(&device, &mut checkpoint)?;
+///
+///     weights.clear();
+///
+///     let mut params = ParamRegistry::new();
+///     params.register("weights", &mut weights);
+///     params.restore(&device, &checkpoint[..])?;
+///
+///     assert_eq!(weights.read(), [1., 2., 3.]);
+///     Ok(())
+/// }
+/// ```
+pub struct ParamRegistry<'a, D> {
+    entries: Vec<Registered<'a, D>>,
+}
+
+impl<D> Default for ParamRegistry<'_, D> {
+    #[inline]
+    fn default() -> Self {
+        ParamRegistry {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<D> core::fmt::Debug for ParamRegistry<'_, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entries(self.entries.iter().map(|entry| (&entry.name, &entry.dims)))
+            .finish()
+    }
+}
+
+impl<'a, D: Device> ParamRegistry<'a, D> {
+    /// Creates an empty registry.
+    #[inline]
+    pub fn new() -> ParamRegistry<'a, D> {
+        ParamRegistry::default()
+    }
+
+    fn insert<T, S>(&mut self, name: String, buf: &'a mut Buffer<'_, T, D, S>, is_param: bool)
+    where
+        T: SafeTensorsElement + CDatatype + Default,
+        D: crate::Read<T, S> + WriteBuf<T, S> + for<'b> Alloc<'b, T, S> + MayTapeReturn,
+        S: Shape,
+    {
+        let dims = match S::DIMS.is_empty() {
+            true => vec![buf.len()],
+            false => S::DIMS.to_vec(),
+        };
+
+        let registered = Registered {
+            name,
+            ident: buf.ident,
+            dims,
+            is_param,
+            buf,
+        };
+
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.name == registered.name)
+        {
+            Some(entry) => *entry = registered,
+            None => self.entries.push(registered),
+        }
+    }
+
+    /// Registers a parameter under `name`. Its gradient is saved and restored as well.
+    /// A buffer that was registered under the same name before is replaced.
+    #[inline]
+    pub fn register<T, S>(&mut self, name: impl Into<String>, param: &'a mut Buffer<'_, T, D, S>)
+    where
+        T: SafeTensorsElement + CDatatype + Default,
+        D: crate::Read<T, S> + WriteBuf<T, S> + for<'b> Alloc<'b, T, S> + MayTapeReturn,
+        S: Shape,
+    {
+        self.insert(name.into(), param, true)
+    }
+
+    /// Registers a state buffer under `name`, e.g. the moments of an optimizer.
+    /// A buffer that was registered under the same name before is replaced.
+    #[inline]
+    pub fn register_state<T, S>(
+        &mut self,
+        name: impl Into<String>,
+        state: &'a mut Buffer<'_, T, D, S>,
+    ) where
+        T: SafeTensorsElement + CDatatype + Default,
+        D: crate::Read<T, S> + WriteBuf<T, S> + for<'b> Alloc<'b, T, S> + MayTapeReturn,
+        S: Shape,
+    {
+        self.insert(name.into(), state, false)
+    }
+
+    /// Returns the [`Ident`] of the buffer registered under `name`.
+    #[inline]
+    pub fn ident(&self, name: &str) -> Option<Ident> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .and_then(|entry| entry.ident)
+    }
+
+    /// Returns the names of all registered buffers in order of registration.
+    pub fn names(&self) -> Vec<&str> {
+        self.entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+
+    /// Saves the registered buffers and the allocated gradients of the parameters as safetensors checkpoint.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the checkpoint could not be written.
+    pub fn save(&self, _device: &D, mut writer: impl Write) -> crate::Result<()> {
+        let mut tensors = SafeTensorsWriter::new(&mut writer as &mut dyn Write);
+
+        for Registered {
+            name,
+            is_param,
+            buf,
+            ..
+        } in &self.entries
+        {
+            match is_param {
+                true => {
+                    buf.save(&format!("param/{name}"), &mut tensors);
+                    #[cfg(feature = "autograd")]
+                    buf.save_grad(_device, &format!("grad/{name}"), &mut tensors);
+                }
+                false => buf.save(&format!("state/{name}"), &mut tensors),
+            }
+        }
+
+        tensors.finish()?;
+        Ok(())
+    }
+
+    /// Restores the registered buffers and the saved gradients of the parameters from a checkpoint written by [`ParamRegistry::save`].
+    /// Gradients that were not allocated when saving are left unchanged.
+    ///
+    /// All buffers are validated before any buffer is written.
+    ///
+    /// # Errors
+    /// Returns an I/O error or a [`SerializeError`] if the checkpoint does not contain a registered buffer
+    /// or if the data type or the shape of a saved buffer differs.
+    pub fn restore(&mut self, device: &D, reader: impl Read) -> crate::Result<()> {
+        let tensors = SafeTensors::read(reader)?;
+
+        let names = self
+            .entries
+            .iter()
+            .map(|registered| {
+                let name = &registered.name;
+                match registered.is_param {
+                    true => (format!("param/{name}"), format!("grad/{name}")),
+                    false => (format!("state/{name}"), String::new()),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let has_grad = |grad_name: &str| {
+            cfg!(feature = "autograd")
+                && !grad_name.is_empty()
+                && tensors.names().any(|name| name == grad_name)
+        };
+
+        for (registered, (name, grad_name)) in self.entries.iter().zip(&names) {
+            registered.buf.check(name, &registered.dims, &tensors)?;
+            if has_grad(grad_name) {
+                registered
+                    .buf
+                    .check(grad_name, &registered.dims, &tensors)?;
+            }
+        }
+
+        for (registered, (name, _grad_name)) in self.entries.iter_mut().zip(&names) {
+            registered.buf.restore(device, name, &tensors)?;
+
+            #[cfg(feature = "autograd")]
+            if has_grad(_grad_name) {
+                registered.buf.restore_grad(device, _grad_name, &tensors)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Serialize, Serializer};
+
+use crate::{Buffer, CDatatype, Read, Shape};
+
+use super::BufferData;
+
+/// Serializes a `Buffer` as [`BufferData`].
+/// A `Buffer` can not be deserialized directly, as it needs a device.
+/// Deserialize [`BufferData`] and use [`BufferData::to_buffer`] instead.
+impl<T, D, S> Serialize for Buffer<'_, T, D, S>
+where
+    T: CDatatype + Default + Clone + Serialize,
+    D: Read<T, S>,
+    S: Shape,
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        BufferData::from_buffer(self).serialize(serializer)
+    }
+}
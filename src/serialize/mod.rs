@@ -0,0 +1,202 @@
+//! Saving and loading [`Buffer`]s.
+//!
+//! A [`Buffer`] is converted to [`BufferData`], which contains the data type, the dimensions and the elements of the buffer.
+//! [`BufferData`] is written in a versioned binary format (see [`BufferData::write_to`]).
+//! If the `serde` feature is enabled, [`BufferData`] and [`Buffer`] also implement `serde`'s traits.
+//!
+//! Buffers can be exchanged with Python tooling through NumPy `.npy` files ([`Buffer::from_npy`], [`Buffer::to_npy`]),
+//! `.npz` archives ([`Npz`], [`NpzWriter`]) and safetensors files ([`SafeTensors`], [`SafeTensorsWriter`]).
+//!
+//! # Example
+#![cfg_attr(feature = "cpu", doc = "```")]
+#![cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+//! use custos::{Buffer, Dim2, CPU};
+//!
+//! fn main() -> custos::Result<()> {
+//!     let device = CPU::new();
+//!     let buf = Buffer::<f32, _, Dim2<2, 3>>::from((&device, vec![1., 2., 3., 4., 5., 6.]));
+//!
+//!     let mut bytes = Vec::new();
+//!     buf.save(&mut bytes)?;
+//!
+//!     let loaded = Buffer::<f32, _, Dim2<2, 3>>::load(&device, &bytes[..])?;
+//!     assert_eq!(loaded.read(), [1., 2., 3., 4., 5., 6.]);
+//!     Ok(())
+//! }
+//! ```
+
+mod binary;
+mod checkpoint;
+#[cfg(feature = "serde")]
+mod impl_serde;
+mod npy;
+mod npz;
+mod safetensors;
+
+pub use binary::*;
+pub use checkpoint::*;
+pub use npy::*;
+pub use npz::*;
+pub use safetensors::*;
+
+use std::io;
+
+use crate::{Alloc, Buffer, CDatatype, Read, Shape, WriteBuf};
+
+/// The contents of a [`Buffer`] on the host, together with its data type and dimensions.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BufferData<T> {
+    /// The data type of the elements, as returned by [`CDatatype::as_c_type_str`].
+    pub dtype: String,
+    /// The dimensions of the buffer, outermost first.
+    /// A buffer without a compile-time [`Shape`] has one dimension, its length.
+    pub dims: Vec<usize>,
+    /// The elements of the buffer.
+    pub data: Vec<T>,
+}
+
+impl<T: CDatatype> BufferData<T> {
+    /// Reads the contents of `buf`.
+    pub fn from_buffer<D, S>(buf: &Buffer<T, D, S>) -> BufferData<T>
+    where
+        T: Default + Clone,
+        D: Read<T, S>,
+        S: Shape,
+    {
+        let dims = match S::DIMS.is_empty() {
+            true => vec![buf.len()],
+            false => S::DIMS.to_vec(),
+        };
+
+        BufferData {
+            dtype: T::as_c_type_str().into(),
+            dims,
+            data: buf.read_to_vec(),
+        }
+    }
+
+    /// Allocates a new [`Buffer`] on `device` and writes the data to it.
+    ///
+    /// # Errors
+    /// A [`SerializeError`] is returned if the data type does not match `T`,
+    /// if the data does not match the dimensions or if the dimensions do not match a compile-time shape `S`.
+    pub fn to_buffer<'a, D, S>(&self, device: &'a D) -> crate::Result<Buffer<'a, T, D, S>>
+    where
+        D: Alloc<'a, T, S> + WriteBuf<T, S>,
+        S: Shape,
+    {
+        self.check::<S>()?;
+
+        let mut buf = Buffer::new(device, self.data.len());
+        device.write(&mut buf, &self.data);
+        Ok(buf)
+    }
+
+    /// Checks that the data can be stored in a [`Buffer`] with elements of type `T` and shape `S`.
+    pub fn check<S: Shape>(&self) -> Result<(), SerializeError> {
+        if self.dtype != T::as_c_type_str() {
+            return Err(SerializeError::DtypeMismatch {
+                expected: T::as_c_type_str().into(),
+                found: self.dtype.clone(),
+            });
+        }
+
+        if self.data.is_empty() || self.dims.iter().product::<usize>() != self.data.len() {
+            return Err(SerializeError::InvalidData);
+        }
+
+        if !S::DIMS.is_empty() && S::DIMS != self.dims {
+            return Err(SerializeError::ShapeMismatch {
+                expected: S::DIMS.to_vec(),
+                found: self.dims.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T, D, S> Buffer<'a, T, D, S>
+where
+    T: CDatatype + BinaryElement,
+    D: Alloc<'a, T, S> + WriteBuf<T, S>,
+    S: Shape,
+{
+    /// Loads a `Buffer` that was saved with [`Buffer::save`].
+    ///
+    /// # Errors
+    /// Returns an error if reading fails, if the format is invalid
+    /// or if the data type or the dimensions do not match (see [`BufferData::to_buffer`]).
+    #[inline]
+    pub fn load(device: &'a D, reader: impl io::Read) -> crate::Result<Buffer<'a, T, D, S>> {
+        BufferData::<T>::read_from(reader)?.to_buffer(device)
+    }
+}
+
+impl<'a, T, D, S> Buffer<'a, T, D, S>
+where
+    T: CDatatype + BinaryElement + Default,
+    D: Read<T, S>,
+    S: Shape,
+{
+    /// Saves the `Buffer` in the binary format of [`BufferData::write_to`].
+    #[inline]
+    pub fn save(&self, writer: impl io::Write) -> crate::Result<()> {
+        BufferData::from_buffer(self).write_to(writer)
+    }
+}
+
+/// Errors that can occur while saving or loading [`Buffer`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SerializeError {
+    /// The data does not start with the expected magic bytes.
+    InvalidMagic,
+    /// The format version is not supported by this version of custos.
+    UnsupportedVersion(u16),
+    /// The header is malformed or the amount of data does not match the dimensions.
+    InvalidData,
+    /// The data type of the saved buffer differs from the requested one.
+    DtypeMismatch {
+        /// The requested data type.
+        expected: String,
+        /// The data type of the saved buffer.
+        found: String,
+    },
+    /// The dimensions of the saved buffer differ from the requested compile-time shape.
+    ShapeMismatch {
+        /// The dimensions of the requested shape.
+        expected: Vec<usize>,
+        /// The dimensions of the saved buffer.
+        found: Vec<usize>,
+    },
+    /// The file uses a feature of its format that is not supported.
+    Unsupported(String),
+    /// An archive does not contain an entry with the given name.
+    MissingEntry(String),
+}
+
+impl core::fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SerializeError::InvalidMagic => write!(f, "The data is not a saved custos buffer."),
+            SerializeError::UnsupportedVersion(version) => {
+                write!(f, "The format version {version} is not supported.")
+            }
+            SerializeError::InvalidData => write!(f, "The saved buffer is malformed."),
+            SerializeError::DtypeMismatch { expected, found } => write!(
+                f,
+                "Expected a buffer with data type {expected}, but found {found}."
+            ),
+            SerializeError::ShapeMismatch { expected, found } => write!(
+                f,
+                "Expected a buffer with dimensions {expected:?}, but found {found:?}."
+            ),
+            SerializeError::Unsupported(feature) => write!(f, "{feature} are not supported."),
+            SerializeError::MissingEntry(name) => {
+                write!(f, "The archive does not contain an entry named '{name}'.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SerializeError {}
@@ -0,0 +1,282 @@
+use std::io::{self, Read, Write};
+
+use crate::{Alloc, Buffer, CDatatype, Shape, WriteBuf};
+
+use super::{
+    elements_from_bytes, elements_to_bytes, read_array, BinaryElement, BufferData, SerializeError,
+};
+
+/// The magic bytes at the start of a `.npy` file.
+pub const NPY_MAGIC: [u8; 6] = *b"\x93NUMPY";
+
+/// An element type that can be stored in a NumPy `.npy` file.
+pub trait NpyElement: BinaryElement {
+    /// The NumPy type string without the byte order character, e.g. `f4`.
+    const DESCR: &'static str;
+}
+
+macro_rules! impl_npy_element {
+    ($($t:ty: $descr:literal),*) => {
+        $(
+            impl NpyElement for $t {
+                const DESCR: &'static str = $descr;
+            }
+        )*
+    };
+}
+
+impl_npy_element!(
+    f32: "f4", f64: "f8", i8: "i1", u8: "u1", i16: "i2", u16: "u2",
+    i32: "i4", u32: "u4", i64: "i8", u64: "u8", bool: "b1"
+);
+
+#[cfg(feature = "half")]
+impl_npy_element!(half::f16: "f2");
+
+/// The parsed header of a `.npy` file.
+struct NpyHeader {
+    descr: String,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+/// Returns the value of `key` in the header dictionary, starting after the colon.
+fn header_value<'h>(header: &'h str, key: &str) -> Result<&'h str, SerializeError> {
+    [format!("'{key}'"), format!("\"{key}\"")]
+        .iter()
+        .find_map(|key| {
+            header
+                .find(key.as_str())
+                .map(|idx| &header[idx + key.len()..])
+        })
+        .and_then(|value| value.trim_start().strip_prefix(':'))
+        .map(str::trim_start)
+        .ok_or(SerializeError::InvalidData)
+}
+
+fn parse_header(header: &str) -> Result<NpyHeader, SerializeError> {
+    let descr = header_value(header, "descr")?;
+    let quote = descr.chars().next().ok_or(SerializeError::InvalidData)?;
+    if quote != '\'' && quote != '"' {
+        return Err(SerializeError::Unsupported(
+            "structured NumPy arrays".into(),
+        ));
+    }
+    let descr = descr[1..]
+        .split(quote)
+        .next()
+        .ok_or(SerializeError::InvalidData)?;
+
+    let fortran_order = header_value(header, "fortran_order")?;
+    let fortran_order = match () {
+        _ if fortran_order.starts_with("True") => true,
+        _ if fortran_order.starts_with("False") => false,
+        _ => return Err(SerializeError::InvalidData),
+    };
+
+    let shape = header_value(header, "shape")?
+        .strip_prefix('(')
+        .and_then(|shape| shape.split(')').next())
+        .ok_or(SerializeError::InvalidData)?;
+    let shape = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse().map_err(|_| SerializeError::InvalidData))
+        .collect::<Result<_, _>>()?;
+
+    Ok(NpyHeader {
+        descr: descr.into(),
+        fortran_order,
+        shape,
+    })
+}
+
+impl<T: NpyElement + CDatatype> BufferData<T> {
+    /// Reads a NumPy `.npy` file (format version 1.0 to 3.0).
+    ///
+    /// # Errors
+    /// Returns an I/O error or a [`SerializeError`] if the file is invalid,
+    /// if its data type differs from `T` or if the array is stored in Fortran order.
+    pub fn read_npy(mut reader: impl Read) -> crate::Result<BufferData<T>> {
+        if read_array(&mut reader)? != NPY_MAGIC {
+            return Err(SerializeError::InvalidMagic.into());
+        }
+
+        let [major, _minor] = read_array(&mut reader)?;
+        let header_len = match major {
+            1 => u16::from_le_bytes(read_array(&mut reader)?) as usize,
+            2 | 3 => u32::from_le_bytes(read_array(&mut reader)?) as usize,
+            _ => return Err(SerializeError::UnsupportedVersion(major as u16).into()),
+        };
+
+        let mut header = Vec::new();
+        (&mut reader)
+            .take(header_len as u64)
+            .read_to_end(&mut header)?;
+        let header = String::from_utf8(header).map_err(|_| SerializeError::InvalidData)?;
+        let header = parse_header(&header)?;
+
+        let (big_endian, descr) = match header.descr.chars().next() {
+            Some('<' | '|' | '=') => (false, &header.descr[1..]),
+            Some('>') => (true, &header.descr[1..]),
+            _ => (false, header.descr.as_str()),
+        };
+
+        if descr != T::DESCR {
+            return Err(SerializeError::DtypeMismatch {
+                expected: T::DESCR.into(),
+                found: header.descr,
+            }
+            .into());
+        }
+
+        if header.fortran_order && header.shape.len() > 1 {
+            return Err(SerializeError::Unsupported("Fortran-ordered NumPy arrays".into()).into());
+        }
+
+        let byte_len = header
+            .shape
+            .iter()
+            .try_fold(core::mem::size_of::<T>(), |len, dim| len.checked_mul(*dim))
+            .ok_or(SerializeError::InvalidData)?;
+
+        // does not allocate `byte_len` bytes upfront, as the shape may be corrupted
+        let mut bytes = Vec::new();
+        reader.take(byte_len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != byte_len {
+            return Err(SerializeError::InvalidData.into());
+        }
+
+        Ok(BufferData {
+            dtype: T::as_c_type_str().into(),
+            dims: header.shape,
+            data: elements_from_bytes(&bytes, big_endian),
+        })
+    }
+}
+
+impl<T: NpyElement> BufferData<T> {
+    /// Writes the data as a NumPy `.npy` file (format version 1.0), which can be read by `numpy.load`.
+    pub fn write_npy(&self, mut writer: impl Write) -> crate::Result<()> {
+        let byte_order = match () {
+            _ if core::mem::size_of::<T>() == 1 => '|',
+            _ if cfg!(target_endian = "big") => '>',
+            _ => '<',
+        };
+
+        let shape = match self.dims.as_slice() {
+            [dim] => format!("({dim},)"),
+            dims => format!(
+                "({})",
+                dims.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        let mut header = format!(
+            "{{'descr': '{byte_order}{}', 'fortran_order': False, 'shape': {shape}, }}",
+            T::DESCR
+        );
+
+        // the header is padded with spaces and terminated by a newline, so that the data is aligned to 64 bytes
+        let unpadded = NPY_MAGIC.len() + 2 + 2 + header.len() + 1;
+        header.extend(core::iter::repeat(' ').take((64 - unpadded % 64) % 64));
+        header.push('\n');
+
+        let header_len = u16::try_from(header.len()).map_err(|_| SerializeError::InvalidData)?;
+
+        let mut bytes =
+            Vec::with_capacity(unpadded + 64 + core::mem::size_of_val(self.data.as_slice()));
+        bytes.extend_from_slice(&NPY_MAGIC);
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&header_len.to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend(elements_to_bytes(&self.data, byte_order == '>'));
+
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+impl<'a, T, D, S> Buffer<'a, T, D, S>
+where
+    T: CDatatype + NpyElement,
+    D: Alloc<'a, T, S> + WriteBuf<T, S>,
+    S: Shape,
+{
+    /// Loads a `Buffer` from a NumPy `.npy` file.
+    ///
+    /// # Errors
+    /// Returns an error if reading fails, if the file is invalid
+    /// or if the data type or the shape do not match (see [`BufferData::read_npy`] and [`BufferData::to_buffer`]).
+    #[inline]
+    pub fn from_npy(device: &'a D, reader: impl io::Read) -> crate::Result<Buffer<'a, T, D, S>> {
+        BufferData::<T>::read_npy(reader)?.to_buffer(device)
+    }
+}
+
+impl<T, D, S> Buffer<'_, T, D, S>
+where
+    T: CDatatype + NpyElement + Default,
+    D: crate::Read<T, S>,
+    S: Shape,
+{
+    /// Saves the `Buffer` as a NumPy `.npy` file.
+    #[inline]
+    pub fn to_npy(&self, writer: impl io::Write) -> crate::Result<()> {
+        BufferData::from_buffer(self).write_npy(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialize::{BufferData, SerializeError};
+
+    #[test]
+    fn test_npy_layout() {
+        let data = BufferData {
+            dtype: "float".into(),
+            dims: vec![2, 3],
+            data: vec![1f32, 2., 3., 4., 5., 6.],
+        };
+
+        let mut bytes = Vec::new();
+        data.write_npy(&mut bytes).unwrap();
+
+        // the layout written by `numpy.save` for `np.arange(1, 7, dtype='<f4').reshape(2, 3)`
+        let header = "{'descr': '<f4', 'fortran_order': False, 'shape': (2, 3), }";
+        assert_eq!(&bytes[..10], b"\x93NUMPY\x01\x00\x76\x00");
+        assert_eq!(&bytes[10..10 + header.len()], header.as_bytes());
+        assert_eq!(bytes[127], b'\n');
+        assert_eq!(bytes.len(), 128 + 6 * 4);
+
+        assert_eq!(BufferData::<f32>::read_npy(&bytes[..]).unwrap(), data);
+    }
+
+    #[test]
+    fn test_npy_read_big_endian() {
+        let header = "{'shape': (3,), 'fortran_order': False, 'descr': '>i2'}\n";
+        let mut bytes = b"\x93NUMPY\x02\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for value in [1i16, -2, 300] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+
+        let data = BufferData::<i16>::read_npy(&bytes[..]).unwrap();
+        assert_eq!(data.dims, [3]);
+        assert_eq!(data.data, [1, -2, 300]);
+
+        let err = BufferData::<u16>::read_npy(&bytes[..]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&SerializeError::DtypeMismatch {
+                expected: "u2".into(),
+                found: ">i2".into()
+            })
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use std::io::{Read, Write};
+
+use crate::{Alloc, Buffer, CDatatype, Shape, WriteBuf};
+
+use super::{BufferData, NpyElement, SerializeError};
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR: u32 = 0x0605_4b50;
+const ZIP64_EXTRA: u16 = 0x0001;
+
+/// "Version needed to extract" of a stored zip entry (2.0)
+const ZIP_VERSION: u16 = 20;
+/// 1980-01-01, the earliest date that can be stored in a zip archive
+const ZIP_DATE: u16 = 0x21;
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut idx = 0;
+    while idx < 256 {
+        let mut crc = idx as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                1 => 0xEDB8_8320 ^ (crc >> 1),
+                _ => crc >> 1,
+            };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+}
+
+static CRC32_TABLE: [u32; 256] = crc32_table();
+
+/// The CRC-32 checksum used by zip archives.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, byte| {
+        CRC32_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Result<u16, SerializeError> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(SerializeError::InvalidData)
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, SerializeError> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(SerializeError::InvalidData)
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> Result<u64, SerializeError> {
+    bytes
+        .get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(SerializeError::InvalidData)
+}
+
+/// An entry of a `.npz` archive.
+#[derive(Debug, Clone)]
+struct NpzEntry {
+    name: String,
+    start: usize,
+    len: usize,
+}
+
+/// A NumPy `.npz` archive of named arrays, as written by `numpy.savez`.
+///
+/// The archive is read into memory. Its arrays are converted with [`Npz::data`] or [`Npz::load`].
+/// Compressed archives (`numpy.savez_compressed`) are not supported.
+///
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use custos::{serialize::{Npz, NpzWriter}, Buffer, Dim2, CPU};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = CPU::new();
+///     let weights = Buffer::<f32, _, Dim2<2, 2>>::from((&device, vec![1., 2., 3., 4.]));
+///
+///     let mut npz = NpzWriter::new(Vec::new());
+///     npz.add("weights", &weights)?;
+///     let bytes = npz.finish()?;
+///
+///     let npz = Npz::read(&bytes[..])?;
+///     let loaded: Buffer<f32, CPU, Dim2<2, 2>> = npz.load(&device, "weights")?;
+///     assert_eq!(loaded.read(), [1., 2., 3., 4.]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Npz {
+    bytes: Vec<u8>,
+    entries: Vec<NpzEntry>,
+}
+
+impl Npz {
+    /// Reads a `.npz` archive.
+    ///
+    /// # Errors
+    /// Returns an I/O error or a [`SerializeError`] if the archive is invalid, compressed or corrupted.
+    pub fn read(mut reader: impl Read) -> crate::Result<Npz> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        // the end of central directory record is followed by a comment of at most u16::MAX bytes
+        let search_start = bytes.len().saturating_sub(22 + u16::MAX as usize);
+        let end = (search_start..bytes.len().saturating_sub(21))
+            .rev()
+            .find(|offset| u32_at(&bytes, *offset) == Ok(END_OF_CENTRAL_DIR))
+            .ok_or(SerializeError::InvalidMagic)?;
+
+        let entry_count = u16_at(&bytes, end + 10)?;
+        let central_dir = u32_at(&bytes, end + 16)?;
+        if central_dir == u32::MAX {
+            return Err(SerializeError::Unsupported("zip64 archives".into()).into());
+        }
+
+        let mut offset = central_dir as usize;
+        let mut entries = Vec::with_capacity(entry_count as usize);
+
+        for _ in 0..entry_count {
+            if u32_at(&bytes, offset)? != CENTRAL_HEADER {
+                return Err(SerializeError::InvalidData.into());
+            }
+
+            let method = u16_at(&bytes, offset + 10)?;
+            let crc = u32_at(&bytes, offset + 16)?;
+            let mut compressed_len = u32_at(&bytes, offset + 20)? as u64;
+            let mut len = u32_at(&bytes, offset + 24)? as u64;
+            let name_len = u16_at(&bytes, offset + 28)? as usize;
+            let extra_len = u16_at(&bytes, offset + 30)? as usize;
+            let comment_len = u16_at(&bytes, offset + 32)? as usize;
+            let mut local_header = u32_at(&bytes, offset + 42)? as u64;
+
+            let name = bytes
+                .get(offset + 46..offset + 46 + name_len)
+                .ok_or(SerializeError::InvalidData)?;
+            let name = String::from_utf8(name.to_vec()).map_err(|_| SerializeError::InvalidData)?;
+
+            // sizes that do not fit into 32 bits are stored in the zip64 extra field
+            let mut extra = offset + 46 + name_len;
+            let extra_end = extra + extra_len;
+            while extra + 4 <= extra_end {
+                let id = u16_at(&bytes, extra)?;
+                let size = u16_at(&bytes, extra + 2)? as usize;
+
+                if id == ZIP64_EXTRA {
+                    let mut field = extra + 4;
+                    for value in [&mut len, &mut compressed_len, &mut local_header] {
+                        if *value == u32::MAX as u64 {
+                            *value = u64_at(&bytes, field)?;
+                            field += 8;
+                        }
+                    }
+                }
+                extra += 4 + size;
+            }
+
+            if method != 0 {
+                return Err(SerializeError::Unsupported("compressed npz archives".into()).into());
+            }
+            if compressed_len != len {
+                return Err(SerializeError::InvalidData.into());
+            }
+
+            let local_header =
+                usize::try_from(local_header).map_err(|_| SerializeError::InvalidData)?;
+            if u32_at(&bytes, local_header)? != LOCAL_HEADER {
+                return Err(SerializeError::InvalidData.into());
+            }
+
+            let start = local_header
+                + 30
+                + u16_at(&bytes, local_header + 26)? as usize
+                + u16_at(&bytes, local_header + 28)? as usize;
+            let len = usize::try_from(len).map_err(|_| SerializeError::InvalidData)?;
+
+            let data = start
+                .checked_add(len)
+                .and_then(|end| bytes.get(start..end))
+                .ok_or(SerializeError::InvalidData)?;
+            if crc32(data) != crc {
+                return Err(SerializeError::InvalidData.into());
+            }
+
+            let name = name.strip_suffix(".npy").unwrap_or(&name).to_string();
+            entries.push(NpzEntry { name, start, len });
+
+            offset += 46 + name_len + extra_len + comment_len;
+        }
+
+        Ok(Npz { bytes, entries })
+    }
+
+    /// Returns the names of the arrays in the archive, without the `.npy` extension.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.name.as_str())
+    }
+
+    /// Reads the array with the given name.
+    /// Use this function to retrieve the dimensions of arrays whose shape is not known at compile time.
+    ///
+    /// # Errors
+    /// Returns [`SerializeError::MissingEntry`] if the archive does not contain the array,
+    /// or an error of [`BufferData::read_npy`].
+    pub fn data<T: NpyElement + CDatatype>(&self, name: &str) -> crate::Result<BufferData<T>> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| SerializeError::MissingEntry(name.into()))?;
+
+        BufferData::read_npy(&self.bytes[entry.start..entry.start + entry.len])
+    }
+
+    /// Loads the array with the given name into a new [`Buffer`] on `device`.
+    ///
+    /// # Errors
+    /// See [`Npz::data`] and [`BufferData::to_buffer`].
+    #[inline]
+    pub fn load<'a, T, D, S>(&self, device: &'a D, name: &str) -> crate::Result<Buffer<'a, T, D, S>>
+    where
+        T: NpyElement + CDatatype,
+        D: Alloc<'a, T, S> + WriteBuf<T, S>,
+        S: Shape,
+    {
+        self.data::<T>(name)?.to_buffer(device)
+    }
+}
+
+/// Writes named arrays into a NumPy `.npz` archive, which can be read by `numpy.load`.
+///
+/// The arrays are stored uncompressed, like `numpy.savez` does.
+pub struct NpzWriter<W> {
+    writer: W,
+    offset: u64,
+    central_dir: Vec<u8>,
+    entry_count: u16,
+}
+
+impl<W: Write> NpzWriter<W> {
+    /// Creates a new archive that is written to `writer`.
+    pub fn new(writer: W) -> NpzWriter<W> {
+        NpzWriter {
+            writer,
+            offset: 0,
+            central_dir: Vec::new(),
+            entry_count: 0,
+        }
+    }
+
+    /// Adds the contents of `buf` as the array `name`.
+    #[inline]
+    pub fn add<T, D, S>(&mut self, name: &str, buf: &Buffer<T, D, S>) -> crate::Result<()>
+    where
+        T: NpyElement + CDatatype + Default,
+        D: crate::Read<T, S>,
+        S: Shape,
+    {
+        self.add_data(name, &BufferData::from_buffer(buf))
+    }
+
+    /// Adds `data` as the array `name`.
+    ///
+    /// # Errors
+    /// Returns an I/O error or [`SerializeError::Unsupported`] if the archive would exceed the limits of a zip archive without zip64 extensions.
+    pub fn add_data<T: NpyElement>(
+        &mut self,
+        name: &str,
+        data: &BufferData<T>,
+    ) -> crate::Result<()> {
+        let mut npy = Vec::new();
+        data.write_npy(&mut npy)?;
+
+        let name = format!("{name}.npy");
+        let too_large = || SerializeError::Unsupported("npz archives larger than 4 GiB".into());
+
+        let len = u32::try_from(npy.len()).map_err(|_| too_large())?;
+        let offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+        let name_len = u16::try_from(name.len()).map_err(|_| SerializeError::InvalidData)?;
+        self.entry_count = self.entry_count.checked_add(1).ok_or_else(too_large)?;
+
+        let crc = crc32(&npy);
+
+        // the fields shared by the local and the central header, starting at "version needed to extract"
+        let mut fields = Vec::with_capacity(26);
+        fields.extend_from_slice(&ZIP_VERSION.to_le_bytes());
+        // flags, compression method (stored) and modification time
+        fields.extend_from_slice(&[0; 6]);
+        fields.extend_from_slice(&ZIP_DATE.to_le_bytes());
+        fields.extend_from_slice(&crc.to_le_bytes());
+        fields.extend_from_slice(&len.to_le_bytes());
+        fields.extend_from_slice(&len.to_le_bytes());
+        fields.extend_from_slice(&name_len.to_le_bytes());
+        // extra field length
+        fields.extend_from_slice(&[0; 2]);
+
+        let mut local = Vec::with_capacity(30 + name.len());
+        local.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+        local.extend_from_slice(&fields);
+        local.extend_from_slice(name.as_bytes());
+
+        self.writer.write_all(&local)?;
+        self.writer.write_all(&npy)?;
+
+        self.central_dir
+            .extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+        // version made by
+        self.central_dir
+            .extend_from_slice(&ZIP_VERSION.to_le_bytes());
+        self.central_dir.extend_from_slice(&fields);
+        // comment length, disk number, internal and external attributes
+        self.central_dir.extend_from_slice(&[0; 10]);
+        self.central_dir.extend_from_slice(&offset.to_le_bytes());
+        self.central_dir.extend_from_slice(name.as_bytes());
+
+        self.offset += (local.len() + npy.len()) as u64;
+        Ok(())
+    }
+
+    /// Writes the central directory of the archive and returns the underlying writer.
+    pub fn finish(mut self) -> crate::Result<W> {
+        let too_large = || SerializeError::Unsupported("npz archives larger than 4 GiB".into());
+
+        let central_dir_offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+        let central_dir_len = u32::try_from(self.central_dir.len()).map_err(|_| too_large())?;
+
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&END_OF_CENTRAL_DIR.to_le_bytes());
+        // number of this disk and of the disk with the central directory
+        end.extend_from_slice(&[0; 4]);
+        end.extend_from_slice(&self.entry_count.to_le_bytes());
+        end.extend_from_slice(&self.entry_count.to_le_bytes());
+        end.extend_from_slice(&central_dir_len.to_le_bytes());
+        end.extend_from_slice(&central_dir_offset.to_le_bytes());
+        // comment length
+        end.extend_from_slice(&[0; 2]);
+
+        self.writer.write_all(&self.central_dir)?;
+        self.writer.write_all(&end)?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crc32;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
@@ -0,0 +1,543 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+use crate::{Alloc, Buffer, CDatatype, Shape, WriteBuf};
+
+use super::{
+    elements_from_bytes, elements_to_bytes, read_array, BinaryElement, BufferData, SerializeError,
+};
+
+/// The largest header that is read, as in the reference implementation.
+const MAX_HEADER_LEN: u64 = 100_000_000;
+
+/// An element type that can be stored in a safetensors file.
+pub trait SafeTensorsElement: BinaryElement {
+    /// The safetensors data type, e.g. `F32`.
+    const DTYPE: &'static str;
+}
+
+macro_rules! impl_safetensors_element {
+    ($($t:ty: $dtype:literal),*) => {
+        $(
+            impl SafeTensorsElement for $t {
+                const DTYPE: &'static str = $dtype;
+            }
+        )*
+    };
+}
+
+impl_safetensors_element!(
+    f32: "F32", f64: "F64", i8: "I8", u8: "U8", i16: "I16", u16: "U16",
+    i32: "I32", u32: "U32", i64: "I64", u64: "U64", bool: "BOOL"
+);
+
+#[cfg(feature = "half")]
+impl_safetensors_element!(half::f16: "F16", half::bf16: "BF16");
+
+/// The header entry of a tensor.
+#[derive(Debug, Clone)]
+struct TensorInfo {
+    name: String,
+    dtype: String,
+    shape: Vec<usize>,
+    offsets: (usize, usize),
+}
+
+/// A safetensors file of named tensors.
+///
+/// The file is read into memory. Its tensors are converted with [`SafeTensors::data`] or [`SafeTensors::load`].
+///
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use custos::{serialize::{SafeTensors, SafeTensorsWriter}, Buffer, Dim2, CPU};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = CPU::new();
+///     let weights = Buffer::<f32, _, Dim2<2, 2>>::from((&device, vec![1., 2., 3., 4.]));
+///
+///     let mut tensors = SafeTensorsWriter::new(Vec::new());
+///     tensors.add("weights", &weights);
+///     let bytes = tensors.finish()?;
+///
+///     let tensors = SafeTensors::read(&bytes[..])?;
+///     let loaded: Buffer<f32, CPU, Dim2<2, 2>> = tensors.load(&device, "weights")?;
+///     assert_eq!(loaded.read(), [1., 2., 3., 4.]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SafeTensors {
+    data: Vec<u8>,
+    tensors: Vec<TensorInfo>,
+    metadata: HashMap<String, String>,
+}
+
+impl SafeTensors {
+    /// Reads a safetensors file.
+    ///
+    /// # Errors
+    /// Returns an I/O error or a [`SerializeError`] if the file is invalid.
+    pub fn read(mut reader: impl Read) -> crate::Result<SafeTensors> {
+        let header_len = u64::from_le_bytes(read_array(&mut reader)?);
+        if header_len > MAX_HEADER_LEN {
+            return Err(SerializeError::InvalidData.into());
+        }
+
+        let mut header = Vec::new();
+        (&mut reader).take(header_len).read_to_end(&mut header)?;
+        let header = String::from_utf8(header).map_err(|_| SerializeError::InvalidData)?;
+
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        let mut parser = JsonParser {
+            json: header.trim_end().as_bytes(),
+            pos: 0,
+        };
+        let header = parser.parse_object(|parser, key| {
+            let value = match key.as_str() {
+                "__metadata__" => {
+                    Header::Metadata(parser.parse_object(|parser, _| parser.parse_string())?)
+                }
+                _ => Header::Tensor(parser.parse_tensor_info(key)?),
+            };
+            Ok(value)
+        })?;
+        if parser.pos != parser.json.len() {
+            return Err(SerializeError::InvalidData.into());
+        }
+
+        let mut tensors = Vec::with_capacity(header.len());
+        let mut metadata = HashMap::new();
+        for (_, value) in header {
+            match value {
+                Header::Metadata(values) => metadata.extend(values),
+                Header::Tensor(info) => {
+                    if info.offsets.0 > info.offsets.1 || info.offsets.1 > data.len() {
+                        return Err(SerializeError::InvalidData.into());
+                    }
+                    tensors.push(info)
+                }
+            }
+        }
+
+        Ok(SafeTensors {
+            data,
+            tensors,
+            metadata,
+        })
+    }
+
+    /// Returns the names of the tensors in the file.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.tensors.iter().map(|info| info.name.as_str())
+    }
+
+    /// Returns the `__metadata__` of the file.
+    #[inline]
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// Reads the tensor with the given name.
+    /// Use this function to retrieve the dimensions of tensors whose shape is not known at compile time.
+    ///
+    /// # Errors
+    /// Returns [`SerializeError::MissingEntry`] if the file does not contain the tensor,
+    /// [`SerializeError::DtypeMismatch`] if the data type of the tensor differs from `T`
+    /// or [`SerializeError::InvalidData`] if the data does not match the shape.
+    pub fn data<T>(&self, name: &str) -> crate::Result<BufferData<T>>
+    where
+        T: SafeTensorsElement + CDatatype,
+    {
+        let info = self
+            .tensors
+            .iter()
+            .find(|info| info.name == name)
+            .ok_or_else(|| SerializeError::MissingEntry(name.into()))?;
+
+        if info.dtype != T::DTYPE {
+            return Err(SerializeError::DtypeMismatch {
+                expected: T::DTYPE.into(),
+                found: info.dtype.clone(),
+            }
+            .into());
+        }
+
+        let bytes = &self.data[info.offsets.0..info.offsets.1];
+        let len = info
+            .shape
+            .iter()
+            .try_fold(core::mem::size_of::<T>(), |len, dim| len.checked_mul(*dim));
+        if len != Some(bytes.len()) {
+            return Err(SerializeError::InvalidData.into());
+        }
+
+        Ok(BufferData {
+            dtype: T::as_c_type_str().into(),
+            dims: info.shape.clone(),
+            data: elements_from_bytes(bytes, false),
+        })
+    }
+
+    /// Loads the tensor with the given name into a new [`Buffer`] on `device`.
+    ///
+    /// # Errors
+    /// See [`SafeTensors::data`] and [`BufferData::to_buffer`].
+    #[inline]
+    pub fn load<'a, T, D, S>(&self, device: &'a D, name: &str) -> crate::Result<Buffer<'a, T, D, S>>
+    where
+        T: SafeTensorsElement + CDatatype,
+        D: Alloc<'a, T, S> + WriteBuf<T, S>,
+        S: Shape,
+    {
+        self.data::<T>(name)?.to_buffer(device)
+    }
+}
+
+/// Collects named tensors and writes them as a safetensors file.
+pub struct SafeTensorsWriter<W> {
+    writer: W,
+    header: String,
+    data: Vec<u8>,
+    metadata: Vec<(String, String)>,
+}
+
+impl<W: Write> SafeTensorsWriter<W> {
+    /// Creates a new safetensors file that is written to `writer` in [`SafeTensorsWriter::finish`].
+    pub fn new(writer: W) -> SafeTensorsWriter<W> {
+        SafeTensorsWriter {
+            writer,
+            header: String::new(),
+            data: Vec::new(),
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Adds the contents of `buf` as the tensor `name`.
+    #[inline]
+    pub fn add<T, D, S>(&mut self, name: &str, buf: &Buffer<T, D, S>)
+    where
+        T: SafeTensorsElement + CDatatype + Default,
+        D: crate::Read<T, S>,
+        S: Shape,
+    {
+        self.add_data(name, &BufferData::from_buffer(buf))
+    }
+
+    /// Adds `data` as the tensor `name`.
+    pub fn add_data<T: SafeTensorsElement>(&mut self, name: &str, data: &BufferData<T>) {
+        let start = self.data.len();
+        self.data.extend(elements_to_bytes(&data.data, false));
+
+        let shape = data
+            .dims
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        if !self.header.is_empty() {
+            self.header.push(',');
+        }
+        self.header.push_str(&format!(
+            "{}:{{\"dtype\":\"{}\",\"shape\":[{shape}],\"data_offsets\":[{start},{}]}}",
+            json_string(name),
+            T::DTYPE,
+            self.data.len()
+        ));
+    }
+
+    /// Adds an entry to the `__metadata__` of the file.
+    pub fn add_metadata(&mut self, key: &str, value: &str) {
+        self.metadata.push((json_string(key), json_string(value)));
+    }
+
+    /// Writes the file and returns the underlying writer.
+    pub fn finish(mut self) -> crate::Result<W> {
+        let mut header = String::from("{");
+        if !self.metadata.is_empty() {
+            let metadata = self
+                .metadata
+                .iter()
+                .map(|(key, value)| format!("{key}:{value}"))
+                .collect::<Vec<_>>()
+                .join(",");
+            header.push_str(&format!("\"__metadata__\":{{{metadata}}}"));
+
+            if !self.header.is_empty() {
+                header.push(',');
+            }
+        }
+        header.push_str(&self.header);
+        header.push('}');
+
+        // the header is padded with spaces, so that the data is aligned to 8 bytes
+        header.extend(core::iter::repeat(' ').take((8 - header.len() % 8) % 8));
+
+        self.writer
+            .write_all(&(header.len() as u64).to_le_bytes())?;
+        self.writer.write_all(header.as_bytes())?;
+        self.writer.write_all(&self.data)?;
+        Ok(self.writer)
+    }
+}
+
+/// Returns `value` as a quoted JSON string.
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+enum Header {
+    Metadata(Vec<(String, String)>),
+    Tensor(TensorInfo),
+}
+
+/// A parser for the subset of JSON used by safetensors headers.
+struct JsonParser<'a> {
+    json: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while matches!(self.json.get(self.pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes `byte` after optional whitespace. Returns `false` if the next byte is different.
+    fn consume(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.json.get(self.pos) == Some(&byte);
+        self.pos += found as usize;
+        found
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), SerializeError> {
+        match self.consume(byte) {
+            true => Ok(()),
+            false => Err(SerializeError::InvalidData),
+        }
+    }
+
+    /// Parses a JSON string.
+    fn parse_string(&mut self) -> Result<String, SerializeError> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+
+        loop {
+            let byte = *self.json.get(self.pos).ok_or(SerializeError::InvalidData)?;
+            self.pos += 1;
+
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = *self.json.get(self.pos).ok_or(SerializeError::InvalidData)?;
+                    self.pos += 1;
+
+                    let c = match escaped {
+                        b'"' | b'\\' | b'/' => escaped as char,
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let code = self
+                                .json
+                                .get(self.pos..self.pos + 4)
+                                .and_then(|hex| core::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .ok_or(SerializeError::InvalidData)?;
+                            self.pos += 4;
+                            // surrogate pairs are not valid chars and are replaced
+                            char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        _ => return Err(SerializeError::InvalidData),
+                    };
+                    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                byte => bytes.push(byte),
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| SerializeError::InvalidData)
+    }
+
+    /// Parses a non-negative JSON integer.
+    fn parse_usize(&mut self) -> Result<usize, SerializeError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while matches!(self.json.get(self.pos), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+
+        core::str::from_utf8(&self.json[start..self.pos])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or(SerializeError::InvalidData)
+    }
+
+    /// Parses a JSON array of non-negative integers.
+    fn parse_usizes(&mut self) -> Result<Vec<usize>, SerializeError> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        if self.consume(b']') {
+            return Ok(values);
+        }
+
+        loop {
+            values.push(self.parse_usize()?);
+            if self.consume(b']') {
+                return Ok(values);
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    /// Parses a JSON object, whose values are parsed by `parse_value`.
+    fn parse_object<V>(
+        &mut self,
+        mut parse_value: impl FnMut(&mut Self, String) -> Result<V, SerializeError>,
+    ) -> Result<Vec<(String, V)>, SerializeError> {
+        self.expect(b'{')?;
+        let mut entries = Vec::new();
+        if self.consume(b'}') {
+            return Ok(entries);
+        }
+
+        loop {
+            let key = self.parse_string()?;
+            self.expect(b':')?;
+            let value = parse_value(self, key.clone())?;
+            entries.push((key, value));
+
+            if self.consume(b'}') {
+                return Ok(entries);
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn parse_tensor_info(&mut self, name: String) -> Result<TensorInfo, SerializeError> {
+        let mut dtype = None;
+        let mut shape = None;
+        let mut offsets = None;
+
+        self.parse_object(|parser, key| {
+            match key.as_str() {
+                "dtype" => dtype = Some(parser.parse_string()?),
+                "shape" => shape = Some(parser.parse_usizes()?),
+                "data_offsets" => match parser.parse_usizes()?.as_slice() {
+                    [start, end] => offsets = Some((*start, *end)),
+                    _ => return Err(SerializeError::InvalidData),
+                },
+                _ => return Err(SerializeError::InvalidData),
+            }
+            Ok(())
+        })?;
+
+        Ok(TensorInfo {
+            name,
+            dtype: dtype.ok_or(SerializeError::InvalidData)?,
+            shape: shape.ok_or(SerializeError::InvalidData)?,
+            offsets: offsets.ok_or(SerializeError::InvalidData)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialize::{BufferData, SafeTensors, SafeTensorsWriter, SerializeError};
+
+    #[test]
+    fn test_safetensors_read() {
+        // a file as written by the reference implementation
+        let header = r#"{"__metadata__":{"format":"pt"},"b":{"dtype":"I64","shape":[],"data_offsets":[16,24]},"a \"x\"":{"dtype":"F32","shape":[2, 2],"data_offsets":[0,16]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        for value in [1f32, 2., 3., 4.] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&(-7i64).to_le_bytes());
+
+        let tensors = SafeTensors::read(&bytes[..]).unwrap();
+        assert_eq!(tensors.names().collect::<Vec<_>>(), ["b", "a \"x\""]);
+        assert_eq!(tensors.metadata()["format"], "pt");
+
+        let a = tensors.data::<f32>("a \"x\"").unwrap();
+        assert_eq!(a.dims, [2, 2]);
+        assert_eq!(a.data, [1., 2., 3., 4.]);
+
+        let b = tensors.data::<i64>("b").unwrap();
+        assert!(b.dims.is_empty());
+        assert_eq!(b.data, [-7]);
+
+        let err = tensors.data::<f64>("a \"x\"").unwrap_err();
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&SerializeError::DtypeMismatch {
+                expected: "F64".into(),
+                found: "F32".into()
+            })
+        );
+
+        let err = tensors.data::<f32>("c").unwrap_err();
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&SerializeError::MissingEntry("c".into()))
+        );
+    }
+
+    #[test]
+    fn test_safetensors_write() {
+        let data = BufferData {
+            dtype: "uint".into(),
+            dims: vec![3],
+            data: vec![1u32, 2, 3],
+        };
+
+        let mut writer = SafeTensorsWriter::new(Vec::new());
+        writer.add_metadata("format", "custos");
+        writer.add_data("x", &data);
+        let bytes = writer.finish().unwrap();
+
+        let header_len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+        assert_eq!(header_len % 8, 0);
+        assert_eq!(
+            std::str::from_utf8(&bytes[8..8 + header_len])
+                .unwrap()
+                .trim_end(),
+            r#"{"__metadata__":{"format":"custos"},"x":{"dtype":"U32","shape":[3],"data_offsets":[0,12]}}"#
+        );
+
+        let tensors = SafeTensors::read(&bytes[..]).unwrap();
+        assert_eq!(tensors.data::<u32>("x").unwrap(), data);
+    }
+
+    #[test]
+    fn test_safetensors_invalid() {
+        let header = r#"{"a":{"dtype":"F32","shape":[1],"data_offsets":[0,8]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+
+        let err = SafeTensors::read(&bytes[..]).unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&SerializeError::InvalidData));
+    }
+}
@@ -5,6 +5,8 @@ use crate::{flag::AllocFlag, Device, PtrConv};
 pub trait Shape: 'static {
     /// The count of elements that fit into the shape.
     const LEN: usize = 0;
+    /// The dimensions of the shape, outermost first. Empty if the shape is not known at compile time.
+    const DIMS: &'static [usize] = &[];
     /// The type of the ND-Array.
     type ARR<T>;
 
@@ -38,6 +40,7 @@ impl<const N: usize> IsConstDim for Dim1<N> {}
 
 impl<const N: usize> Shape for Dim1<N> {
     const LEN: usize = N;
+    const DIMS: &'static [usize] = &[N];
     type ARR<T> = [T; N];
 
     #[inline]
@@ -54,6 +57,7 @@ impl<const B: usize, const A: usize> IsConstDim for Dim2<B, A> {}
 
 impl<const B: usize, const A: usize> Shape for Dim2<B, A> {
     const LEN: usize = B * A;
+    const DIMS: &'static [usize] = &[B, A];
     type ARR<T> = [[T; A]; B];
 
     #[inline]
@@ -62,6 +66,40 @@ impl<const B: usize, const A: usize> Shape for Dim2<B, A> {
     }
 }
 
+/// Evaluating [`AssertSameLen::OK`] fails to build if `I` and `O` do not have the same [`Shape::LEN`].
+pub(crate) struct AssertSameLen<I, O>(core::marker::PhantomData<(I, O)>);
+
+impl<I: Shape, O: Shape> AssertSameLen<I, O> {
+    pub(crate) const OK: () = assert!(
+        I::LEN == O::LEN,
+        "The shapes must contain the same amount of elements."
+    );
+}
+
+/// Evaluating [`AssertNonEmpty::OK`] fails to build if `S` does not contain any elements.
+pub(crate) struct AssertNonEmpty<S>(core::marker::PhantomData<S>);
+
+impl<S: Shape> AssertNonEmpty<S> {
+    pub(crate) const OK: () = assert!(S::LEN > 0, "The shape must contain at least one element.");
+}
+
+/// Evaluating [`AssertConvertible::OK`] fails to build if a buffer of shape `I` cannot be viewed as shape `O`.
+/// Any shape converts to `()`. Converting from `()` to a const shape is only checkable at runtime.
+pub(crate) struct AssertConvertible<I, O>(core::marker::PhantomData<(I, O)>);
+
+impl<I: Shape, O: Shape> AssertConvertible<I, O> {
+    pub(crate) const OK: () = assert!(
+        O::DIMS.is_empty() || (!I::DIMS.is_empty() && I::LEN == O::LEN),
+        "The shapes must contain the same amount of elements. Use a `try_` conversion for dynamic shapes."
+    );
+}
+
+/// Returns `true` if a buffer with `len` elements can be viewed as shape `O`.
+#[inline]
+pub(crate) fn fits_shape<O: Shape>(len: usize) -> bool {
+    O::DIMS.is_empty() || len == O::LEN
+}
+
 /// The shape may be 2D or ().
 pub trait MayDim2<const A: usize, const B: usize>: Shape {}
 
@@ -77,6 +115,7 @@ impl<const C: usize, const B: usize, const A: usize> IsConstDim for Dim3<C, B, A
 
 impl<const C: usize, const B: usize, const A: usize> Shape for Dim3<C, B, A> {
     const LEN: usize = B * A * C;
+    const DIMS: &'static [usize] = &[C, B, A];
     type ARR<T> = [[[T; A]; B]; C];
 
     #[inline]
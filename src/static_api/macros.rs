@@ -9,10 +9,50 @@
 /// assert_eq!(buf.read(), [2.; 10]);
 ///
 /// let buf = buf![5, 3, 2, 6, 2];
-/// assert_eq!(buf.read(), &[5, 3, 2, 6, 2])
+/// assert_eq!(buf.read(), &[5, 3, 2, 6, 2]);
+///
+/// // the following buffers are filled on the device
+/// let buf: custos::Buffer<f32> = buf![ones; 3];
+/// assert_eq!(buf.read(), [1.; 3]);
+///
+/// let buf = buf![arange(0, 6, 2)];
+/// assert_eq!(buf.read(), [0, 2, 4]);
+///
+/// let buf = buf![linspace(0., 1., 5)];
+/// assert_eq!(buf.read(), [0., 0.25, 0.5, 0.75, 1.]);
+///
+/// let buf: custos::Buffer<f32, _, custos::Dim2<2, 2>> = buf![eye; 2];
+/// assert_eq!(buf.read(), [1., 0., 0., 1.]);
 /// ```
 #[macro_export]
 macro_rules! buf {
+    (ones; $n:expr) => (
+        $crate::Buffer::<_, $crate::CPU>::ones($crate::static_api::static_cpu(), $n)
+    );
+
+    (eye; $n:expr) => (
+        $crate::Buffer::<_, $crate::CPU, $crate::Dim2<{ $n }, { $n }>>::eye(
+            $crate::static_api::static_cpu()
+        )
+    );
+
+    (arange($start:expr, $end:expr)) => (
+        $crate::Buffer::<_, $crate::CPU>::arange(
+            $crate::static_api::static_cpu(),
+            $start,
+            $end,
+            $crate::number::One::one(),
+        )
+    );
+
+    (arange($start:expr, $end:expr, $step:expr)) => (
+        $crate::Buffer::<_, $crate::CPU>::arange($crate::static_api::static_cpu(), $start, $end, $step)
+    );
+
+    (linspace($start:expr, $end:expr, $n:expr)) => (
+        $crate::Buffer::<_, $crate::CPU>::linspace($crate::static_api::static_cpu(), $start, $end, $n)
+    );
+
     ($elem:expr; $n:expr) => (
         if $n == 0 {
             panic!("The length of the buffer can't be 0.");
@@ -44,4 +84,22 @@ mod tests {
         let buf = buf![5, 3, 2, 6, 2];
         assert_eq!(buf.as_slice(), &[5, 3, 2, 6, 2])
     }
+
+    #[test]
+    fn test_macro_constructors() {
+        let buf: crate::Buffer<i32> = buf![ones; 4];
+        assert_eq!(buf.as_slice(), &[1i32; 4]);
+
+        let buf = buf![arange(1, 4)];
+        assert_eq!(buf.as_slice(), &[1, 2, 3]);
+
+        let buf = buf![arange(0., 1., 0.5)];
+        assert_eq!(buf.as_slice(), &[0., 0.5]);
+
+        let buf = buf![linspace(-1f32, 1., 3)];
+        assert_eq!(buf.as_slice(), &[-1., 0., 1.]);
+
+        let buf: crate::Buffer<i32, _, crate::Dim2<3, 3>> = buf![eye; 3];
+        assert_eq!(buf.as_slice(), &[1, 0, 0, 0, 1, 0, 0, 0, 1]);
+    }
 }
@@ -104,7 +104,7 @@ mod tests {
         let cached = cache
             .nodes
             .get(&Ident {
-                idx: 2,
+                idx: crate::thread_ident_offset() + 2,
                 len: out.len(),
             })
             .unwrap();
@@ -0,0 +1,159 @@
+use core::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+type PollFn<'a, R> = Box<dyn FnMut(bool) -> Option<crate::Result<R>> + 'a>;
+
+enum State<'a, R> {
+    Pending(PollFn<'a, R>),
+    Complete(Option<crate::Result<R>>),
+}
+
+/// A handle to a (possibly) unfinished read or write operation.
+/// It is returned by [`ReadAsync::read_async`](crate::ReadAsync::read_async) and [`WriteAsync::write_async`](crate::WriteAsync::write_async).
+///
+/// The host can do other work while the transfer is in flight.
+/// The result is retrieved by [`wait`](Transfer::wait)ing for it or by `.await`ing the transfer.
+/// As devices do not wake up the task, an awaited transfer is polled repeatedly until it is complete.
+///
+/// Dropping an unfinished transfer blocks until the device is done with the involved memory.
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use custos::{Buffer, ReadAsync, CPU};
+///
+/// let device = CPU::new();
+/// let buf = Buffer::from((&device, [1, 2, 3]));
+///
+/// let mut read = device.read_async(&buf);
+/// // ... do other work on the host
+/// assert!(read.is_complete());
+/// assert_eq!(read.wait().unwrap(), [1, 2, 3]);
+/// ```
+#[must_use = "a transfer does nothing unless it is waited for or awaited"]
+pub struct Transfer<'a, R> {
+    state: State<'a, R>,
+}
+
+impl<'a, R> Transfer<'a, R> {
+    /// Creates a transfer that is driven by `poll`.
+    ///
+    /// `poll(block)` returns `Some` with the result once the transfer is complete.
+    /// If `block` is `true`, it must block until the transfer is complete and return `Some`.
+    /// `poll` is not called anymore after it returned `Some`.
+    #[inline]
+    pub fn new(poll: impl FnMut(bool) -> Option<crate::Result<R>> + 'a) -> Transfer<'a, R> {
+        Transfer {
+            state: State::Pending(Box::new(poll)),
+        }
+    }
+
+    /// Creates a transfer that is already complete.
+    #[inline]
+    pub fn from_result(result: crate::Result<R>) -> Transfer<'a, R> {
+        Transfer {
+            state: State::Complete(Some(result)),
+        }
+    }
+
+    /// Creates a transfer that completed successfully with `value`.
+    #[inline]
+    pub fn ready(value: R) -> Transfer<'a, R> {
+        Transfer::from_result(Ok(value))
+    }
+
+    fn poll_transfer(&mut self, block: bool) -> bool {
+        if let State::Pending(poll) = &mut self.state {
+            match poll(block) {
+                Some(result) => self.state = State::Complete(Some(result)),
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Returns `true` if the transfer is complete. This does not block.
+    #[inline]
+    pub fn is_complete(&mut self) -> bool {
+        self.poll_transfer(false)
+    }
+
+    /// Blocks until the transfer is complete and returns its result.
+    pub fn wait(mut self) -> crate::Result<R> {
+        self.poll_transfer(true);
+
+        match core::mem::replace(&mut self.state, State::Complete(None)) {
+            State::Complete(Some(result)) => result,
+            _ => unreachable!("A blocking poll must complete the transfer."),
+        }
+    }
+}
+
+// The result is never pinned.
+impl<R> Unpin for Transfer<'_, R> {}
+
+impl<R> Future for Transfer<'_, R> {
+    type Output = crate::Result<R>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if !self.poll_transfer(false) {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        match &mut self.state {
+            State::Complete(result) => {
+                Poll::Ready(result.take().expect("Transfer polled after completion."))
+            }
+            State::Pending(_) => unreachable!(),
+        }
+    }
+}
+
+impl<R> Drop for Transfer<'_, R> {
+    fn drop(&mut self) {
+        self.poll_transfer(true);
+    }
+}
+
+impl<R> Debug for Transfer<'_, R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let complete = matches!(self.state, State::Complete(_));
+        f.debug_struct("Transfer")
+            .field("complete", &complete)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transfer;
+
+    #[test]
+    fn test_transfer_completes_after_polls() {
+        let mut polls = 0;
+        let mut transfer = Transfer::new(|block| {
+            polls += 1;
+            (block || polls == 3).then(|| Ok(polls))
+        });
+
+        assert!(!transfer.is_complete());
+        assert!(!transfer.is_complete());
+        assert!(transfer.is_complete());
+        assert!(transfer.is_complete());
+        assert_eq!(transfer.wait().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_transfer_drop_blocks() {
+        let mut blocked = false;
+        drop(Transfer::new(|block| {
+            blocked = block;
+            block.then(|| Ok(()))
+        }));
+        assert!(blocked);
+    }
+}
@@ -0,0 +1,114 @@
+use core::array;
+
+use super::{Eval, Resolve};
+
+/// The number of elements that are evaluated at once by [`eval_in_lanes`].
+/// Eight lanes fill a 256 bit register with `f32`s (AVX2) or two 128 bit registers (SSE, NEON).
+pub const LANES: usize = 8;
+
+/// The marker of the argument of an operation chain that is evaluated lane-wise (see [`Eval::eval_lanes`]).
+pub const LANE_MARKER: &str = "\0lanes";
+
+/// Evaluates `f` for every element of `x`.
+///
+/// `f` is called once with an argument marked with [`LANE_MARKER`].
+/// The resulting operation chain is then evaluated for chunks of [`LANES`] elements via [`Eval::eval_lanes`]:
+/// every operation of the chain is applied to all lanes at once, hence the compiler emits SIMD instructions for the arithmetic operations and comparisons.
+/// Chains that cannot be evaluated lane-wise fall back to evaluating every element on its own.
+///
+/// Like for the kernels of the GPU devices, `f` must build its result from its argument with the [`Combiner`](crate::Combiner) operations.
+/// A value computed from [`Resolve::val`] is treated as a constant.
+///
+/// `write` receives the offset and the results of every chunk.
+/// # Example
+/// ```
+/// use custos::{eval_in_lanes, Combiner};
+///
+/// let x = [1., 2., 3., 4., 5., 6., 7., 8., 9., 10.];
+/// let mut out = [0.; 10];
+///
+/// eval_in_lanes(&x, |x| x.mul(2.).add(1.), |offset, values| {
+///     out[offset..offset + values.len()].copy_from_slice(values)
+/// });
+///
+/// assert_eq!(out, [3., 5., 7., 9., 11., 13., 15., 17., 19., 21.]);
+/// ```
+#[inline]
+pub fn eval_in_lanes<T, F>(x: &[T], f: impl Fn(Resolve<T>) -> F, mut write: impl FnMut(usize, &[T]))
+where
+    T: Copy,
+    F: Eval<T>,
+{
+    let Some(first) = x.first() else {
+        return;
+    };
+
+    let chain = f(Resolve {
+        val: *first,
+        marker: LANE_MARKER,
+    });
+
+    let chunks = x.chunks_exact(LANES);
+    let remainder = chunks.remainder();
+
+    for (idx, chunk) in chunks.enumerate() {
+        let lanes: &[T; LANES] = chunk.try_into().unwrap();
+        let values = chain
+            .eval_lanes(lanes)
+            .unwrap_or_else(|| array::from_fn(|lane| f(Resolve::with_val(lanes[lane])).eval()));
+        write(idx * LANES, &values);
+    }
+
+    let offset = x.len() - remainder.len();
+    for (idx, x) in remainder.iter().enumerate() {
+        write(offset + idx, &[f(Resolve::with_val(*x)).eval()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::array;
+
+    use crate::{eval_in_lanes, Combiner, Eval, Resolve, LANES};
+
+    fn lanes_eq_scalar<F: Eval<f32>>(f: impl Fn(Resolve<f32>) -> F) {
+        let x: [f32; 27] = array::from_fn(|x| x as f32 / 3. - 4.);
+
+        let mut out = [0.; 27];
+        eval_in_lanes(&x, &f, |offset, values| {
+            out[offset..offset + values.len()].copy_from_slice(values)
+        });
+
+        for (x, out) in x.iter().zip(&out) {
+            assert_eq!(f(Resolve::with_val(*x)).eval().to_bits(), out.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_lanes_eq_scalar() {
+        lanes_eq_scalar(|x| x.mul(2.).add(x).sub(1.).div(3.));
+        lanes_eq_scalar(|x| x.sin().mul(x.cos()).add(x.tan().neg()));
+        lanes_eq_scalar(|x| x.exp().pow(x.mul(0.1)));
+        lanes_eq_scalar(|x| x.geq(0.).mul(x).add(x.leq(1.)).add(x.eq(-3.)));
+        lanes_eq_scalar(|_| 3.);
+    }
+
+    #[test]
+    fn test_chain_is_evaluated_lane_wise() {
+        let x: [f32; LANES] = array::from_fn(|x| x as f32);
+        let captured = Resolve::with_val(2.);
+
+        let chain = Resolve::with_marker(crate::LANE_MARKER)
+            .mul(captured)
+            .add(1.);
+        assert_eq!(
+            chain.eval_lanes(&x),
+            Some([1., 3., 5., 7., 9., 11., 13., 15.])
+        );
+    }
+
+    #[test]
+    fn test_eval_in_lanes_empty() {
+        eval_in_lanes(&[] as &[f32], |x| x.add(1.), |_, _| unreachable!());
+    }
+}
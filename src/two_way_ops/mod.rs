@@ -1,6 +1,8 @@
+mod lanes;
 mod ops;
 mod resolve;
 
+pub use lanes::*;
 pub use resolve::*;
 
 use self::ops::{Add, Cos, Div, Eq, Exp, GEq, LEq, Mul, Neg, Pow, Sin, Sub, Tan};
@@ -10,13 +12,24 @@ use self::ops::{Add, Cos, Div, Eq, Exp, GEq, LEq, Mul, Neg, Pow, Sin, Sub, Tan};
 pub trait ToCLSource {
     /// Evaluates a combined (via [`Combiner`]) math operations chain to a valid OpenCL C (and possibly CUDA) source string.
     fn to_cl_source(&self) -> String;
+
+    /// Returns the C vector type (e.g. `float2`), if the expression evaluates to a complex number.
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        None
+    }
 }
 
 #[cfg(not(feature = "no-std"))]
 impl<N: crate::number::Numeric> ToCLSource for N {
     #[inline]
     fn to_cl_source(&self) -> String {
-        self.to_string()
+        self.to_cl_literal()
+    }
+
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        N::complex_c_type()
     }
 }
 
@@ -47,6 +60,15 @@ pub trait Eval<T> {
     /// assert_eq!(x, 14.);
     /// ```
     fn eval(self) -> T;
+
+    /// Evaluates the operation chain for all [`LANES`] at once, see [`eval_in_lanes`].
+    /// The argument of the chain, marked with [`LANE_MARKER`], takes the values of `x`.
+    ///
+    /// Returns `None` if the chain cannot be evaluated lane-wise. Then, every lane is evaluated with [`eval`](Eval::eval).
+    #[inline]
+    fn eval_lanes(&self, _x: &[T; LANES]) -> Option<[T; LANES]> {
+        None
+    }
 }
 
 impl<T: Copy> Eval<T> for T {
@@ -54,6 +76,11 @@ impl<T: Copy> Eval<T> for T {
     fn eval(self) -> T {
         self
     }
+
+    #[inline]
+    fn eval_lanes(&self, _x: &[T; LANES]) -> Option<[T; LANES]> {
+        Some([*self; LANES])
+    }
 }
 
 /// A trait that allows combining math operations.
@@ -331,6 +358,127 @@ mod tests {
         assert_eq!(buf.read(), &[6, 6, 7, 8, 6, 5]);
     }
 
+    #[cfg(all(feature = "cpu", feature = "macro", feature = "half"))]
+    #[test]
+    fn test_apply_fn_cpu_f16() {
+        use crate::{ApplyFunction, Buffer, Combiner, CPU};
+        use half::f16;
+
+        let device = CPU::new();
+
+        let mut buf = Buffer::<f16>::new(&device, 4);
+        buf.write_f32(&[1., 2.5, -3., 0.5]);
+
+        let buf = device.apply_fn(&buf, |x| x.mul(f16::from_f32(2.)).add(f16::from_f32(1.)));
+        assert_eq!(buf.read_f32(), [3., 6., -5., 2.]);
+    }
+
+    #[cfg(not(feature = "no-std"))]
+    #[test]
+    fn test_complex_cl_source() {
+        use crate::{number::Complex, Combiner, Resolve, ToCLSource};
+
+        let x = Resolve::<Complex<f32>>::with_marker("x");
+        let src = x
+            .mul(x)
+            .div(Complex::new(1f32, 2.))
+            .add(x)
+            .mul(2.)
+            .to_cl_source();
+
+        assert_eq!(
+            src,
+            "float2_mul(float2_add(float2_div(float2_mul(x, x), float2_new(1, 2)), x), float2_new(2, 0))"
+        );
+    }
+
+    #[cfg(not(feature = "no-std"))]
+    #[test]
+    fn test_complex_cl_source_real_operand() {
+        use crate::{number::Complex, Combiner, Resolve, ToCLSource};
+
+        let x = Resolve::<Complex<f32>>::with_marker("x");
+        let real = Resolve::<f32>::with_marker("y");
+
+        assert_eq!(
+            x.exp().sub(2.).sin().to_cl_source(),
+            "float2_sin(float2_sub(float2_exp(x), float2_new(2, 0)))"
+        );
+        assert_eq!(
+            real.add(x.tan()).to_cl_source(),
+            "float2_add(float2_new(y, 0), float2_tan(x))"
+        );
+        assert_eq!(
+            real.div(x).neg().to_cl_source(),
+            "float2_neg(float2_div(float2_new(y, 0), x))"
+        );
+        assert_eq!(real.div(2.).to_cl_source(), "(y / 2)");
+    }
+
+    #[cfg(not(feature = "no-std"))]
+    #[test]
+    #[should_panic(expected = "pow is not supported for complex numbers")]
+    fn test_complex_cl_source_pow() {
+        use crate::{number::Complex, Combiner, Resolve, ToCLSource};
+
+        let x = Resolve::<Complex<f32>>::with_marker("x");
+        x.pow(2.).to_cl_source();
+    }
+
+    #[cfg(all(feature = "cpu", feature = "macro"))]
+    #[test]
+    fn test_apply_fn_cpu_complex() {
+        use crate::{number::Complex, ApplyFunction, Buffer, Combiner, CPU};
+
+        let device = CPU::new();
+
+        let buf = Buffer::from((&device, [Complex::new(1f32, 2.), Complex::new(0., -1.)]));
+
+        let buf = device.apply_fn(&buf, |x| x.mul(x).add(Complex::new(1., 0.)));
+        assert_eq!(buf.read_interleaved(), [-2., 4., 0., 0.]);
+    }
+
+    #[cfg(all(feature = "cpu", feature = "macro"))]
+    #[test]
+    fn test_apply_fn_cpu_complex_exp() {
+        use crate::{number::Complex, ApplyFunction, Buffer, Combiner, CPU};
+
+        let device = CPU::new();
+
+        let values = [Complex::new(1f64, 2.), Complex::new(0., -1.)];
+        let buf = Buffer::from((&device, values));
+
+        let buf = device.apply_fn(&buf, |x| x.exp().add(x.cos()));
+        for (out, x) in buf.read().iter().zip(values) {
+            assert_eq!(*out, x.exp() + x.cos());
+        }
+    }
+
+    #[cfg(feature = "opencl")]
+    #[test]
+    fn test_apply_fn_opencl_complex() -> crate::Result<()> {
+        use crate::{
+            number::Complex, opencl::try_cl_apply_fn, ApplyFunction, Buffer, Combiner, OpenCL,
+            Resolve,
+        };
+
+        let device = OpenCL::new(0)?;
+
+        let mut buf = Buffer::<Complex<f32>, _>::new(&device, 2);
+        buf.write_interleaved(&[1., 2., 0., -1.]);
+
+        let buf = device.apply_fn(&buf, |x| x.mul(x).div(Complex::new(0., 1.)));
+        assert_eq!(buf.read_interleaved(), [4., 3., 0., 1.]);
+
+        // a real numerator is converted to a complex number
+        let buf = try_cl_apply_fn(&device, &buf, |x| {
+            Resolve::<f32>::with_marker("2.0f").div(x)
+        })?;
+        assert_eq!(buf.read_interleaved(), [0.32, -0.24, 0., -2.]);
+
+        Ok(())
+    }
+
     #[cfg(feature = "opencl")]
     #[test]
     fn test_run_apply_fn_opencl() -> crate::Result<()> {
@@ -1,15 +1,35 @@
 mod cmps;
 mod unary;
 
+use core::array;
+
 use crate::prelude::Float;
 
 #[cfg(not(feature = "no-std"))]
 use crate::ToCLSource;
 
-use super::{Combiner, Eval};
+use super::{Combiner, Eval, LANES};
 pub use cmps::*;
 pub use unary::*;
 
+/// Returns the source code of a binary operation on complex numbers (e.g. `float2_mul(a, b)`),
+/// or `None` if both operands are real.
+/// A real operand is converted to a complex number, because the vector operators would apply it to both components.
+#[cfg(not(feature = "no-std"))]
+fn complex_binary(op: &str, lhs: &impl ToCLSource, rhs: &impl ToCLSource) -> Option<String> {
+    let c_type = lhs.complex_c_type().or(rhs.complex_c_type())?;
+    let as_complex = |operand: &dyn ToCLSource| match operand.complex_c_type() {
+        Some(_) => operand.to_cl_source(),
+        None => format!("{c_type}_new({}, 0)", operand.to_cl_source()),
+    };
+
+    Some(format!(
+        "{c_type}_{op}({}, {})",
+        as_complex(lhs),
+        as_complex(rhs)
+    ))
+}
+
 pub struct Mul<C, R> {
     comb: C,
     rhs: R,
@@ -28,19 +48,39 @@ impl<C, R> Combiner for Mul<C, R> {}
 impl<C: ToCLSource, R: ToCLSource> ToCLSource for Mul<C, R> {
     #[inline]
     fn to_cl_source(&self) -> String {
+        if let Some(src) = complex_binary("mul", &self.comb, &self.rhs) {
+            return src;
+        }
+
         format!(
             "({} * {})",
             self.comb.to_cl_source(),
             self.rhs.to_cl_source()
         )
     }
+
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        self.comb.complex_c_type().or(self.rhs.complex_c_type())
+    }
 }
 
-impl<C: Eval<T>, R: Eval<T>, T: core::ops::Mul<Output = T>> Eval<T> for Mul<C, R> {
+impl<C, R, T> Eval<T> for Mul<C, R>
+where
+    C: Eval<T>,
+    R: Eval<T>,
+    T: core::ops::Mul<Output = T> + Copy,
+{
     #[inline]
     fn eval(self) -> T {
         self.comb.eval() * self.rhs.eval()
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        let (lhs, rhs) = (self.comb.eval_lanes(x)?, self.rhs.eval_lanes(x)?);
+        Some(array::from_fn(|lane| lhs[lane] * rhs[lane]))
+    }
 }
 
 pub struct Add<C, R> {
@@ -61,19 +101,38 @@ impl<C, R> Combiner for Add<C, R> {}
 impl<C: ToCLSource, R: ToCLSource> ToCLSource for Add<C, R> {
     #[inline]
     fn to_cl_source(&self) -> String {
+        if let Some(src) = complex_binary("add", &self.comb, &self.rhs) {
+            return src;
+        }
+
         format!(
             "({} + {})",
             self.comb.to_cl_source(),
             self.rhs.to_cl_source()
         )
     }
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        self.comb.complex_c_type().or(self.rhs.complex_c_type())
+    }
 }
 
-impl<C: Eval<T>, R: Eval<T>, T: core::ops::Add<Output = T>> Eval<T> for Add<C, R> {
+impl<C, R, T> Eval<T> for Add<C, R>
+where
+    C: Eval<T>,
+    R: Eval<T>,
+    T: core::ops::Add<Output = T> + Copy,
+{
     #[inline]
     fn eval(self) -> T {
         self.comb.eval() + self.rhs.eval()
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        let (lhs, rhs) = (self.comb.eval_lanes(x)?, self.rhs.eval_lanes(x)?);
+        Some(array::from_fn(|lane| lhs[lane] + rhs[lane]))
+    }
 }
 
 pub struct Sub<C, R> {
@@ -94,19 +153,38 @@ impl<C, R> Combiner for Sub<C, R> {}
 impl<C: ToCLSource, R: ToCLSource> ToCLSource for Sub<C, R> {
     #[inline]
     fn to_cl_source(&self) -> String {
+        if let Some(src) = complex_binary("sub", &self.comb, &self.rhs) {
+            return src;
+        }
+
         format!(
             "({} - {})",
             self.comb.to_cl_source(),
             self.rhs.to_cl_source()
         )
     }
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        self.comb.complex_c_type().or(self.rhs.complex_c_type())
+    }
 }
 
-impl<C: Eval<T>, R: Eval<T>, T: core::ops::Sub<Output = T>> Eval<T> for Sub<C, R> {
+impl<C, R, T> Eval<T> for Sub<C, R>
+where
+    C: Eval<T>,
+    R: Eval<T>,
+    T: core::ops::Sub<Output = T> + Copy,
+{
     #[inline]
     fn eval(self) -> T {
         self.comb.eval() - self.rhs.eval()
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        let (lhs, rhs) = (self.comb.eval_lanes(x)?, self.rhs.eval_lanes(x)?);
+        Some(array::from_fn(|lane| lhs[lane] - rhs[lane]))
+    }
 }
 
 pub struct Div<C, R> {
@@ -127,19 +205,39 @@ impl<C, R> Combiner for Div<C, R> {}
 impl<C: ToCLSource, R: ToCLSource> ToCLSource for Div<C, R> {
     #[inline]
     fn to_cl_source(&self) -> String {
+        if let Some(src) = complex_binary("div", &self.comb, &self.rhs) {
+            return src;
+        }
+
         format!(
             "({} / {})",
             self.comb.to_cl_source(),
             self.rhs.to_cl_source()
         )
     }
+
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        self.comb.complex_c_type().or(self.rhs.complex_c_type())
+    }
 }
 
-impl<C: Eval<T>, R: Eval<T>, T: core::ops::Div<Output = T>> Eval<T> for Div<C, R> {
+impl<C, R, T> Eval<T> for Div<C, R>
+where
+    C: Eval<T>,
+    R: Eval<T>,
+    T: core::ops::Div<Output = T> + Copy,
+{
     #[inline]
     fn eval(self) -> T {
         self.comb.eval() / self.rhs.eval()
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        let (lhs, rhs) = (self.comb.eval_lanes(x)?, self.rhs.eval_lanes(x)?);
+        Some(array::from_fn(|lane| lhs[lane] / rhs[lane]))
+    }
 }
 
 pub struct Pow<C, R> {
@@ -158,8 +256,15 @@ impl<C, R> Combiner for Pow<C, R> {}
 
 #[cfg(not(feature = "no-std"))]
 impl<C: ToCLSource, R: ToCLSource> ToCLSource for Pow<C, R> {
+    /// # Panics
+    /// If an operand is complex, as there is no complex power function for generated source code.
     #[inline]
     fn to_cl_source(&self) -> String {
+        assert!(
+            self.comb.complex_c_type().is_none() && self.rhs.complex_c_type().is_none(),
+            "pow is not supported for complex numbers in generated source code"
+        );
+
         format!(
             "pow({}, {})",
             self.comb.to_cl_source(),
@@ -173,4 +278,10 @@ impl<C: Eval<T>, R: Eval<T>, T: Float> Eval<T> for Pow<C, R> {
     fn eval(self) -> T {
         self.comb.eval().powf(self.rhs.eval())
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        let (lhs, rhs) = (self.comb.eval_lanes(x)?, self.rhs.eval_lanes(x)?);
+        Some(array::from_fn(|lane| lhs[lane].powf(rhs[lane])))
+    }
 }
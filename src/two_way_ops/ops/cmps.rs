@@ -1,4 +1,6 @@
-use crate::{prelude::Number, Combiner, Eval};
+use core::array;
+
+use crate::{prelude::Number, Combiner, Eval, LANES};
 
 #[cfg(not(feature = "no-std"))]
 use super::ToCLSource;
@@ -32,6 +34,14 @@ impl<C: Eval<T>, R: Eval<T>, T: Number> Eval<T> for GEq<C, R> {
     fn eval(self) -> T {
         T::from_usize(self.comb.eval().ge(&self.rhs.eval()) as usize)
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        let (lhs, rhs) = (self.comb.eval_lanes(x)?, self.rhs.eval_lanes(x)?);
+        Some(array::from_fn(|lane| {
+            T::from_usize(lhs[lane].ge(&rhs[lane]) as usize)
+        }))
+    }
 }
 
 impl<C, R> Combiner for GEq<C, R> {}
@@ -65,6 +75,14 @@ impl<C: Eval<T>, R: Eval<T>, T: Number> Eval<T> for LEq<C, R> {
     fn eval(self) -> T {
         T::from_usize(self.comb.eval().le(&self.rhs.eval()) as usize)
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        let (lhs, rhs) = (self.comb.eval_lanes(x)?, self.rhs.eval_lanes(x)?);
+        Some(array::from_fn(|lane| {
+            T::from_usize(lhs[lane].le(&rhs[lane]) as usize)
+        }))
+    }
 }
 
 impl<C, R> Combiner for LEq<C, R> {}
@@ -96,7 +114,15 @@ impl<C: ToCLSource, R: ToCLSource> ToCLSource for Eq<C, R> {
 impl<C: Eval<T>, R: Eval<T>, T: Number> Eval<T> for Eq<C, R> {
     #[inline]
     fn eval(self) -> T {
-        T::from_usize(self.comb.eval().le(&self.rhs.eval()) as usize)
+        T::from_usize(self.comb.eval().eq(&self.rhs.eval()) as usize)
+    }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        let (lhs, rhs) = (self.comb.eval_lanes(x)?, self.rhs.eval_lanes(x)?);
+        Some(array::from_fn(|lane| {
+            T::from_usize(lhs[lane].eq(&rhs[lane]) as usize)
+        }))
     }
 }
 
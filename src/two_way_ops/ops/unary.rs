@@ -1,4 +1,4 @@
-use crate::{prelude::Float, Combiner, Eval};
+use crate::{number::Complex, prelude::Float, Combiner, Eval, LANES};
 
 #[cfg(not(feature = "no-std"))]
 use super::ToCLSource;
@@ -14,14 +14,41 @@ impl<T: Float, C: Eval<T>> Eval<T> for Exp<C> {
     fn eval(self) -> T {
         self.comb.eval().exp()
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        Some(self.comb.eval_lanes(x)?.map(|x| x.exp()))
+    }
+}
+
+impl<T: Float, C: Eval<Complex<T>>> Eval<Complex<T>> for Exp<C> {
+    #[inline]
+    fn eval(self) -> Complex<T> {
+        self.comb.eval().exp()
+    }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[Complex<T>; LANES]) -> Option<[Complex<T>; LANES]> {
+        Some(self.comb.eval_lanes(x)?.map(Complex::exp))
+    }
 }
 
 #[cfg(not(feature = "no-std"))]
 impl<C: ToCLSource> ToCLSource for Exp<C> {
     #[inline]
     fn to_cl_source(&self) -> String {
+        // the built-in function would be applied to both components
+        if let Some(c_type) = self.comb.complex_c_type() {
+            return format!("{c_type}_exp({})", self.comb.to_cl_source());
+        }
+
         format!("exp({})", self.comb.to_cl_source())
     }
+
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        self.comb.complex_c_type()
+    }
 }
 
 pub struct Sin<C> {
@@ -35,14 +62,41 @@ impl<T: Float, C: Eval<T>> Eval<T> for Sin<C> {
     fn eval(self) -> T {
         self.comb.eval().sin()
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        Some(self.comb.eval_lanes(x)?.map(|x| x.sin()))
+    }
+}
+
+impl<T: Float, C: Eval<Complex<T>>> Eval<Complex<T>> for Sin<C> {
+    #[inline]
+    fn eval(self) -> Complex<T> {
+        self.comb.eval().sin()
+    }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[Complex<T>; LANES]) -> Option<[Complex<T>; LANES]> {
+        Some(self.comb.eval_lanes(x)?.map(Complex::sin))
+    }
 }
 
 #[cfg(not(feature = "no-std"))]
 impl<C: ToCLSource> ToCLSource for Sin<C> {
     #[inline]
     fn to_cl_source(&self) -> String {
+        // the built-in function would be applied to both components
+        if let Some(c_type) = self.comb.complex_c_type() {
+            return format!("{c_type}_sin({})", self.comb.to_cl_source());
+        }
+
         format!("sin({})", self.comb.to_cl_source())
     }
+
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        self.comb.complex_c_type()
+    }
 }
 
 pub struct Cos<C> {
@@ -56,14 +110,41 @@ impl<T: Float, C: Eval<T>> Eval<T> for Cos<C> {
     fn eval(self) -> T {
         self.comb.eval().cos()
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        Some(self.comb.eval_lanes(x)?.map(|x| x.cos()))
+    }
+}
+
+impl<T: Float, C: Eval<Complex<T>>> Eval<Complex<T>> for Cos<C> {
+    #[inline]
+    fn eval(self) -> Complex<T> {
+        self.comb.eval().cos()
+    }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[Complex<T>; LANES]) -> Option<[Complex<T>; LANES]> {
+        Some(self.comb.eval_lanes(x)?.map(Complex::cos))
+    }
 }
 
 #[cfg(not(feature = "no-std"))]
 impl<C: ToCLSource> ToCLSource for Cos<C> {
     #[inline]
     fn to_cl_source(&self) -> String {
+        // the built-in function would be applied to both components
+        if let Some(c_type) = self.comb.complex_c_type() {
+            return format!("{c_type}_cos({})", self.comb.to_cl_source());
+        }
+
         format!("cos({})", self.comb.to_cl_source())
     }
+
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        self.comb.complex_c_type()
+    }
 }
 
 pub struct Tan<C> {
@@ -77,14 +158,41 @@ impl<T: Float, C: Eval<T>> Eval<T> for Tan<C> {
     fn eval(self) -> T {
         self.comb.eval().tan()
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        Some(self.comb.eval_lanes(x)?.map(|x| x.tan()))
+    }
+}
+
+impl<T: Float, C: Eval<Complex<T>>> Eval<Complex<T>> for Tan<C> {
+    #[inline]
+    fn eval(self) -> Complex<T> {
+        self.comb.eval().tan()
+    }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[Complex<T>; LANES]) -> Option<[Complex<T>; LANES]> {
+        Some(self.comb.eval_lanes(x)?.map(Complex::tan))
+    }
 }
 
 #[cfg(not(feature = "no-std"))]
 impl<C: ToCLSource> ToCLSource for Tan<C> {
     #[inline]
     fn to_cl_source(&self) -> String {
+        // the built-in function would be applied to both components
+        if let Some(c_type) = self.comb.complex_c_type() {
+            return format!("{c_type}_tan({})", self.comb.to_cl_source());
+        }
+
         format!("tan({})", self.comb.to_cl_source())
     }
+
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        self.comb.complex_c_type()
+    }
 }
 
 pub struct Neg<C> {
@@ -93,17 +201,32 @@ pub struct Neg<C> {
 
 impl<C> Combiner for Neg<C> {}
 
-impl<T: core::ops::Neg<Output = T>, C: Eval<T>> Eval<T> for Neg<C> {
+impl<T: core::ops::Neg<Output = T> + Copy, C: Eval<T>> Eval<T> for Neg<C> {
     #[inline]
     fn eval(self) -> T {
         self.comb.eval().neg()
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        Some(self.comb.eval_lanes(x)?.map(|x| -x))
+    }
 }
 
 #[cfg(not(feature = "no-std"))]
 impl<C: ToCLSource> ToCLSource for Neg<C> {
     #[inline]
     fn to_cl_source(&self) -> String {
+        // CUDA does not define the negation of vector types
+        if let Some(c_type) = self.comb.complex_c_type() {
+            return format!("{c_type}_neg({})", self.comb.to_cl_source());
+        }
+
         format!("-({})", self.comb.to_cl_source())
     }
+
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        self.comb.complex_c_type()
+    }
 }
@@ -1,7 +1,7 @@
 #[cfg(not(feature = "no-std"))]
 use crate::ToCLSource;
 
-use super::{Combiner, Eval};
+use super::{Combiner, Eval, LANES, LANE_MARKER};
 
 /// Resolves to either a mathematical expression as string or a computed value.
 /// This is used to create generic kernels / operations over `OpenCL`, `CUDA` and `CPU`.
@@ -128,19 +128,32 @@ impl<T> Resolve<T> {
     }
 }
 
-impl<T> Eval<T> for Resolve<T> {
+impl<T: Copy> Eval<T> for Resolve<T> {
     #[inline]
     fn eval(self) -> T {
         self.val
     }
+
+    #[inline]
+    fn eval_lanes(&self, x: &[T; LANES]) -> Option<[T; LANES]> {
+        if self.marker == LANE_MARKER {
+            return Some(*x);
+        }
+        Some([self.val; LANES])
+    }
 }
 
 #[cfg(not(feature = "no-std"))]
-impl<T> ToCLSource for Resolve<T> {
+impl<T: crate::number::Numeric> ToCLSource for Resolve<T> {
     #[inline]
     fn to_cl_source(&self) -> String {
         self.marker.to_string()
     }
+
+    #[inline]
+    fn complex_c_type(&self) -> Option<&'static str> {
+        T::complex_c_type()
+    }
 }
 
 impl<T> Combiner for Resolve<T> {}
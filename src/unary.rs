@@ -1,6 +1,7 @@
 use crate::{Alloc, Buffer, Device, Eval, MayTapeReturn, MayToCLSource, Resolve, Shape};
 
 /// Applies a function to a buffer and returns a new buffer.
+/// The function is `Sync`, because devices like the [`CPU`](crate::CPU) may call it from several threads.
 pub trait ApplyFunction<T, S: Shape = (), D: Device = Self>: Device {
     /// Applies a function to a buffer and returns a new buffer.
     /// # Example
@@ -14,7 +15,11 @@ pub trait ApplyFunction<T, S: Shape = (), D: Device = Self>: Device {
     /// let out = device.apply_fn(&a, |x| x.mul(2.));
     /// assert_eq!(&*out, &[2., 4., 6., 6., 4., 2.,]);
     /// ```
-    fn apply_fn<F>(&self, buf: &Buffer<T, D, S>, f: impl Fn(Resolve<T>) -> F) -> Buffer<T, Self, S>
+    fn apply_fn<F>(
+        &self,
+        buf: &Buffer<T, D, S>,
+        f: impl Fn(Resolve<T>) -> F + Sync,
+    ) -> Buffer<T, Self, S>
     where
         F: Eval<T> + MayToCLSource;
 }
@@ -44,7 +49,7 @@ pub trait UnaryGrad<T, S: Shape = (), D: Device = Self>: Device {
         lhs: &Buffer<T, D, S>,
         lhs_grad: &mut Buffer<T, D, S>,
         out_grad: &Buffer<T, D, S>,
-        lhs_grad_fn: impl Fn(Resolve<T>) -> F,
+        lhs_grad_fn: impl Fn(Resolve<T>) -> F + Sync,
     ) where
         F: Eval<T> + MayToCLSource;
 }
@@ -78,7 +83,7 @@ pub trait UnaryElementWiseMayGrad<T, D: Device, S: Shape>: Device {
     fn unary_ew<FO, GO>(
         &self,
         buf: &Buffer<T, D, S>,
-        forward_fn: impl Fn(Resolve<T>) -> FO,
+        forward_fn: impl Fn(Resolve<T>) -> FO + Sync,
         grad_fn: fn(Resolve<T>) -> GO,
     ) -> Buffer<T, Self, S>
     where
@@ -97,7 +102,7 @@ where
     fn unary_ew<FO, GO>(
         &self,
         buf: &Buffer<T, D, S>,
-        forward_fn: impl Fn(Resolve<T>) -> FO,
+        forward_fn: impl Fn(Resolve<T>) -> FO + Sync,
         _grad_fn: fn(Resolve<T>) -> GO,
     ) -> Buffer<T, Self, S>
     where
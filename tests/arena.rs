@@ -0,0 +1,137 @@
+use custos::{
+    arena::{ArenaError, BumpArena},
+    ApplyFunction, Buffer, ClearBuf, Combiner, Device, Read, WriteBuf,
+};
+
+fn region(size: usize) -> &'static mut [u8] {
+    Box::leak(vec![0; size].into_boxed_slice())
+}
+
+#[test]
+fn test_arena_read_write() {
+    let arena = BumpArena::new(region(256));
+
+    let mut buf = Buffer::from((&arena, [1, 2, 3, 4]));
+    assert_eq!(arena.read(&buf), [1, 2, 3, 4]);
+
+    arena.write(&mut buf, &[4, 3, 2, 1]);
+    assert_eq!(buf.read(), [4, 3, 2, 1]);
+
+    arena.clear(&mut buf);
+    assert_eq!(buf.read(), [0; 4]);
+}
+
+#[test]
+fn test_arena_alignment() {
+    let arena = BumpArena::new(region(1024)).with_align(64);
+
+    let a = arena.try_buffer::<u8, ()>(3).unwrap();
+    let b = arena.try_buffer::<f64, ()>(5).unwrap();
+    let c = arena.try_buffer::<u16, ()>(1).unwrap();
+
+    for addr in [a.ptr.ptr as usize, b.ptr.ptr as usize, c.ptr.ptr as usize] {
+        assert_eq!(addr % 64, 0);
+    }
+    assert!(arena.used() <= arena.capacity());
+}
+
+#[test]
+fn test_arena_exhausted() {
+    let arena = BumpArena::new(region(64));
+
+    let _buf = arena.try_buffer::<f32, ()>(8).unwrap();
+    let remaining = arena.remaining();
+
+    let err = arena.try_buffer::<f32, ()>(64).err();
+    assert_eq!(
+        err,
+        Some(ArenaError::Exhausted {
+            requested: 256,
+            remaining
+        })
+    );
+
+    // a failed allocation does not use any memory
+    assert_eq!(arena.remaining(), remaining);
+
+    let err = arena.try_buffer::<f32, ()>(usize::MAX).err();
+    assert_eq!(err, Some(ArenaError::TooLarge));
+}
+
+#[test]
+fn test_arena_try_from_slice() {
+    let arena = BumpArena::new(region(64));
+
+    let buf = arena.try_from_slice::<_, ()>(&[1., 2., 3.]).unwrap();
+    assert_eq!(buf.read(), [1., 2., 3.]);
+
+    assert!(arena.try_from_slice::<f64, ()>(&[0.; 16]).is_err());
+}
+
+#[test]
+fn test_arena_reset_reuses_region() {
+    let mut arena = BumpArena::new(region(128));
+
+    let first = {
+        let buf = Buffer::<f32, _>::new(&arena, 8);
+        buf.ptr.ptr
+    };
+    assert!(arena.used() >= 32);
+
+    arena.reset();
+    assert_eq!(arena.used(), 0);
+    assert_eq!(arena.remaining(), 128);
+
+    let buf = Buffer::<f32, _>::new(&arena, 8);
+    assert_eq!(buf.ptr.ptr, first);
+    // the memory is zeroed again
+    assert_eq!(buf.read(), [0.; 8]);
+}
+
+#[test]
+fn test_arena_apply_fn_loop() {
+    let mut arena = BumpArena::new(region(256));
+
+    for i in 0..1000 {
+        let buf = Buffer::from((&arena, [1., 2., 3.]));
+        let out = arena.apply_fn(&buf, |x| x.mul(2.).add(1.));
+        assert_eq!(out.read(), [3., 5., 7.], "iteration {i}");
+
+        drop((buf, out));
+        arena.reset();
+    }
+}
+
+#[test]
+fn test_arena_device_new() {
+    assert!(<BumpArena as Device>::new().is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_arena_alloc_exhausted() {
+    let arena = BumpArena::new(region(16));
+    let _buf = Buffer::<f32, _>::new(&arena, 8);
+}
+
+#[test]
+#[should_panic]
+fn test_arena_invalid_align() {
+    let _arena = BumpArena::new(region(16)).with_align(3);
+}
+
+#[test]
+fn test_arena_try_apply_fn_exhausted() {
+    let arena = BumpArena::new(region(64));
+
+    let buf = Buffer::from((&arena, [1., 2., 3.]));
+    let out = arena.try_apply_fn(&buf, |x| x.add(1.)).unwrap();
+    assert_eq!(out.read(), [2., 3., 4.]);
+
+    let remaining = arena.remaining();
+    assert!(matches!(
+        arena.try_apply_fn(&buf, |x| x.add(1.)),
+        Err(ArenaError::Exhausted { .. })
+    ));
+    assert_eq!(arena.remaining(), remaining);
+}
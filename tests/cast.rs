@@ -0,0 +1,85 @@
+use custos::{Buffer, CastBuf, WithShape, CPU};
+
+use custos_macro::stack_cpu_test;
+
+#[stack_cpu_test]
+#[test]
+fn test_cast_cpu() {
+    let device = CPU::new();
+
+    let buf = Buffer::with(&device, [1.7, -2.2, 3., 0.]);
+
+    let casted: Buffer<i32, _, _> = device.cast(&buf);
+    assert_eq!(casted.read(), [1, -2, 3, 0]);
+
+    let casted = casted.cast::<f64>();
+    assert_eq!(casted.read(), [1., -2., 3., 0.]);
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_cast_mask_cpu() {
+    use custos::{ApplyFunction, Combiner};
+
+    let device = CPU::new();
+
+    let buf = Buffer::with(&device, [1., -2., 3., 0.]);
+    let mask = device.apply_fn(&buf, |x| x.geq(1.));
+
+    let mask = mask.cast::<bool>();
+    assert_eq!(mask.read(), [true, false, true, false]);
+
+    let ones = mask.cast::<u8>();
+    assert_eq!(ones.read(), [1, 0, 1, 0]);
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_cast_cl() -> custos::Result<()> {
+    use custos::{ApplyFunction, Combiner, OpenCL};
+
+    let device = OpenCL::new(0)?;
+
+    let buf = Buffer::from((&device, [1.7f32, -2.2, 3., 0.]));
+
+    let casted: Buffer<i32, _> = device.cast(&buf);
+    assert_eq!(casted.read(), [1, -2, 3, 0]);
+
+    let mask = device.apply_fn(&buf, |x| x.geq(1.)).cast::<bool>();
+    assert_eq!(mask.read(), [true, false, true, false]);
+
+    let ones = mask.cast::<f32>();
+    assert_eq!(ones.read(), [1., 0., 1., 0.]);
+    Ok(())
+}
+
+#[cfg(feature = "cuda")]
+#[test]
+fn test_cast_cuda() -> custos::Result<()> {
+    use custos::CUDA;
+
+    let device = CUDA::new(0)?;
+
+    let buf = Buffer::from((&device, [1.7f32, -2.2, 3., 0.]));
+
+    let casted: Buffer<i32, _> = device.cast(&buf);
+    assert_eq!(casted.read(), vec![1, -2, 3, 0]);
+
+    let mask = buf.cast::<bool>();
+    assert_eq!(mask.read(), vec![true, true, true, false]);
+    Ok(())
+}
+
+#[cfg(feature = "wgpu")]
+#[test]
+fn test_cast_wgpu() -> custos::Result<()> {
+    use custos::WGPU;
+
+    let device = WGPU::new(wgpu::Backends::all())?;
+
+    let buf = Buffer::from((&device, [1.7f32, -2.2, 3., 0.]));
+
+    let casted: Buffer<i32, _> = device.cast(&buf);
+    assert_eq!(casted.read(), vec![1, -2, 3, 0]);
+    Ok(())
+}
@@ -0,0 +1,20 @@
+//! Helpers shared by the test devices that store their buffers in host memory, like the `CPU`.
+
+use custos::{cpu::CPUPtr, flag::AllocFlag};
+
+/// Allocates host memory holding a copy of `data`.
+pub fn host_ptr_with_slice<T: Clone>(data: &[T]) -> CPUPtr<T> {
+    let mut ptr = CPUPtr::new_initialized(data.len(), AllocFlag::None);
+    host_slice_mut(&mut ptr).clone_from_slice(data);
+    ptr
+}
+
+/// Returns the memory of `ptr` as a slice.
+pub fn host_slice<T>(ptr: &CPUPtr<T>) -> &[T] {
+    unsafe { std::slice::from_raw_parts(ptr.ptr, ptr.len) }
+}
+
+/// Returns the memory of `ptr` as a mutable slice.
+pub fn host_slice_mut<T>(ptr: &mut CPUPtr<T>) -> &mut [T] {
+    unsafe { std::slice::from_raw_parts_mut(ptr.ptr, ptr.len) }
+}
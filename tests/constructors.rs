@@ -0,0 +1,135 @@
+use custos::{Buffer, Dim1, Dim2, CPU};
+
+use custos_macro::stack_cpu_test;
+
+#[stack_cpu_test]
+#[test]
+fn test_full_ones_cpu() {
+    let device = CPU::new();
+
+    let buf = Buffer::<f32, _, Dim1<4>>::full(&device, 4, -1.5);
+    assert_eq!(buf.read(), [-1.5; 4]);
+
+    let buf = Buffer::<u8, _, Dim1<3>>::ones(&device, 3);
+    assert_eq!(buf.read(), [1; 3]);
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_arange_linspace_cpu() {
+    let device = CPU::new();
+
+    let buf = Buffer::<i32, _, Dim1<5>>::arange(&device, 5, 0, -1);
+    assert_eq!(buf.read(), [5, 4, 3, 2, 1]);
+
+    let buf = Buffer::<f64, _, Dim1<5>>::linspace(&device, 1., 3., 5);
+    assert_eq!(buf.read(), [1., 1.5, 2., 2.5, 3.]);
+
+    // descending ranges end at `end` as well
+    let buf = Buffer::<f32, _, Dim1<6>>::linspace(&device, 5., 0., 6);
+    assert_eq!(buf.read(), [5., 4., 3., 2., 1., 0.]);
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_eye_cpu() {
+    let device = CPU::new();
+
+    let buf = Buffer::<f32, _, Dim2<3, 3>>::eye(&device);
+    assert_eq!(buf.as_slice(), &[1., 0., 0., 0., 1., 0., 0., 0., 1.]);
+
+    let buf = Buffer::<i32, _, Dim2<2, 3>>::eye(&device);
+    assert_eq!(buf.as_slice(), &[1, 0, 0, 0, 1, 0]);
+}
+
+#[test]
+#[should_panic]
+fn test_arange_empty_cpu() {
+    let device = CPU::new();
+    Buffer::<i32>::arange(&device, 3, 3, 1);
+}
+
+#[test]
+#[should_panic(expected = "must not be zero")]
+fn test_arange_zero_step_cpu() {
+    let device = CPU::new();
+    Buffer::<f32>::arange(&device, 0., 1., 0.);
+}
+
+#[test]
+#[should_panic(expected = "towards `end`")]
+fn test_arange_wrong_direction_cpu() {
+    let device = CPU::new();
+    Buffer::<u8>::arange(&device, 5, 0, 1);
+}
+
+#[test]
+#[should_panic(expected = "does not match the length")]
+fn test_full_wrong_shape_len_cpu() {
+    let device = CPU::new();
+    Buffer::<f32, _, Dim1<4>>::full(&device, 3, 1.);
+}
+
+#[test]
+#[should_panic(expected = "does not match the length")]
+fn test_arange_wrong_shape_len_cpu() {
+    let device = CPU::new();
+    Buffer::<i32, _, Dim1<4>>::arange(&device, 0, 3, 1);
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_constructors_cl() -> custos::Result<()> {
+    use custos::OpenCL;
+
+    let device = OpenCL::new(0)?;
+
+    let buf = Buffer::<f32, _>::full(&device, 4, 3.);
+    assert_eq!(buf.read(), [3.; 4]);
+
+    let buf = Buffer::<i32, _>::arange(&device, -2, 4, 2);
+    assert_eq!(buf.read(), [-2, 0, 2]);
+
+    let buf = Buffer::<f32, _>::linspace(&device, 0., 1., 3);
+    assert_eq!(buf.read(), [0., 0.5, 1.]);
+
+    let buf = Buffer::<f32, _, Dim2<2, 2>>::eye(&device);
+    assert_eq!(buf.read(), [1., 0., 0., 1.]);
+    Ok(())
+}
+
+#[cfg(feature = "cuda")]
+#[test]
+fn test_constructors_cuda() -> custos::Result<()> {
+    use custos::{Read, CUDA};
+
+    let device = CUDA::new(0)?;
+
+    let buf = Buffer::<f32, _>::ones(&device, 4);
+    assert_eq!(device.read(&buf), vec![1.; 4]);
+
+    let buf = Buffer::<i32, _>::arange(&device, 0, 6, 2);
+    assert_eq!(device.read(&buf), vec![0, 2, 4]);
+
+    let buf = Buffer::<f64, _>::linspace(&device, -1., 1., 5);
+    assert_eq!(device.read(&buf), vec![-1., -0.5, 0., 0.5, 1.]);
+    Ok(())
+}
+
+#[cfg(feature = "wgpu")]
+#[test]
+fn test_constructors_wgpu() -> custos::Result<()> {
+    use custos::WGPU;
+
+    let device = WGPU::new(wgpu::Backends::all())?;
+
+    let buf = Buffer::<f32, _>::full(&device, 3, 2.);
+    assert_eq!(buf.read(), [2.; 3]);
+
+    let buf = Buffer::<i32, _>::arange(&device, 1, 4, 1);
+    assert_eq!(buf.read(), [1, 2, 3]);
+
+    let buf = Buffer::<f32, _, Dim2<2, 2>>::eye(&device);
+    assert_eq!(buf.as_dims::<()>().read(), [1., 0., 0., 1.]);
+    Ok(())
+}
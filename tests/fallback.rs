@@ -0,0 +1,161 @@
+use std::ops::{Deref, DerefMut};
+
+use custos::{
+    cpu::CPUPtr,
+    exec_on_cpu::{clear_fallbacks, fallbacks, CPUFallback},
+    flag::AllocFlag,
+    Alloc, ApplyFunction, Buffer, Combiner, CopySlice, Device, PtrConv, Read, Shape, UnaryGrad,
+    WriteBuf,
+};
+
+mod common;
+
+use common::{host_ptr_with_slice, host_slice, host_slice_mut};
+
+/// A device without any native operations, apart from allocating, reading and writing.
+struct Host;
+
+impl Device for Host {
+    type Ptr<U, S: Shape> = HostPtr<U>;
+    type Cache = ();
+
+    fn new() -> custos::Result<Self> {
+        Ok(Host)
+    }
+}
+
+impl CPUFallback for Host {}
+
+/// Wraps a `CPUPtr` so that `Host` buffers can not be passed to `CPU` operations.
+struct HostPtr<T>(CPUPtr<T>);
+
+impl<T> Deref for HostPtr<T> {
+    type Target = CPUPtr<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for HostPtr<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> custos::PtrType for HostPtr<T> {
+    fn size(&self) -> usize {
+        self.len
+    }
+
+    fn flag(&self) -> AllocFlag {
+        self.flag
+    }
+}
+
+impl PtrConv for Host {
+    unsafe fn convert<T, IS: Shape, Conv, OS: Shape>(
+        ptr: &Self::Ptr<T, IS>,
+        flag: AllocFlag,
+    ) -> Self::Ptr<Conv, OS> {
+        HostPtr(CPUPtr::from_ptr(ptr.ptr.cast(), ptr.len, flag))
+    }
+}
+
+impl<T, S: Shape> Alloc<'_, T, S> for Host {
+    fn alloc(&self, len: usize, flag: AllocFlag) -> HostPtr<T> {
+        HostPtr(CPUPtr::new_initialized(len, flag))
+    }
+
+    fn with_slice(&self, data: &[T]) -> HostPtr<T>
+    where
+        T: Clone,
+    {
+        HostPtr(host_ptr_with_slice(data))
+    }
+}
+
+impl<T: Clone> Read<T> for Host {
+    type Read<'a>
+        = Vec<T>
+    where
+        T: 'a;
+
+    fn read(&self, buf: &Buffer<T, Host>) -> Vec<T> {
+        host_slice(&buf.ptr).to_vec()
+    }
+
+    fn read_to_vec(&self, buf: &Buffer<T, Host>) -> Vec<T>
+    where
+        T: Default + Clone,
+    {
+        host_slice(&buf.ptr).to_vec()
+    }
+}
+
+impl<T: Clone> WriteBuf<T> for Host {
+    fn write(&self, buf: &mut Buffer<T, Host>, data: &[T]) {
+        host_slice_mut(&mut buf.ptr).clone_from_slice(data);
+    }
+
+    fn write_buf(&self, dst: &mut Buffer<T, Host>, src: &Buffer<T, Host>) {
+        self.write(dst, host_slice(&src.ptr))
+    }
+}
+
+#[test]
+fn test_fallback_apply_fn() {
+    let device = Host;
+
+    let buf = Buffer::from((&device, [1., 2., 3., 4.]));
+    let out = device.apply_fn(&buf, |x| x.mul(2.).add(1.));
+
+    assert_eq!(out.read(), [3., 5., 7., 9.]);
+    assert!(fallbacks()
+        .iter()
+        .any(|fallback| fallback.op == "apply_fn" && fallback.device.ends_with("Host")));
+}
+
+#[test]
+fn test_fallback_unary_grad() {
+    let device = Host;
+
+    let lhs = Buffer::from((&device, [1., 2., 3.]));
+    let out_grad = Buffer::from((&device, [1., 1., 2.]));
+    let mut lhs_grad = Buffer::from((&device, [1., 0., 0.]));
+
+    device.add_unary_grad(&lhs, &mut lhs_grad, &out_grad, |x| x.mul(3.));
+    assert_eq!(lhs_grad.read(), [4., 6., 18.]);
+}
+
+#[test]
+fn test_fallback_copy_slice() {
+    let device = Host;
+
+    let source = Buffer::from((&device, [1, 2, 3, 4, 5]));
+    let slice = device.copy_slice(&source, 1..3);
+    assert_eq!(slice.read(), [2, 3]);
+
+    let mut dest = Buffer::<i32, _>::new(&device, 6);
+    device.copy_slice_all(&source, &mut dest, [(0..2, 4..6), (3..5, 0..2)]);
+    assert_eq!(dest.read(), [4, 5, 0, 0, 1, 2]);
+}
+
+#[test]
+fn test_fallback_log_is_deduplicated() {
+    clear_fallbacks();
+
+    let device = Host;
+    let buf = Buffer::from((&device, [1, 2, 3]));
+
+    for _ in 0..3 {
+        device.apply_fn(&buf, |x| x.add(1));
+    }
+    device.copy_slice(&buf, ..2);
+
+    let ops = fallbacks()
+        .iter()
+        .map(|fallback| fallback.op)
+        .collect::<Vec<_>>();
+    assert_eq!(ops, ["apply_fn", "copy_slice_to"]);
+}
@@ -0,0 +1,134 @@
+use core::{alloc::Layout, cell::Cell};
+
+use custos::{
+    heap::HeapAllocator, range, ApplyFunction, Buffer, CacheReturn, ClearBuf, Combiner, CopySlice,
+    Device, Heap, Read, WriteBuf,
+};
+
+#[test]
+fn test_heap_read_write() {
+    let device = Heap::new();
+
+    let mut buf = Buffer::from((&device, [1, 2, 3, 4]));
+    assert_eq!(device.read(&buf), [1, 2, 3, 4]);
+
+    device.write(&mut buf, &[4, 3, 2, 1]);
+    assert_eq!(buf.read(), [4, 3, 2, 1]);
+
+    device.clear(&mut buf);
+    assert_eq!(buf.read(), [0; 4]);
+
+    let zeroed = Buffer::<f32, _>::new(&device, 5);
+    assert_eq!(zeroed.read(), [0.; 5]);
+}
+
+#[test]
+fn test_heap_copy_slice() {
+    let device = Heap::new();
+
+    let buf = Buffer::from((&device, [1., 2., 6., 2., 4.]));
+    let slice = device.copy_slice(&buf, 1..3);
+    assert_eq!(slice.read(), [2., 6.]);
+}
+
+#[test]
+fn test_heap_apply_fn() {
+    let device = Heap::new();
+
+    let buf = Buffer::from((&device, [1., 2., 3.]));
+    let out = device.apply_fn(&buf, |x| x.mul(2.).add(1.));
+    assert_eq!(out.read(), [3., 5., 7.]);
+}
+
+#[cfg(not(feature = "realloc"))]
+#[test]
+fn test_heap_cache_reuse() {
+    let device = Heap::new();
+    let buf = Buffer::from((&device, [1., 2., 3.]));
+
+    let mut ptrs = Vec::new();
+    for _ in range(10) {
+        let out = device.apply_fn(&buf, |x| x.add(1.));
+        ptrs.push(out.ptr.ptr);
+    }
+
+    assert!(ptrs.iter().all(|ptr| *ptr == ptrs[0]));
+    // `buf` and `out`
+    assert_eq!(device.cache().nodes.len(), 2);
+}
+
+/// A bump allocator over a fixed block of memory. Memory is only freed when the arena is dropped.
+struct Arena {
+    memory: Box<[u8]>,
+    used: Cell<usize>,
+    allocations: Cell<usize>,
+    deallocations: Cell<usize>,
+}
+
+impl Arena {
+    fn leak(size: usize) -> &'static Arena {
+        Box::leak(Box::new(Arena {
+            memory: vec![0; size].into_boxed_slice(),
+            used: Cell::new(0),
+            allocations: Cell::new(0),
+            deallocations: Cell::new(0),
+        }))
+    }
+}
+
+impl HeapAllocator for Arena {
+    fn allocate(&self, layout: Layout) -> *mut u8 {
+        let base = self.memory.as_ptr() as usize;
+        let start = (base + self.used.get() + layout.align() - 1) & !(layout.align() - 1);
+        let end = start - base + layout.size();
+
+        if end > self.memory.len() {
+            return core::ptr::null_mut();
+        }
+
+        self.used.set(end);
+        self.allocations.set(self.allocations.get() + 1);
+        start as *mut u8
+    }
+
+    unsafe fn deallocate(&self, _ptr: *mut u8, _layout: Layout) {
+        self.deallocations.set(self.deallocations.get() + 1);
+    }
+}
+
+#[test]
+fn test_heap_arena_allocator() {
+    let arena = Arena::leak(1024);
+
+    {
+        let device = Heap::with_allocator(arena);
+        let buf = Buffer::from((&device, [1u64, 2, 3]));
+        let out = device.apply_fn(&buf, |x| x.mul(3));
+        assert_eq!(out.read(), [3, 6, 9]);
+
+        let start = arena.memory.as_ptr() as usize;
+        let buf_addr = buf.ptr.ptr as usize;
+        assert!(buf_addr >= start && buf_addr < start + arena.memory.len());
+        assert_eq!(buf_addr % core::mem::align_of::<u64>(), 0);
+    }
+
+    assert_eq!(arena.allocations.get(), 2);
+    assert_eq!(arena.deallocations.get(), 2);
+}
+
+#[test]
+#[should_panic]
+fn test_heap_arena_exhausted() {
+    let arena = Arena::leak(16);
+
+    let device = Heap::with_allocator(arena);
+    let _buf = Buffer::<f32, _>::new(&device, 8);
+}
+
+#[test]
+fn test_heap_device_new() -> custos::Result<()> {
+    let device = <Heap as Device>::new()?;
+    let buf = Buffer::from((&device, [1, 2]));
+    assert_eq!(buf.read(), [1, 2]);
+    Ok(())
+}
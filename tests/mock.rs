@@ -0,0 +1,86 @@
+use custos::{
+    ApplyFunction, Buffer, ClearBuf, Combiner, CopySlice, Mock, MockCall, Read, WriteBuf,
+};
+
+#[test]
+fn test_mock_read_write() {
+    let device = Mock::new();
+
+    let mut buf = Buffer::from((&device, [1, 2, 3, 4]));
+    assert_eq!(device.read(&buf), [1, 2, 3, 4]);
+
+    device.write(&mut buf, &[4, 3, 2, 1]);
+    assert_eq!(device.read_to_vec(&buf), [4, 3, 2, 1]);
+
+    device.clear(&mut buf);
+    assert_eq!(device.read(&buf), [0; 4]);
+
+    assert_eq!(
+        device.calls(),
+        [
+            MockCall::new("with_slice", [4]),
+            MockCall::new("read", [4]),
+            MockCall::new("write", [4, 4]),
+            MockCall::new("read_to_vec", [4]),
+            MockCall::new("clear", [4]),
+            MockCall::new("read", [4]),
+        ]
+    );
+}
+
+#[test]
+fn test_mock_apply_fn_records_source() {
+    let device = Mock::new();
+
+    let buf = Buffer::from((&device, [1., 2., 3.]));
+    device.clear_calls();
+
+    let out = device.apply_fn(&buf, |x| x.mul(x).sub(1.));
+    assert_eq!(device.read(&out), [0., 3., 8.]);
+
+    assert_eq!(
+        device.calls(),
+        [
+            MockCall::with_src("apply_fn", "((lhs[id] * lhs[id]) - 1)", [3]),
+            MockCall::new("alloc", [3]),
+            MockCall::new("read", [3]),
+        ]
+    );
+}
+
+#[test]
+fn test_mock_copy_slice() {
+    let device = Mock::new();
+
+    let source = Buffer::from((&device, [1., 2., 6., 2., 4.]));
+    let mut dest = Buffer::<f32, _>::new(&device, 4);
+    device.clear_calls();
+
+    device.copy_slice_all(&source, &mut dest, [(0..2, 2..4), (3..5, 0..2)]);
+    assert_eq!(device.read(&dest), [2., 4., 1., 2.]);
+
+    assert_eq!(
+        device.calls()[..3],
+        [
+            MockCall::new("copy_slice_all", [5, 4]),
+            MockCall::new("copy_slice_to", [5, 2, 4]),
+            MockCall::new("copy_slice_to", [5, 2, 4]),
+        ]
+    );
+
+    let slice = device.copy_slice(&source, 1..3);
+    assert_eq!(device.read(&slice), [2., 6.]);
+    assert_eq!(device.last_call(), Some(MockCall::new("read", [2])));
+}
+
+#[test]
+fn test_mock_write_buf() {
+    let device = Mock::new();
+
+    let src = Buffer::from((&device, [3, 2, 1]));
+    let mut dst = Buffer::<i32, _>::new(&device, 3);
+
+    device.write_buf(&mut dst, &src);
+    assert_eq!(device.read(&dst), [3, 2, 1]);
+    assert!(device.calls().contains(&MockCall::new("write_buf", [3, 3])));
+}
@@ -1,15 +1,204 @@
-use custos::prelude::*;
-use cuwanto_server as cuw;
+use std::net::{SocketAddr, TcpListener};
+
+use custos::{
+    network::{serve, DeviceType},
+    ApplyFunction, Buffer, Combiner, CopySlice, Network, WriteBuf,
+};
+
+fn spawn_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    std::thread::spawn(move || serve(listener));
+    addr
+}
 
 #[test]
 fn test_network_device() -> custos::Result<()> {
-    let device = Network::new("127.0.0.1:11001", cuw::DeviceType::CPU)?;
-    //let device = CPU::new();
-    //let device = Stack::new().unwrap();
-    //let buf = device.cuw_client.borrow_mut().alloc_buf::<f64>(4)?;
-    let buf = Buffer::<f64, _>::from((&device, &[1., 2., 3., 4.]));
-    // ....
-    println!("buf: {:?}", buf.read());
-    //buf.clear();
+    let device = Network::new(spawn_server(), DeviceType::CPU)?;
+
+    let mut buf = Buffer::<f64, _>::from((&device, &[1., 2., 3., 4.]));
+    assert_eq!(buf.read(), [1., 2., 3., 4.]);
+
+    device.write(&mut buf, &[4., 3., 2., 1.]);
+    assert_eq!(buf.read(), [4., 3., 2., 1.]);
+
+    buf.clear();
+    assert_eq!(buf.read(), [0.; 4]);
+    Ok(())
+}
+
+#[test]
+fn test_network_copy_slice() -> custos::Result<()> {
+    let device = Network::new(spawn_server(), DeviceType::CPU)?;
+
+    let buf = Buffer::from((&device, [1i32, 2, 3, 4, 5]));
+
+    let slice = device.copy_slice(&buf, 1..4);
+    assert_eq!(slice.read(), [2, 3, 4]);
+
+    let mut dest = Buffer::from((&device, [0i32; 6]));
+    device.copy_slice_all(&buf, &mut dest, [(0..2, 4..6), (3..5, 0..2)]);
+    assert_eq!(dest.read(), [4, 5, 0, 0, 1, 2]);
+    Ok(())
+}
+
+#[test]
+fn test_network_apply_fn() -> custos::Result<()> {
+    let device = Network::new(spawn_server(), DeviceType::CPU)?;
+
+    let buf = Buffer::from((&device, [1f32, -2., 3.]));
+    let out = device.apply_fn(&buf, |x| x.mul(2.).add(x.sin()).sub(-1.));
+
+    let expected = [1f32, -2., 3.].map(|x| x * 2. + x.sin() - -1.);
+    assert_eq!(out.read(), expected);
+
+    let buf = Buffer::from((&device, [4i64, -3, 7]));
+    let out = device.apply_fn(&buf, |x| x.mul(x).geq(10));
+    assert_eq!(out.read(), [1, 0, 1]);
+    Ok(())
+}
+
+#[test]
+fn test_network_remote_errors() -> custos::Result<()> {
+    use custos::network::protocol::NetworkError;
+
+    let device = Network::new(spawn_server(), DeviceType::CPU)?;
+    let buf = Buffer::from((&device, [1u8, 2, 3]));
+
+    let mut client = device.client.borrow_mut();
+    let err = client.read_buf::<f32>(buf.ptr.id).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<NetworkError>(),
+        Some(NetworkError::Remote(_))
+    ));
+
+    assert!(client.apply_fn(buf.ptr.id, "sin(x)".into()).is_err());
+    assert!(client.write_buf(buf.ptr.id, &[1u8]).is_err());
+    assert!(client.clear_buf(u64::MAX).is_err());
+
+    // the connection is still usable
+    assert_eq!(client.read_buf::<u8>(buf.ptr.id)?, [1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn test_network_dealloc_on_drop() -> custos::Result<()> {
+    use custos::flag::AllocFlag;
+
+    let device = Network::new(spawn_server(), DeviceType::CPU)?;
+
+    let buf = Buffer::from((&device, [1f32, 2., 3.]));
+    let id = buf.ptr.id;
+    drop(buf);
+    assert!(device.client.borrow_mut().read_buf::<f32>(id).is_err());
+
+    // wrapping buffers are not freed
+    let wrapper = Buffer::<f32, _> {
+        ptr: device.try_alloc(3, AllocFlag::Wrapper)?,
+        device: Some(&device),
+        ident: None,
+    };
+    let id = wrapper.ptr.id;
+    drop(wrapper);
+    assert_eq!(device.client.borrow_mut().read_buf::<f32>(id)?, [0.; 3]);
+    Ok(())
+}
+
+#[test]
+fn test_network_timeout() -> custos::Result<()> {
+    use custos::network::{protocol::NetworkError, NetworkConfig};
+    use std::time::Duration;
+
+    // accepts connections, but never answers
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    std::thread::spawn(move || {
+        let _streams = listener.incoming().collect::<Vec<_>>();
+    });
+
+    let config = NetworkConfig {
+        timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    };
+    let err = Network::with_config(addr, DeviceType::CPU, config)
+        .err()
+        .unwrap();
+    assert_eq!(err.downcast_ref(), Some(&NetworkError::Timeout));
+    Ok(())
+}
+
+#[test]
+fn test_network_connection_failed() {
+    use custos::network::{protocol::NetworkError, NetworkConfig};
+    use std::time::Duration;
+
+    // nobody listens on this port after the listener is dropped
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let config = NetworkConfig {
+        connect_attempts: 2,
+        retry_delay: Duration::from_millis(10),
+        ..Default::default()
+    };
+    let err = Network::with_config(addr, DeviceType::CPU, config)
+        .err()
+        .unwrap();
+    assert_eq!(err.downcast_ref(), Some(&NetworkError::ConnectionFailed));
+}
+
+#[test]
+fn test_network_reconnect() -> custos::Result<()> {
+    use custos::network::protocol::NetworkError;
+
+    let device = Network::new(spawn_server(), DeviceType::CPU)?;
+    let mut old = Buffer::from((&device, [1i32, 2, 3]));
+
+    device.reconnect()?;
+
+    let err = device.try_read(&old).unwrap_err();
+    assert_eq!(err.downcast_ref(), Some(&NetworkError::StaleBuffer));
+    assert!(device.try_clear(&mut old).is_err());
+
+    let new = Buffer::from((&device, [4i32, 5, 6]));
+    drop(old);
+    assert_eq!(device.try_read(&new)?, [4, 5, 6]);
+    Ok(())
+}
+
+#[test]
+fn test_network_rejects_invalid_sizes() -> custos::Result<()> {
+    use custos::network::{protocol::NetworkError, serve_with, ServerConfig};
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let config = ServerConfig {
+        max_alloc_size: 1024,
+    };
+    std::thread::spawn(move || serve_with(listener, config));
+
+    let device = Network::new(addr, DeviceType::CPU)?;
+    let buf = Buffer::from((&device, [1u8, 2, 3]));
+
+    let mut client = device.client.borrow_mut();
+    for len in [usize::MAX, usize::MAX / 2, 257] {
+        let err = client.alloc_buf::<f32>(len).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NetworkError>(),
+            Some(NetworkError::Remote(_))
+        ));
+    }
+    assert!(client.alloc_buf::<f32>(256).is_ok());
+
+    // `dst_start + len` overflows
+    let id = buf.ptr.id;
+    assert!(client.copy_slice(id, 0, 2, id, usize::MAX).is_err());
+    assert!(client.copy_slice(id, 1, 3, id, usize::MAX - 1).is_err());
+
+    // the connection is still usable
+    assert_eq!(client.read_buf::<u8>(id)?, [1, 2, 3]);
     Ok(())
 }
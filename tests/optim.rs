@@ -0,0 +1,179 @@
+use custos::{
+    optim::{Adam, Momentum, Optimizer, Sgd, UpdateFn},
+    Buffer, Combiner, Dim1, CPU,
+};
+use custos_macro::stack_cpu_test;
+
+#[stack_cpu_test]
+#[test]
+fn test_update_fn_cpu() {
+    let device = CPU::new();
+
+    let mut buf = Buffer::<f32, _, Dim1<4>>::from((&device, vec![1., 2., 3., 4.]));
+    let lhs = Buffer::<f32, _, Dim1<4>>::from((&device, vec![2., 2., 2., 2.]));
+    let rhs = Buffer::<f32, _, Dim1<4>>::from((&device, vec![1., 0., -1., -2.]));
+
+    device.update_fn(&mut buf, (&lhs, &rhs), [], |x, lhs, rhs, []| {
+        x.mul(lhs).add(rhs)
+    });
+    assert_eq!(buf.read(), [3., 4., 5., 6.]);
+
+    device.update_fn(&mut buf, (&lhs, &rhs), [0.5, 2.], |x, _, rhs, [a, b]| {
+        x.mul(a).sub(rhs.mul(b))
+    });
+    assert_eq!(buf.read(), [-0.5, 2., 4.5, 7.]);
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_sgd_cpu() {
+    let device = CPU::new();
+
+    let mut param = Buffer::<f32, _, Dim1<3>>::from((&device, vec![1., 2., 3.]));
+    let grad = Buffer::<f32, _, Dim1<3>>::from((&device, vec![1., -1., 0.5]));
+
+    let mut sgd = Sgd::new(0.5);
+    sgd.update(&mut param, &grad);
+    assert_eq!(param.read(), [0.5, 2.5, 2.75]);
+}
+
+#[test]
+fn test_momentum_velocity() {
+    let device = CPU::new();
+
+    let mut param = Buffer::<f32>::from((&device, [1., 2.]));
+    let grad = Buffer::<f32>::from((&device, [1., -2.]));
+
+    let mut momentum = Momentum::new(0.5, 0.5);
+    momentum.update(&mut param, &grad);
+    assert_eq!(momentum.velocity(&param).read(), [1., -2.]);
+    assert_eq!(param.read(), [0.5, 3.]);
+
+    momentum.update(&mut param, &grad);
+    assert_eq!(momentum.velocity(&param).read(), [1.5, -3.]);
+    assert_eq!(param.read(), [-0.25, 4.5]);
+}
+
+#[test]
+fn test_momentum_state_with_retrieve_in_range() {
+    use custos::{range, Device};
+
+    let device = CPU::new();
+
+    let mut param = Buffer::<f32>::from((&device, [1., 2.]));
+    let grad = Buffer::<f32>::from((&device, [1., -2.]));
+
+    let mut momentum = Momentum::new(0.5, 0.5);
+
+    for _ in range(2) {
+        momentum.update(&mut param, &grad);
+
+        // must not return the velocity, which is created in the first iteration
+        let mut out = device.retrieve::<f32, ()>(param.len(), ());
+        out.write(&[7., 7.]);
+    }
+
+    assert_eq!(momentum.velocity(&param).read(), [1.5, -3.]);
+    assert_eq!(param.read(), [-0.25, 4.5]);
+}
+
+#[test]
+fn test_adam_first_update() {
+    let device = CPU::new();
+
+    let mut param = Buffer::<f64>::from((&device, [1., 2.]));
+    let grad = Buffer::<f64>::from((&device, [4., -0.5]));
+
+    let mut adam = Adam::new(0.1);
+    adam.update(&mut param, &grad);
+
+    // the bias corrected first update moves each parameter by about `lr` against the sign of its gradient
+    let values = param.read();
+    assert!((values[0] - 0.9).abs() < 1e-6);
+    assert!((values[1] - 2.1).abs() < 1e-6);
+    assert_eq!(*adam.updates(&param), 1);
+}
+
+/// Fits `y = weight * x + bias` to samples of `y = 2x + 1` by minimizing the mean squared error.
+fn linear_regression(mut optimizer: impl Optimizer<f32, CPU>, steps: usize) -> (f32, f32) {
+    let device = CPU::new();
+
+    let xs = [-2., -1., 0., 0.5, 1., 2., 3.];
+    let ys = xs.map(|x: f32| 2. * x + 1.);
+
+    let mut weight = Buffer::<f32>::from((&device, [0.]));
+    let mut bias = Buffer::<f32>::from((&device, [0.]));
+    let mut weight_grad = Buffer::<f32>::new(&device, 1);
+    let mut bias_grad = Buffer::<f32>::new(&device, 1);
+
+    for _ in 0..steps {
+        let (w, b) = (weight.read()[0], bias.read()[0]);
+
+        let (mut dw, mut db) = (0., 0.);
+        for (x, y) in xs.iter().zip(ys) {
+            let err = w * x + b - y;
+            dw += 2. * err * x / xs.len() as f32;
+            db += 2. * err / xs.len() as f32;
+        }
+        weight_grad.write(&[dw]);
+        bias_grad.write(&[db]);
+
+        optimizer.update(&mut weight, &weight_grad);
+        optimizer.update(&mut bias, &bias_grad);
+    }
+
+    (weight.read()[0], bias.read()[0])
+}
+
+#[test]
+fn test_linear_regression_sgd() {
+    let (weight, bias) = linear_regression(Sgd::new(0.05), 500);
+    assert!((weight - 2.).abs() < 1e-3, "weight: {weight}");
+    assert!((bias - 1.).abs() < 1e-3, "bias: {bias}");
+}
+
+#[test]
+fn test_linear_regression_momentum() {
+    let (weight, bias) = linear_regression(Momentum::new(0.02, 0.9), 300);
+    assert!((weight - 2.).abs() < 1e-3, "weight: {weight}");
+    assert!((bias - 1.).abs() < 1e-3, "bias: {bias}");
+}
+
+#[test]
+fn test_linear_regression_adam() {
+    let (weight, bias) = linear_regression(Adam::new(0.05), 1000);
+    assert!((weight - 2.).abs() < 1e-2, "weight: {weight}");
+    assert!((bias - 1.).abs() < 1e-2, "bias: {bias}");
+}
+
+#[cfg(feature = "autograd")]
+#[test]
+fn test_adam_step_tape() {
+    use custos::{TapeReturn, UnaryElementWiseMayGrad};
+
+    let device = CPU::new();
+
+    let mut param = Buffer::<f32>::from((&device, [1., -2., 3.]));
+    let mut adam = Adam::new(0.1);
+
+    for _ in 0..300 {
+        let out = device.unary_ew(&param, |x| x.mul(x), |x| x.mul(2.));
+        out.backward();
+
+        adam.step(&mut param);
+        device.tape_mut().grads.zero_grad();
+    }
+
+    assert!(param.read().iter().all(|x| x.abs() < 1e-2), "{param:?}");
+    assert_eq!(*adam.updates(&param), 300);
+}
+
+#[cfg(feature = "autograd")]
+#[test]
+fn test_step_without_grad() {
+    let device = CPU::new();
+
+    let mut param = Buffer::<f32>::from((&device, [1., 2.]));
+    Sgd::new(0.1).step(&mut param);
+    assert_eq!(param.read(), [1., 2.]);
+}
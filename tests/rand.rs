@@ -0,0 +1,275 @@
+use custos::{
+    random::{philox4x32, philox_bits, Distribution},
+    Buffer, RandBuf, WithShape, CPU,
+};
+
+use custos_macro::stack_cpu_test;
+
+/// The Philox4x32-10 outputs for the counters `[idx, 0, 0, 0]` and the key of the seed 42.
+/// Generated by an independent implementation that reproduces the Random123 known-answer tests.
+const SEED_42_BITS: [[u32; 4]; 8] = [
+    [0x9ceaf053, 0x77f5493b, 0x12bf50ad, 0x5742b3d7],
+    [0xfcdb2127, 0x53ba6cfd, 0x838f5a6e, 0x744e06fb],
+    [0xd36c0225, 0xa8875dcb, 0x9a4d6d99, 0xc609a559],
+    [0xbac70475, 0xabaf0dab, 0x961e5543, 0x610e67f7],
+    [0x539023bc, 0xd6cbaeb5, 0x529f4963, 0x3c58227f],
+    [0x5099d809, 0x4b20b5d2, 0x0d41b5e2, 0x0a653407],
+    [0x39b9314c, 0xbe3e422a, 0x7aabd4bb, 0x2eb8a992],
+    [0x5bbd83b1, 0x68784c85, 0x172067aa, 0x2e6b75b7],
+];
+
+/// The upper 24 bits as float in [0, 1).
+fn unit(bits: u32) -> f64 {
+    (bits >> 8) as f64 / (1 << 24) as f64
+}
+
+fn expected_uniform(lo: f32, hi: f32) -> Vec<f32> {
+    SEED_42_BITS
+        .iter()
+        .map(|bits| lo + (hi - lo) * unit(bits[0]) as f32)
+        .collect()
+}
+
+fn expected_normal(mean: f64, std: f64) -> Vec<f64> {
+    SEED_42_BITS
+        .iter()
+        .map(|bits| {
+            let u1 = ((bits[0] >> 8) + 1) as f64 / (1 << 24) as f64;
+            let u2 = unit(bits[1]);
+            mean + std * (-2. * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+        })
+        .collect()
+}
+
+fn expected_bernoulli(p: f64) -> Vec<f32> {
+    SEED_42_BITS
+        .iter()
+        .map(|bits| if unit(bits[0]) < p { 1. } else { 0. })
+        .collect()
+}
+
+fn assert_close(actual: impl AsRef<[f32]>, expected: &[f64]) {
+    let actual = actual.as_ref();
+    assert_eq!(actual.len(), expected.len());
+    for (x, y) in actual.iter().zip(expected) {
+        assert!((*x as f64 - y).abs() < 1e-5, "{x} != {y}");
+    }
+}
+
+// known answer tests of the Random123 reference implementation
+#[test]
+fn test_philox_known_answers() {
+    assert_eq!(
+        philox4x32([0; 4], [0; 2]),
+        [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]
+    );
+    assert_eq!(
+        philox4x32([u32::MAX; 4], [u32::MAX; 2]),
+        [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd]
+    );
+    assert_eq!(
+        philox4x32(
+            [0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344],
+            [0xa4093822, 0x299f31d0]
+        ),
+        [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]
+    );
+}
+
+#[test]
+fn test_philox_bits_counter_and_key() {
+    for (idx, bits) in SEED_42_BITS.iter().enumerate() {
+        assert_eq!(&philox_bits(42, idx), bits);
+    }
+
+    // the seed is split into the two key words and the index into the first two counter words
+    let seed = 0x0123_4567_89ab_cdef;
+    assert_eq!(
+        philox_bits(seed, 1),
+        [0xadca1466, 0x523e0d85, 0x65401425, 0xb299da3f]
+    );
+    assert_eq!(
+        philox_bits(seed, (1 << 32) + 5),
+        [0xb8b243f7, 0xda8fb9a8, 0x46004299, 0xe54c389c]
+    );
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_rand_cpu() {
+    let device = CPU::new();
+
+    let mut buf = Buffer::with(&device, [0f32; 8]);
+
+    device.rand_uniform(&mut buf, 42, 0., 1.);
+    assert_eq!(expected_uniform(0., 1.), buf.read());
+
+    device.rand_uniform(&mut buf, 42, -1., 1.);
+    assert_eq!(expected_uniform(-1., 1.), buf.read());
+
+    device.rand_normal(&mut buf, 42, 0., 1.);
+    assert_close(buf.read(), &expected_normal(0., 1.));
+
+    device.rand_bernoulli(&mut buf, 42, 0.5);
+    assert_eq!(expected_bernoulli(0.5), buf.read());
+}
+
+#[test]
+fn test_rand_reproducible_cpu() {
+    let device = CPU::new();
+
+    let dist = Distribution::Normal {
+        mean: 2f64,
+        std: 0.5,
+    };
+
+    let mut lhs = Buffer::<_>::new(&device, 100);
+    let mut rhs = Buffer::<_>::new(&device, 100);
+    device.rand(&mut lhs, 3, dist);
+    device.rand(&mut rhs, 3, dist);
+    assert_eq!(lhs.read(), rhs.read());
+
+    device.rand(&mut rhs, 4, dist);
+    assert_ne!(lhs.read(), rhs.read());
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_philox_c_source_cl() -> custos::Result<()> {
+    use custos::{opencl::enqueue_kernel, random::philox_c_source, OpenCL};
+
+    let device = OpenCL::new(0)?;
+
+    let src = format!(
+        "
+        {philox}
+        __kernel void bits(__global uint* out, uint k0, uint k1) {{
+            size_t id = get_global_id(0);
+            uint bits[4] = {{(uint) id, 0, 0, 0}};
+            philox4x32(bits, k0, k1);
+            for (int i = 0; i < 4; i++) {{
+                out[id * 4 + i] = bits[i];
+            }}
+        }}
+    ",
+        philox = philox_c_source("uint", "ulong", "inline")
+    );
+
+    let out = Buffer::<u32, _>::new(&device, 32);
+    enqueue_kernel(&device, &src, [8, 0, 0], None, &[&out, &42u32, &0u32])?;
+    assert_eq!(out.read(), SEED_42_BITS.concat());
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_rand_cl() -> custos::Result<()> {
+    use custos::OpenCL;
+
+    let device = OpenCL::new(0)?;
+    let mut buf = Buffer::<f32, _>::new(&device, 8);
+
+    device.rand_uniform(&mut buf, 42, -1., 1.);
+    assert_eq!(buf.read(), expected_uniform(-1., 1.));
+
+    device.rand_bernoulli(&mut buf, 42, 0.5);
+    assert_eq!(buf.read(), expected_bernoulli(0.5));
+
+    device.rand_normal(&mut buf, 42, 0., 1.);
+    assert_close(buf.read(), &expected_normal(0., 1.));
+    Ok(())
+}
+
+#[cfg(feature = "cuda")]
+#[test]
+fn test_philox_c_source_cuda() -> custos::Result<()> {
+    use custos::{cuda::launch_kernel1d, random::philox_c_source, Read, CUDA};
+
+    let device = CUDA::new(0)?;
+
+    let src = format!(
+        r#"{philox}
+        extern "C" __global__ void bits(unsigned int* out, unsigned int k0, unsigned int k1, int numElements)
+            {{
+                int idx = blockDim.x * blockIdx.x + threadIdx.x;
+                if (idx < numElements) {{
+                    unsigned int bits[4] = {{(unsigned int) idx, 0, 0, 0}};
+                    philox4x32(bits, k0, k1);
+                    for (int i = 0; i < 4; i++) {{
+                        out[idx * 4 + i] = bits[i];
+                    }}
+                }}
+            }}
+    "#,
+        philox = philox_c_source("unsigned int", "unsigned long long", "__device__")
+    );
+
+    let out = Buffer::<u32, _>::new(&device, 32);
+    launch_kernel1d(8, &device, &src, "bits", &[&out, &42u32, &0u32, &8i32])?;
+    assert_eq!(device.read(&out), SEED_42_BITS.concat());
+    Ok(())
+}
+
+#[cfg(feature = "cuda")]
+#[test]
+fn test_rand_cuda() -> custos::Result<()> {
+    use custos::{Read, CUDA};
+
+    let device = CUDA::new(0)?;
+    let mut buf = Buffer::<f32, _>::new(&device, 8);
+
+    device.rand_bernoulli(&mut buf, 42, 0.5);
+    assert_eq!(device.read(&buf), expected_bernoulli(0.5));
+
+    device.rand_uniform(&mut buf, 42, -1., 1.);
+    let expected = expected_uniform(-1., 1.)
+        .into_iter()
+        .map(f64::from)
+        .collect::<Vec<_>>();
+    assert_close(device.read(&buf), &expected);
+    Ok(())
+}
+
+#[cfg(feature = "wgpu")]
+#[test]
+fn test_philox_wgsl_source() -> custos::Result<()> {
+    use custos::{random::philox_wgsl_source, wgpu::launch_shader, WGPU};
+
+    let device = WGPU::new(wgpu::Backends::all())?;
+
+    let src = format!(
+        "{philox}
+        @group(0)
+        @binding(0)
+        var<storage, read_write> out: array<u32>;
+
+        @compute
+        @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+            let bits = philox4x32(vec4<u32>(global_id.x, 0u, 0u, 0u), 42u, 0u);
+            for (var i = 0u; i < 4u; i++) {{
+                out[global_id.x * 4u + i] = bits[i];
+            }}
+        }}
+        ",
+        philox = philox_wgsl_source()
+    );
+
+    let out = Buffer::<u32, _>::new(&device, 32);
+    launch_shader(&device, &src, [8, 1, 1], &[&out]);
+    assert_eq!(out.read(), SEED_42_BITS.concat());
+    Ok(())
+}
+
+#[cfg(feature = "wgpu")]
+#[test]
+fn test_rand_wgpu() -> custos::Result<()> {
+    use custos::WGPU;
+
+    let device = WGPU::new(wgpu::Backends::all())?;
+    let mut buf = Buffer::<f32, _>::new(&device, 8);
+
+    device.rand_bernoulli(&mut buf, 42, 0.5);
+    assert_eq!(buf.read(), expected_bernoulli(0.5));
+    Ok(())
+}
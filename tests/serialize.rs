@@ -0,0 +1,367 @@
+use custos::{
+    serialize::{
+        BufferData, Npz, NpzWriter, ParamRegistry, SafeTensors, SafeTensorsWriter, SerializeError,
+    },
+    Buffer, Dim1, Dim2, Dim3, CPU,
+};
+use custos_macro::stack_cpu_test;
+
+#[stack_cpu_test]
+#[test]
+fn test_save_load_dim2_cpu() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let buf = Buffer::<f32, _, Dim2<2, 3>>::from((&device, vec![1., -2., 3.5, 4., 5., 6.]));
+
+    let mut bytes = Vec::new();
+    buf.save(&mut bytes)?;
+
+    let loaded = Buffer::<f32, _, Dim2<2, 3>>::load(&device, &bytes[..])?;
+    assert_eq!(loaded.as_slice(), &[1., -2., 3.5, 4., 5., 6.]);
+    Ok(())
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_save_load_dim3_cpu() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let data = (0..24).collect::<Vec<i64>>();
+    let buf = Buffer::<i64, _, Dim3<2, 3, 4>>::from((&device, &data));
+
+    let mut bytes = Vec::new();
+    buf.save(&mut bytes)?;
+
+    let loaded = Buffer::<i64, _, Dim3<2, 3, 4>>::load(&device, &bytes[..])?;
+    assert_eq!(loaded.as_slice(), &data[..]);
+
+    let saved = BufferData::<i64>::read_from(&bytes[..])?;
+    assert_eq!(saved.dtype, "long");
+    assert_eq!(saved.dims, [2, 3, 4]);
+    Ok(())
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_load_shape_mismatch_cpu() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let buf = Buffer::<u8, _, Dim2<2, 3>>::from((&device, vec![1, 2, 3, 4, 5, 6]));
+    let mut bytes = Vec::new();
+    buf.save(&mut bytes)?;
+
+    let err = Buffer::<u8, _, Dim2<3, 2>>::load(&device, &bytes[..])
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.downcast_ref(),
+        Some(&SerializeError::ShapeMismatch {
+            expected: vec![3, 2],
+            found: vec![2, 3]
+        })
+    );
+
+    let err = Buffer::<i8, _, Dim2<2, 3>>::load(&device, &bytes[..])
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err.downcast_ref(),
+        Some(SerializeError::DtypeMismatch { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_save_load_without_shape() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let buf = Buffer::<f64, _, Dim2<2, 2>>::from((&device, vec![1., 2., 3., 4.]));
+    let mut bytes = Vec::new();
+    buf.save(&mut bytes)?;
+
+    // a buffer without a compile-time shape accepts any dimensions
+    let loaded = Buffer::<f64>::load(&device, &bytes[..])?;
+    assert_eq!(loaded.read(), [1., 2., 3., 4.]);
+
+    // a stack buffer is loaded into a cpu buffer
+    let stack = Buffer::<f64, _, Dim2<2, 2>>::from((&custos::Stack, vec![4., 3., 2., 1.]));
+    let mut bytes = Vec::new();
+    stack.save(&mut bytes)?;
+
+    let loaded = Buffer::<f64, _, Dim2<2, 2>>::load(&device, &bytes[..])?;
+    assert_eq!(loaded.as_slice(), &[4., 3., 2., 1.]);
+    Ok(())
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_npy_dim2_cpu() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let buf = Buffer::<f64, _, Dim2<3, 2>>::from((&device, vec![1., 2., 3., 4., 5., 6.]));
+    let mut bytes = Vec::new();
+    buf.to_npy(&mut bytes)?;
+
+    let loaded = Buffer::<f64, _, Dim2<3, 2>>::from_npy(&device, &bytes[..])?;
+    assert_eq!(loaded.as_slice(), &[1., 2., 3., 4., 5., 6.]);
+
+    let err = Buffer::<f64, _, Dim1<6>>::from_npy(&device, &bytes[..])
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.downcast_ref(),
+        Some(&SerializeError::ShapeMismatch {
+            expected: vec![6],
+            found: vec![3, 2]
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_npy_dynamic_shape() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let buf = Buffer::<u16, _, Dim3<2, 1, 3>>::from((&device, vec![1, 2, 3, 4, 5, 6]));
+    let mut bytes = Vec::new();
+    buf.to_npy(&mut bytes)?;
+
+    let data = BufferData::<u16>::read_npy(&bytes[..])?;
+    assert_eq!(data.dims, [2, 1, 3]);
+
+    let loaded: Buffer<u16> = data.to_buffer(&device)?;
+    assert_eq!(loaded.read(), [1, 2, 3, 4, 5, 6]);
+    Ok(())
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_npz_cpu() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let weights = Buffer::<f32, _, Dim2<2, 3>>::from((&device, vec![1., 2., 3., 4., 5., 6.]));
+    let bias = Buffer::<f32, _, Dim1<3>>::from((&device, vec![-1., 0., 1.]));
+    let steps = Buffer::<i64, _, Dim1<1>>::from((&device, vec![42]));
+
+    let mut npz = NpzWriter::new(Vec::new());
+    npz.add("weights", &weights)?;
+    npz.add("bias", &bias)?;
+    npz.add("steps", &steps)?;
+    let bytes = npz.finish()?;
+
+    let npz = Npz::read(&bytes[..])?;
+    assert_eq!(
+        npz.names().collect::<Vec<_>>(),
+        ["weights", "bias", "steps"]
+    );
+
+    let loaded: Buffer<f32, _, Dim2<2, 3>> = npz.load(&device, "weights")?;
+    assert_eq!(loaded.as_slice(), weights.as_slice());
+
+    let loaded: Buffer<f32, _, Dim1<3>> = npz.load(&device, "bias")?;
+    assert_eq!(loaded.as_slice(), &[-1., 0., 1.]);
+
+    let loaded: Buffer<i64, _, Dim1<1>> = npz.load(&device, "steps")?;
+    assert_eq!(loaded.as_slice(), &[42]);
+
+    let err = npz
+        .load::<f32, _, Dim1<3>>(&device, "missing")
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.downcast_ref(),
+        Some(&SerializeError::MissingEntry("missing".into()))
+    );
+    Ok(())
+}
+
+#[stack_cpu_test]
+#[test]
+fn test_safetensors_dim3_cpu() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let data = (0..24).map(|x| x as f32 / 2.).collect::<Vec<_>>();
+    let embedding = Buffer::<f32, _, Dim3<2, 3, 4>>::from((&device, &data));
+    let mask = Buffer::<u8, _, Dim1<3>>::from((&device, vec![1, 0, 1]));
+
+    let mut tensors = SafeTensorsWriter::new(Vec::new());
+    tensors.add_metadata("format", "pt");
+    tensors.add("embedding", &embedding);
+    tensors.add("mask", &mask);
+    let bytes = tensors.finish()?;
+
+    let tensors = SafeTensors::read(&bytes[..])?;
+    assert_eq!(tensors.metadata()["format"], "pt");
+
+    let loaded: Buffer<f32, _, Dim3<2, 3, 4>> = tensors.load(&device, "embedding")?;
+    assert_eq!(loaded.as_slice(), &data[..]);
+
+    let loaded: Buffer<u8, _, Dim1<3>> = tensors.load(&device, "mask")?;
+    assert_eq!(loaded.as_slice(), &[1, 0, 1]);
+
+    let err = tensors
+        .load::<f32, _, Dim2<6, 4>>(&device, "embedding")
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.downcast_ref(),
+        Some(&SerializeError::ShapeMismatch {
+            expected: vec![6, 4],
+            found: vec![2, 3, 4]
+        })
+    );
+
+    assert_eq!(tensors.data::<f32>("embedding")?.dims, [2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn test_param_registry_resume() -> custos::Result<()> {
+    let mut checkpoint = Vec::new();
+
+    {
+        let device = CPU::new();
+        let mut weights = Buffer::<f32, _, Dim2<2, 2>>::from((&device, vec![0.5, -1., 2., 3.]));
+        let mut bias = Buffer::<f32>::from((&device, [0.1, 0.2]));
+        let mut momentum = Buffer::<f32, _, Dim2<2, 2>>::from((&device, vec![1., 1., 0., 0.]));
+        let mut step = Buffer::<u64>::from((&device, [7]));
+        let bias_id = bias.id();
+
+        let mut params = ParamRegistry::new();
+        params.register("weights", &mut weights);
+        params.register("bias", &mut bias);
+        params.register_state("sgd.momentum.weights", &mut momentum);
+        params.register_state("sgd.step", &mut step);
+        assert_eq!(params.ident("bias"), Some(bias_id));
+
+        params.save(&device, &mut checkpoint)?;
+
+        let tensors = SafeTensors::read(&checkpoint[..])?;
+        assert_eq!(
+            tensors.names().collect::<Vec<_>>(),
+            [
+                "param/weights",
+                "param/bias",
+                "state/sgd.momentum.weights",
+                "state/sgd.step"
+            ]
+        );
+    }
+
+    // a new run with newly allocated buffers
+    let device = CPU::new();
+    let _unrelated = Buffer::<f32>::new(&device, 3);
+    let mut weights = Buffer::<f32, _, Dim2<2, 2>>::new(&device, 4);
+    let mut bias = Buffer::<f32>::new(&device, 2);
+    let mut momentum = Buffer::<f32, _, Dim2<2, 2>>::new(&device, 4);
+    let mut step = Buffer::<u64>::new(&device, 1);
+
+    let mut params = ParamRegistry::new();
+    params.register("weights", &mut weights);
+    params.register("bias", &mut bias);
+    params.register_state("sgd.momentum.weights", &mut momentum);
+    params.register_state("sgd.step", &mut step);
+    params.restore(&device, &checkpoint[..])?;
+
+    assert_eq!(weights.read(), [0.5, -1., 2., 3.]);
+    assert_eq!(bias.read(), [0.1, 0.2]);
+    assert_eq!(momentum.read(), [1., 1., 0., 0.]);
+    assert_eq!(step.read(), [7]);
+    Ok(())
+}
+
+#[test]
+fn test_param_registry_restore_validates() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let mut weights = Buffer::<f32>::from((&device, [1., 2., 3.]));
+    let mut params = ParamRegistry::new();
+    params.register("weights", &mut weights);
+
+    let mut checkpoint = Vec::new();
+    params.save(&device, &mut checkpoint)?;
+
+    let mut a = Buffer::<f32>::from((&device, [0.; 3]));
+    let mut b = Buffer::<f32>::from((&device, [0.; 4]));
+
+    let mut params = ParamRegistry::new();
+    params.register("weights", &mut a);
+    params.register("bias", &mut b);
+
+    let err = params.restore(&device, &checkpoint[..]).unwrap_err();
+    assert_eq!(
+        err.downcast_ref(),
+        Some(&SerializeError::MissingEntry("param/bias".into()))
+    );
+    // nothing was restored
+    assert_eq!(a.read(), [0.; 3]);
+
+    let mut params = ParamRegistry::new();
+    params.register("weights", &mut a);
+    // replaces the previously registered buffer
+    params.register("weights", &mut b);
+    let err = params.restore(&device, &checkpoint[..]).unwrap_err();
+    assert_eq!(
+        err.downcast_ref(),
+        Some(&SerializeError::ShapeMismatch {
+            expected: vec![4],
+            found: vec![3]
+        })
+    );
+    Ok(())
+}
+
+#[cfg(feature = "autograd")]
+#[test]
+fn test_param_registry_grads() -> custos::Result<()> {
+    use custos::{Combiner, UnaryElementWiseMayGrad};
+
+    let mut checkpoint = Vec::new();
+    {
+        let device = CPU::new();
+        let mut weights = Buffer::<f32>::from((&device, [1., -2., 3.]));
+        let mut unused = Buffer::<f32>::from((&device, [4.]));
+
+        let out = device.unary_ew(&weights, |x| x.mul(x), |x| x.mul(2.));
+        out.backward();
+
+        let mut params = ParamRegistry::new();
+        params.register("weights", &mut weights);
+        params.register("unused", &mut unused);
+        params.save(&device, &mut checkpoint)?;
+    }
+
+    let tensors = SafeTensors::read(&checkpoint[..])?;
+    assert_eq!(
+        tensors.names().collect::<Vec<_>>(),
+        ["param/weights", "grad/weights", "param/unused"]
+    );
+
+    let device = CPU::new();
+    let mut weights = Buffer::<f32>::new(&device, 3);
+    let mut unused = Buffer::<f32>::new(&device, 1);
+
+    let mut params = ParamRegistry::new();
+    params.register("weights", &mut weights);
+    params.register("unused", &mut unused);
+    params.restore(&device, &checkpoint[..])?;
+
+    assert_eq!(weights.read(), [1., -2., 3.]);
+    assert_eq!(weights.grad().read(), [2., -4., 6.]);
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_buffer() -> custos::Result<()> {
+    let device = CPU::new();
+
+    let buf = Buffer::<i32, _, Dim2<2, 2>>::from((&device, vec![1, 2, 3, 4]));
+    let json = serde_json::to_string(&buf)?;
+    assert_eq!(json, r#"{"dtype":"int","dims":[2,2],"data":[1,2,3,4]}"#);
+
+    let data: BufferData<i32> = serde_json::from_str(&json)?;
+    let loaded: Buffer<i32, CPU, Dim2<2, 2>> = data.to_buffer(&device)?;
+    assert_eq!(loaded.as_slice(), &[1, 2, 3, 4]);
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use custos::{Buffer, Dim1, Dim2, Dim3, Stack};
+
+#[test]
+fn test_stack_matmul() {
+    let lhs = Buffer::<_, _, Dim2<2, 3>>::from((&Stack, [1., 2., 3., 4., 5., 6.]));
+    let rhs = Buffer::<_, _, Dim2<3, 4>>::from((
+        &Stack,
+        [1., 0., 2., 1., 0., 1., 1., 1., 1., 1., 0., 1.],
+    ));
+
+    let out = Stack.matmul(&lhs, &rhs);
+    assert_eq!(out.read(), [[4., 5., 4., 6.], [10., 11., 13., 15.]]);
+}
+
+#[test]
+fn test_stack_transpose() {
+    let buf = Buffer::<_, _, Dim2<3, 2>>::from((&Stack, [1, 2, 3, 4, 5, 6]));
+
+    let out = Stack.transpose(&buf);
+    assert_eq!(out.read(), [[1, 3, 5], [2, 4, 6]]);
+
+    let back = Stack.transpose(&out);
+    assert_eq!(back.read(), buf.read());
+}
+
+#[test]
+fn test_stack_reshape() {
+    let buf = Buffer::<_, _, Dim3<2, 2, 2>>::from((&Stack, &[1, 2, 3, 4, 5, 6, 7, 8][..]));
+
+    let flat = Stack.reshape::<_, _, _, Dim1<8>>(&buf);
+    assert_eq!(flat.read(), [1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let matrix = Stack.reshape::<_, _, _, Dim2<4, 2>>(&flat);
+    assert_eq!(matrix.read(), [[1, 2], [3, 4], [5, 6], [7, 8]]);
+}
+
+#[test]
+fn test_stack_reductions() {
+    let buf = Buffer::<_, _, Dim1<5>>::from((&Stack, [3., -1., 7., 2., 4.]));
+
+    assert_eq!(Stack.sum(&buf), 15.);
+    assert_eq!(Stack.mean(&buf), 3.);
+    assert_eq!(Stack.max(&buf), 7.);
+    assert_eq!(Stack.min(&buf), -1.);
+}
+
+#[test]
+fn test_stack_sum_rows_cols() {
+    let buf = Buffer::<_, _, Dim2<3, 2>>::from((&Stack, [1, 2, 3, 4, 5, 6]));
+
+    assert_eq!(Stack.sum_rows(&buf).read(), [9, 12]);
+    assert_eq!(Stack.sum_cols(&buf).read(), [3, 7, 11]);
+}
+
+#[test]
+fn test_stack_linear_layer() {
+    let inputs = Buffer::<_, _, Dim2<2, 3>>::from((&Stack, [1., 2., 3., -1., 0., 1.]));
+    let weights = Buffer::<_, _, Dim2<3, 2>>::from((&Stack, [0.5, 1., 0., -1., 1., 0.5]));
+
+    let out = Stack.matmul(&inputs, &weights);
+    assert_eq!(out.read(), [[3.5, 0.5], [0.5, -0.5]]);
+
+    let col_sums = Stack.sum_rows(&out);
+    assert_eq!(col_sums.read(), [4., 0.]);
+    assert_eq!(Stack.sum(&out), 4.);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_stack_ops_cpu_inputs() {
+    use custos::WithShape;
+
+    let device = custos::CPU::new();
+
+    let lhs = Buffer::with(&device, [[1, 2], [3, 4]]);
+    let rhs = Buffer::with(&device, [[1, 0], [0, 1]]);
+
+    let out = Stack.matmul(&lhs, &rhs);
+    assert_eq!(out.read(), [[1, 2], [3, 4]]);
+    assert_eq!(Stack.transpose(&lhs).read(), [[1, 3], [2, 4]]);
+}
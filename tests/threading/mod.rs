@@ -1 +1,2 @@
+mod parallel_cpu;
 mod threads;
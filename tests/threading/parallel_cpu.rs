@@ -0,0 +1,62 @@
+#[cfg(feature = "cpu")]
+use custos::{cpu::Parallelism, ApplyFunction, Buffer, Combiner, UnaryGrad, CPU};
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_parallel_apply_fn() {
+    let data = (0..10_001).map(|x| x as f32 / 100.).collect::<Vec<_>>();
+
+    let serial = CPU::new();
+    let buf = Buffer::<_, _>::from((&serial, &data));
+    let expected = serial.apply_fn(&buf, |x| x.sin().mul(x).add(1.));
+
+    for threads in [2, 3, 8] {
+        let device = CPU::with_parallelism(Parallelism::new(threads, 97).with_min_len(0));
+        let buf = Buffer::<_, _>::from((&device, &data));
+
+        let out = device.apply_fn(&buf, |x| x.sin().mul(x).add(1.));
+        assert_eq!(out.as_slice(), expected.as_slice());
+    }
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_parallel_add_unary_grad_and_clear() {
+    let device = CPU::with_parallelism(Parallelism::new(4, 16).with_min_len(0));
+
+    let lhs = Buffer::<_, _>::from((&device, (0..1000).map(|x| x as f64).collect::<Vec<_>>()));
+    let out = Buffer::from((&device, vec![2.; 1000]));
+    let mut lhs_grad = Buffer::from((&device, vec![1.; 1000]));
+
+    device.add_unary_grad(&lhs, &mut lhs_grad, &out, |x| x.mul(3.));
+
+    for (idx, grad) in lhs_grad.iter().enumerate() {
+        assert_eq!(*grad, 1. + 2. * 3. * idx as f64);
+    }
+
+    lhs_grad.clear();
+    assert_eq!(lhs_grad.as_slice(), &[0.; 1000]);
+}
+
+#[cfg(feature = "cpu")]
+#[cfg(not(feature = "realloc"))]
+#[test]
+fn test_parallel_cpu_uses_cache() {
+    use custos::range;
+
+    let device = CPU::with_parallelism(Parallelism::new(4, 8).with_min_len(0));
+    let buf = Buffer::from((&device, [1i32; 100]));
+
+    let mut old_ptr: *mut i32 = std::ptr::null_mut();
+
+    for _ in range(10) {
+        let mut out = device.apply_fn(&buf, |x| x.add(1));
+        assert_eq!(out.as_slice(), &[2; 100]);
+
+        if !old_ptr.is_null() {
+            assert_eq!(out.host_ptr_mut(), old_ptr);
+        }
+        old_ptr = out.host_ptr_mut();
+        assert_eq!(device.addons.cache.borrow().nodes.len(), 2);
+    }
+}
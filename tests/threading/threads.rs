@@ -1,11 +1,10 @@
-use custos::{Buffer, CPU};
-
-#[cfg(feature = "cpu")]
+#[cfg(all(feature = "cpu", feature = "thread-safe", not(feature = "autograd")))]
 #[test]
 fn test_with_threads() {
+    use custos::{Buffer, CPU};
+
     let device = CPU::new();
 
-    //let buf = Buffer::from((&device, [1, 2, 3, 4]));
     let buf = Buffer::<f32>::deviceless(&device, 10);
 
     let vec: Vec<f64> = vec![1., 2., 3.];
@@ -20,3 +19,57 @@ fn test_with_threads() {
     });
     a.join().unwrap();
 }
+
+#[cfg(all(feature = "cpu", feature = "thread-safe", not(feature = "autograd")))]
+#[test]
+fn test_share_device_across_threads() {
+    use custos::{ApplyFunction, Buffer, Combiner, Device, CPU};
+
+    let device = CPU::new();
+    let buf = Buffer::from((&device, [1f32, 2., 3., 4.]));
+
+    std::thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|thread| {
+                let (device, buf) = (&device, &buf);
+                scope.spawn(move || {
+                    let mut sums = Vec::new();
+                    for _ in 0..100 {
+                        // every thread retrieves cached buffers from the shared cache
+                        let mut out = device.retrieve::<f32, ()>(buf.len(), buf);
+                        out.copy_from_slice(&device.apply_fn(buf, |x| x.mul(thread as f32)));
+                        sums.push(out.iter().sum::<f32>());
+                        custos::bump_count();
+                    }
+                    sums
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (thread, handle) in handles.into_iter().enumerate() {
+            let sums = handle.join().unwrap();
+            assert!(sums.iter().all(|sum| *sum == 10. * thread as f32));
+        }
+    });
+
+    // moving buffers to another thread
+    let buf = std::thread::scope(|scope| {
+        scope
+            .spawn(|| Buffer::from((&device, [5, 6, 7])))
+            .join()
+            .unwrap()
+    });
+    assert_eq!(buf.read(), [5, 6, 7]);
+}
+
+#[cfg(all(feature = "cpu", feature = "thread-safe", not(feature = "autograd")))]
+#[test]
+fn test_threads_use_disjoint_idents() {
+    use custos::{get_ident_idx, Ident};
+
+    let main = Ident::new(0);
+    let other = std::thread::spawn(|| Ident::new(0)).join().unwrap();
+
+    assert_eq!(main.idx, get_ident_idx());
+    assert_ne!(main.idx, other.idx);
+}
@@ -0,0 +1,167 @@
+use std::{
+    cell::Cell,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use custos::{
+    cpu::CPUPtr, flag::AllocFlag, Alloc, Buffer, Device, Dim1, ReadAsync, Shape, Transfer,
+    WriteAsync, CPU,
+};
+use custos_macro::stack_cpu_test;
+
+mod common;
+
+use common::{host_ptr_with_slice, host_slice, host_slice_mut};
+
+#[stack_cpu_test]
+#[test]
+fn test_read_write_async_cpu() {
+    let device = CPU::new();
+
+    let mut buf = Buffer::<i32, _, Dim1<4>>::new(&device, 4);
+
+    let mut write = device.write_async(&mut buf, &[4, 3, 2, 1]);
+    assert!(write.is_complete());
+    write.wait().unwrap();
+
+    let mut read = device.read_async(&buf);
+    assert!(read.is_complete());
+    assert_eq!(read.wait().unwrap(), [4, 3, 2, 1]);
+}
+
+/// A host memory device whose transfers complete after a fixed number of non-blocking polls.
+struct Delayed {
+    polls: usize,
+    pending: Cell<usize>,
+}
+
+impl Delayed {
+    fn new(polls: usize) -> Delayed {
+        Delayed {
+            polls,
+            pending: Cell::new(0),
+        }
+    }
+
+    fn transfer<'a, R: 'a>(&'a self, mut complete: impl FnMut() -> R + 'a) -> Transfer<'a, R> {
+        self.pending.set(self.pending.get() + 1);
+        let mut polls = 0;
+
+        Transfer::new(move |block| {
+            polls += 1;
+            if !block && polls <= self.polls {
+                return None;
+            }
+            self.pending.set(self.pending.get() - 1);
+            Some(Ok(complete()))
+        })
+    }
+}
+
+impl Device for Delayed {
+    type Ptr<U, S: Shape> = CPUPtr<U>;
+    type Cache = ();
+
+    fn new() -> custos::Result<Self> {
+        Ok(Delayed::new(2))
+    }
+}
+
+impl<T, S: Shape> Alloc<'_, T, S> for Delayed {
+    fn alloc(&self, len: usize, flag: AllocFlag) -> CPUPtr<T> {
+        CPUPtr::new_initialized(len, flag)
+    }
+
+    fn with_slice(&self, data: &[T]) -> CPUPtr<T>
+    where
+        T: Clone,
+    {
+        host_ptr_with_slice(data)
+    }
+}
+
+impl<T: Clone> ReadAsync<T> for Delayed {
+    fn read_async<'a>(&'a self, buf: &'a Buffer<T, Delayed>) -> Transfer<'a, Vec<T>> {
+        self.transfer(move || host_slice(&buf.ptr).to_vec())
+    }
+}
+
+impl<T: Clone> WriteAsync<T> for Delayed {
+    fn write_async<'a>(
+        &'a self,
+        buf: &'a mut Buffer<T, Delayed>,
+        data: &'a [T],
+    ) -> Transfer<'a, ()> {
+        self.transfer(move || host_slice_mut(&mut buf.ptr).clone_from_slice(data))
+    }
+}
+
+#[test]
+fn test_transfer_pending_until_complete() {
+    let device = Delayed::new(2);
+    let mut buf = Buffer::<f32, _>::from((&device, [1., 2., 3.]));
+
+    let mut write = device.write_async(&mut buf, &[3., 2., 1.]);
+    assert!(!write.is_complete());
+    assert!(!write.is_complete());
+    assert!(write.is_complete());
+    write.wait().unwrap();
+
+    let read = device.read_async(&buf);
+    assert_eq!(device.pending.get(), 1);
+    assert_eq!(read.wait().unwrap(), [3., 2., 1.]);
+    assert_eq!(device.pending.get(), 0);
+}
+
+#[test]
+fn test_dropped_transfer_completes() {
+    let device = Delayed::new(10);
+    let mut buf = Buffer::<i32, _>::from((&device, [1, 2, 3]));
+
+    drop(device.write_async(&mut buf, &[7, 8, 9]));
+    assert_eq!(device.pending.get(), 0);
+
+    assert_eq!(device.read_async(&buf).wait().unwrap(), [7, 8, 9]);
+}
+
+struct CountWakes(AtomicUsize);
+
+impl Wake for CountWakes {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Polls the future until it is ready. Returns the output and the number of wake-ups.
+fn block_on<F: Future + Unpin>(mut future: F) -> (F::Output, usize) {
+    let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = std::pin::Pin::new(&mut future).poll(&mut cx) {
+            return (output, wakes.0.load(Ordering::Relaxed));
+        }
+    }
+}
+
+#[test]
+fn test_await_transfer() {
+    let device = Delayed::new(3);
+    let buf = Buffer::<u8, _>::from((&device, [4, 5, 6]));
+
+    let (read, wakes) = block_on(device.read_async(&buf));
+    assert_eq!(read.unwrap(), [4, 5, 6]);
+    assert_eq!(wakes, 3);
+
+    let cpu = CPU::new();
+    let buf = Buffer::<u8, _>::from((&cpu, [1, 2]));
+    let (read, wakes) = block_on(cpu.read_async(&buf));
+    assert_eq!(read.unwrap(), [1, 2]);
+    assert_eq!(wakes, 0);
+}